@@ -5,8 +5,9 @@ use core::hash::{Hash, Hasher};
 use core::iter::{FromIterator, FusedIterator, Peekable};
 use core::marker::PhantomData;
 use core::ops::Bound::{Excluded, Included, Unbounded};
-use core::ops::{Index, RangeBounds};
-use core::{fmt, mem, ptr};
+use core::ops::{Bound, Index, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::{fmt, mem};
 
 use super::node::{self, marker, ForceResult::*, Handle, InsertResult::*, NodeRef};
 use super::search::{self, SearchResult::*};
@@ -521,6 +522,73 @@ impl<K: Debug + Ord, V: Debug> Debug for OccupiedEntry<'_, K, V> {
     }
 }
 
+/// A cursor over a `BTreeMap`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// Cursors always point to an element in the tree, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the last and
+/// first elements of the tree.
+///
+/// A `Cursor` is created with the [`lower_bound`] and [`upper_bound`] methods of [`BTreeMap`].
+///
+/// [`lower_bound`]: struct.BTreeMap.html#method.lower_bound
+/// [`upper_bound`]: struct.BTreeMap.html#method.upper_bound
+/// [`BTreeMap`]: struct.BTreeMap.html
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct Cursor<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: &'a node::Root<K, V>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        Cursor { current: self.current, root: self.root }
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K: Debug, V: Debug> Debug for Cursor<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor").field(&self.key_value()).finish()
+    }
+}
+
+/// A cursor over a `BTreeMap` with editing operations.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth, and can
+/// safely mutate the tree during iteration. This is because the lifetime of its yielded
+/// references is tied to its own lifetime, instead of just the underlying tree. This means
+/// cursors cannot yield multiple elements at once.
+///
+/// Cursors always point to an element in the tree, and index in a logically circular way.
+/// To accommodate this, there is a "ghost" non-element that yields `None` between the last and
+/// first elements of the tree.
+///
+/// A `CursorMut` is created with the [`lower_bound_mut`] and [`upper_bound_mut`] methods of
+/// [`BTreeMap`].
+///
+/// [`lower_bound_mut`]: struct.BTreeMap.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.BTreeMap.html#method.upper_bound_mut
+/// [`BTreeMap`]: struct.BTreeMap.html
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct CursorMut<'a, K: 'a, V: 'a> {
+    current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>>,
+    root: NonNull<node::Root<K, V>>,
+    length: &'a mut usize,
+
+    // Be invariant in `K` and `V`
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K: Debug, V: Debug> Debug for CursorMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut").field(&self.key_value()).finish()
+    }
+}
+
 // An iterator for merging two sorted sequences into one
 struct MergeIter<K, V, I: Iterator<Item = (K, V)>> {
     left: Peekable<I>,
@@ -1043,6 +1111,164 @@ impl<K: Ord, V> BTreeMap<K, V> {
         RangeMut { front: f, back: b, _marker: PhantomData }
     }
 
+    /// Returns a [`Cursor`] pointing at the first element that is above the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first
+    /// element of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let current = lower_bound_kv(self.root.as_ref(), bound);
+        Cursor { current, root: &self.root }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the first element that is above the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first
+    /// element of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.lower_bound_mut(Bound::Excluded(&2));
+    /// assert_eq!(cursor.key(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let root = NonNull::from(&mut self.root);
+        let current = lower_bound_kv(unsafe { (*root.as_ptr()).as_mut() }, bound);
+        CursorMut { current, root, length: &mut self.length, _marker: PhantomData }
+    }
+
+    /// Returns a [`Cursor`] pointing at the last element that is below the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last
+    /// element of the map.
+    ///
+    /// [`Cursor`]: struct.Cursor.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let current = upper_bound_kv(self.root.as_ref(), bound);
+        Cursor { current, root: &self.root }
+    }
+
+    /// Returns a [`CursorMut`] pointing at the last element that is below the
+    /// given bound.
+    ///
+    /// If no such element exists then a cursor pointing at the "ghost"
+    /// non-element is returned.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last
+    /// element of the map.
+    ///
+    /// [`CursorMut`]: struct.CursorMut.html
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    ///
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut a = BTreeMap::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(4, "c");
+    /// let cursor = a.upper_bound_mut(Bound::Excluded(&3));
+    /// assert_eq!(cursor.key(), Some(&2));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let root = NonNull::from(&mut self.root);
+        let current = upper_bound_kv(unsafe { (*root.as_ptr()).as_mut() }, bound);
+        CursorMut { current, root, length: &mut self.length, _marker: PhantomData }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
@@ -1943,6 +2169,52 @@ where
     }
 }
 
+/// Finds the first KV in the tree that is above the given bound, if any.
+fn lower_bound_kv<BorrowType, K, V, Q: ?Sized>(
+    root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>,
+) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+where
+    Q: Ord,
+    K: Borrow<Q>,
+{
+    let edge = match bound {
+        Included(key) => match search::search_tree(root, key) {
+            Found(kv) => return Some(kv),
+            GoDown(edge) => edge,
+        },
+        Excluded(key) => match search::search_tree(root, key) {
+            Found(kv) => kv.next_leaf_edge(),
+            GoDown(edge) => edge,
+        },
+        Unbounded => root.first_leaf_edge(),
+    };
+    edge.next_kv().ok()
+}
+
+/// Finds the last KV in the tree that is below the given bound, if any.
+fn upper_bound_kv<BorrowType, K, V, Q: ?Sized>(
+    root: NodeRef<BorrowType, K, V, marker::LeafOrInternal>,
+    bound: Bound<&Q>,
+) -> Option<Handle<NodeRef<BorrowType, K, V, marker::LeafOrInternal>, marker::KV>>
+where
+    Q: Ord,
+    K: Borrow<Q>,
+{
+    let edge = match bound {
+        Included(key) => match search::search_tree(root, key) {
+            Found(kv) => return Some(kv),
+            GoDown(edge) => edge,
+        },
+        Excluded(key) => match search::search_tree(root, key) {
+            Found(kv) => kv.next_back_leaf_edge(),
+            GoDown(edge) => edge,
+        },
+        Unbounded => root.last_leaf_edge(),
+    };
+    edge.next_back_kv().ok()
+}
+
 impl<K, V> BTreeMap<K, V> {
     /// Gets an iterator over the entries of the map, sorted by key.
     ///
@@ -2295,41 +2567,7 @@ impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn insert(self, value: V) -> &'a mut V {
         *self.length += 1;
-
-        let out_ptr;
-
-        let mut ins_k;
-        let mut ins_v;
-        let mut ins_edge;
-
-        let mut cur_parent = match self.handle.insert(self.key, value) {
-            (Fit(handle), _) => return handle.into_kv_mut().1,
-            (Split(left, k, v, right), ptr) => {
-                ins_k = k;
-                ins_v = v;
-                ins_edge = right;
-                out_ptr = ptr;
-                left.ascend().map_err(|n| n.into_root_mut())
-            }
-        };
-
-        loop {
-            match cur_parent {
-                Ok(parent) => match parent.insert(ins_k, ins_v, ins_edge) {
-                    Fit(_) => return unsafe { &mut *out_ptr },
-                    Split(left, k, v, right) => {
-                        ins_k = k;
-                        ins_v = v;
-                        ins_edge = right;
-                        cur_parent = left.ascend().map_err(|n| n.into_root_mut());
-                    }
-                },
-                Err(root) => {
-                    root.push_level().push(ins_k, ins_v, ins_edge);
-                    return unsafe { &mut *out_ptr };
-                }
-            }
-        }
+        insert_recursing(self.handle, self.key, value).into_kv_mut().1
     }
 }
 
@@ -2498,55 +2736,136 @@ impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
     fn remove_kv(self) -> (K, V) {
         *self.length -= 1;
 
-        let (small_leaf, old_key, old_val) = match self.handle.force() {
-            Leaf(leaf) => {
-                let (hole, old_key, old_val) = leaf.remove();
-                (hole.into_node(), old_key, old_val)
-            }
-            Internal(mut internal) => {
-                let key_loc = internal.kv_mut().0 as *mut K;
-                let val_loc = internal.kv_mut().1 as *mut V;
+        let (old_key, old_val, _) = remove_kv_tracking(self.handle);
+        (old_key, old_val)
+    }
+}
 
-                let to_remove = internal.right_edge().descend().first_leaf_edge().right_kv().ok();
-                let to_remove = unsafe { unwrap_unchecked(to_remove) };
+/// Inserts a key/value pair at the given leaf edge, splitting nodes on the way up as needed,
+/// and returns a handle to the inserted pair.
+fn insert_recursing<'a, K, V>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>,
+    key: K,
+    value: V,
+) -> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV> {
+    let out_kv;
+
+    let mut ins_k;
+    let mut ins_v;
+    let mut ins_edge;
+
+    let mut cur_parent = match handle.insert(key, value) {
+        (Fit(_), kv) => return kv,
+        (Split(left, k, v, right), kv) => {
+            ins_k = k;
+            ins_v = v;
+            ins_edge = right;
+            out_kv = kv;
+            left.ascend().map_err(|n| n.into_root_mut())
+        }
+    };
 
-                let (hole, key, val) = to_remove.remove();
+    loop {
+        match cur_parent {
+            Ok(parent) => match parent.insert(ins_k, ins_v, ins_edge) {
+                Fit(_) => return out_kv,
+                Split(left, k, v, right) => {
+                    ins_k = k;
+                    ins_v = v;
+                    ins_edge = right;
+                    cur_parent = left.ascend().map_err(|n| n.into_root_mut());
+                }
+            },
+            Err(root) => {
+                root.push_level().push(ins_k, ins_v, ins_edge);
+                return out_kv;
+            }
+        }
+    }
+}
 
-                let old_key = unsafe { mem::replace(&mut *key_loc, key) };
-                let old_val = unsafe { mem::replace(&mut *val_loc, val) };
+/// Removes the key/value pair pointed to by the given handle and rebalances the tree.
+/// Returns the pair, along with the leaf edge between the now adjacent pairs to its
+/// left and right.
+fn remove_kv_tracking<'a, K, V>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
+) -> (K, V, Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    match handle.force() {
+        Leaf(leaf) => remove_leaf_kv_tracking(leaf),
+        Internal(internal) => {
+            // Remove the preceding pair from its leaf, and put it in place of the pair we were
+            // asked to remove. Rebalancing the leaf may move the latter around, so look it up
+            // again from the leaf edge we get back.
+            let to_remove = internal.left_edge().descend().last_leaf_edge().left_kv().ok();
+            let to_remove = unsafe { unwrap_unchecked(to_remove) };
+
+            let (key, val, hole) = remove_leaf_kv_tracking(to_remove);
+
+            let mut internal = unsafe { unwrap_unchecked(hole.next_kv().ok()) };
+            let old_key = mem::replace(internal.kv_mut().0, key);
+            let old_val = mem::replace(internal.kv_mut().1, val);
+
+            (old_key, old_val, internal.next_leaf_edge())
+        }
+    }
+}
 
-                (hole.into_node(), old_key, old_val)
+/// Removes the key/value pair pointed to by the given leaf handle and rebalances the tree.
+/// Returns the pair, along with the leaf edge between the now adjacent pairs to its
+/// left and right.
+fn remove_leaf_kv_tracking<'a, K, V>(
+    handle: Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV>,
+) -> (K, V, Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>) {
+    let (mut pos, old_key, old_val) = handle.remove();
+
+    // Handle underflow. Only fixing up the leaf itself moves the pairs around `pos`,
+    // so we only need to keep track of where it ends up in the first iteration.
+    let mut cur_node = unsafe { ptr::read(&pos) }.into_node().forget_type();
+    while cur_node.len() < node::MIN_LEN {
+        let at_leaf = cur_node.height() == 0;
+        match handle_underfull_node(cur_node) {
+            AtRoot => break,
+            EmptyParent(_) => unreachable!(),
+            Merged(merged_edge, is_left, left_len) => {
+                if at_leaf {
+                    let idx = if is_left { left_len + 1 + pos.idx() } else { pos.idx() };
+                    let merged = unsafe { ptr::read(&merged_edge) }.descend();
+                    let merged = match merged.force() {
+                        Leaf(leaf) => leaf,
+                        Internal(_) => unreachable!(),
+                    };
+                    pos = unsafe { Handle::new_edge(merged, idx) };
+                }
+                let parent = merged_edge.into_node();
+                if parent.len() == 0 {
+                    // We must be at the root
+                    parent.into_root_mut().pop_level();
+                    break;
+                } else {
+                    cur_node = parent.forget_type();
+                }
             }
-        };
-
-        // Handle underflow
-        let mut cur_node = small_leaf.forget_type();
-        while cur_node.len() < node::MIN_LEN {
-            match handle_underfull_node(cur_node) {
-                AtRoot => break,
-                EmptyParent(_) => unreachable!(),
-                Merged(parent) => {
-                    if parent.len() == 0 {
-                        // We must be at the root
-                        parent.into_root_mut().pop_level();
-                        break;
-                    } else {
-                        cur_node = parent.forget_type();
-                    }
+            Stole(is_left) => {
+                if at_leaf && is_left {
+                    let idx = pos.idx() + 1;
+                    pos = unsafe { Handle::new_edge(pos.into_node(), idx) };
                 }
-                Stole(_) => break,
+                break;
             }
         }
-
-        (old_key, old_val)
     }
+
+    (old_key, old_val, pos)
 }
 
 enum UnderflowResult<'a, K, V> {
     AtRoot,
     EmptyParent(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
-    Merged(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
-    Stole(NodeRef<marker::Mut<'a>, K, V, marker::Internal>),
+    /// The node was merged with a sibling. Holds the edge to the merged node, whether the
+    /// sibling was on the left, and the length of the left one of the two nodes.
+    Merged(Handle<NodeRef<marker::Mut<'a>, K, V, marker::Internal>, marker::Edge>, bool, usize),
+    /// The node stole a key/value pair from a sibling. Holds whether it was the left one.
+    Stole(bool),
 }
 
 fn handle_underfull_node<K, V>(
@@ -2569,14 +2888,15 @@ fn handle_underfull_node<K, V>(
     };
 
     if handle.can_merge() {
-        Merged(handle.merge().into_node())
+        let left_len = handle.reborrow().left_edge().descend().len();
+        Merged(handle.merge(), is_left, left_len)
     } else {
         if is_left {
             handle.steal_left();
         } else {
             handle.steal_right();
         }
-        Stole(handle.into_node())
+        Stole(is_left)
     }
 }
 
@@ -2603,3 +2923,347 @@ impl<K: Ord, V, I: Iterator<Item = (K, V)>> Iterator for MergeIter<K, V, I> {
         }
     }
 }
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn move_next(&mut self) {
+        let edge = match self.current.take() {
+            None => self.root.as_ref().first_leaf_edge(),
+            Some(current) => current.next_leaf_edge(),
+        };
+        self.current = edge.next_kv().ok();
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn move_prev(&mut self) {
+        let edge = match self.current.take() {
+            None => self.root.as_ref().last_leaf_edge(),
+            Some(current) => current.next_back_leaf_edge(),
+        };
+        self.current = edge.next_back_kv().ok();
+    }
+
+    /// Returns a reference to the key of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn key(&self) -> Option<&'a K> {
+        self.current.map(|current| current.into_kv().0)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn value(&self) -> Option<&'a V> {
+        self.current.map(|current| current.into_kv().1)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn key_value(&self) -> Option<(&'a K, &'a V)> {
+        self.current.map(Handle::into_kv)
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        let mut next = self.clone();
+        next.move_next();
+        next.current.map(Handle::into_kv)
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        let mut prev = self.clone();
+        prev.move_prev();
+        prev.current.map(Handle::into_kv)
+    }
+}
+
+impl<'a, K, V> CursorMut<'a, K, V> {
+    /// Returns the root node of the tree, without regard for the current position.
+    fn root_node(&mut self) -> NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal> {
+        unsafe { (*self.root.as_ptr()).as_mut() }
+    }
+
+    /// Moves the cursor to the next element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn move_next(&mut self) {
+        let edge = match self.current.take() {
+            None => self.root_node().first_leaf_edge(),
+            Some(current) => current.next_leaf_edge(),
+        };
+        self.current = edge.next_kv().ok();
+    }
+
+    /// Moves the cursor to the previous element of the `BTreeMap`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn move_prev(&mut self) {
+        let edge = match self.current.take() {
+            None => self.root_node().last_leaf_edge(),
+            Some(current) => current.next_back_leaf_edge(),
+        };
+        self.current = edge.next_back_kv().ok();
+    }
+
+    /// Returns a reference to the key of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn key(&self) -> Option<&K> {
+        self.current.as_ref().map(|current| current.reborrow().into_kv().0)
+    }
+
+    /// Returns a reference to the value of the element that the cursor is
+    /// currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn value(&self) -> Option<&V> {
+        self.current.as_ref().map(|current| current.reborrow().into_kv().1)
+    }
+
+    /// Returns a reference to the key and value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn key_value(&self) -> Option<(&K, &V)> {
+        self.current.as_ref().map(|current| current.reborrow().into_kv())
+    }
+
+    /// Returns a mutable reference to the value of the element that the cursor
+    /// is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn value_mut(&mut self) -> Option<&mut V> {
+        self.current.as_mut().map(|current| current.kv_mut().1)
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of
+    /// the element that the cursor is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn key_value_mut(&mut self) -> Option<(&K, &mut V)> {
+        self.current.as_mut().map(|current| {
+            let (k, v) = current.kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of
+    /// the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `BTreeMap`. If it is pointing to the last
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&mut self) -> Option<(&K, &mut V)> {
+        let edge = match self.current.as_mut() {
+            None => unsafe { (*self.root.as_ptr()).as_mut() }.first_leaf_edge(),
+            Some(current) => unsafe { current.reborrow_mut() }.next_leaf_edge(),
+        };
+        edge.next_kv().ok().map(|kv| {
+            let (k, v) = kv.into_kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a reference to the key and a mutable reference to the value of
+    /// the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `BTreeMap`. If it is pointing to the first
+    /// element of the `BTreeMap` then this returns `None`.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&mut self) -> Option<(&K, &mut V)> {
+        let edge = match self.current.as_mut() {
+            None => unsafe { (*self.root.as_ptr()).as_mut() }.last_leaf_edge(),
+            Some(current) => unsafe { current.reborrow_mut() }.next_back_leaf_edge(),
+        };
+        edge.next_back_kv().ok().map(|kv| {
+            let (k, v) = kv.into_kv_mut();
+            (&*k, v)
+        })
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn as_cursor(&self) -> Cursor<'_, K, V> {
+        Cursor {
+            current: self.current.as_ref().map(|current| current.reborrow()),
+            root: unsafe { self.root.as_ref() },
+        }
+    }
+}
+
+// Now the tree editing operations
+impl<'a, K: Ord, V> CursorMut<'a, K, V> {
+    /// Inserts a new element into the `BTreeMap` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `BTreeMap`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    /// - the given key compares less than or equal to the current element (unless
+    ///   the cursor is pointing at the "ghost" non-element).
+    /// - the given key compares greater than or equal to the next element (if
+    ///   any).
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_after(&mut self, key: K, value: V) {
+        if let Some(current) = self.key() {
+            if &key <= current {
+                panic!("key must be ordered above the current element");
+            }
+        }
+        if let Some((next, _)) = self.peek_next() {
+            if &key >= next {
+                panic!("key must be ordered below the next element");
+            }
+        }
+
+        let edge = match self.current.take() {
+            None => {
+                // The map may still be using the shared empty root.
+                let root = unsafe { &mut *self.root.as_ptr() };
+                if root.is_shared_root() {
+                    *root = node::Root::new_leaf();
+                }
+                root.as_mut().first_leaf_edge()
+            }
+            Some(current) => current.next_leaf_edge(),
+        };
+        *self.length += 1;
+        let new_kv = insert_recursing(edge, key, value);
+
+        // Inserting may have split the node the cursor pointed into, so find the
+        // current element again, right before the new one.
+        self.current = new_kv.left_edge().next_back_kv().ok();
+    }
+
+    /// Inserts a new element into the `BTreeMap` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `BTreeMap`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    /// - the given key compares greater than or equal to the current element
+    ///   (unless the cursor is pointing at the "ghost" non-element).
+    /// - the given key compares less than or equal to the previous element (if
+    ///   any).
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_before(&mut self, key: K, value: V) {
+        if let Some(current) = self.key() {
+            if &key >= current {
+                panic!("key must be ordered below the current element");
+            }
+        }
+        if let Some((prev, _)) = self.peek_prev() {
+            if &key <= prev {
+                panic!("key must be ordered above the previous element");
+            }
+        }
+
+        let edge = match self.current.take() {
+            None => {
+                // The map may still be using the shared empty root.
+                let root = unsafe { &mut *self.root.as_ptr() };
+                if root.is_shared_root() {
+                    *root = node::Root::new_leaf();
+                }
+                root.as_mut().last_leaf_edge()
+            }
+            Some(current) => current.next_back_leaf_edge(),
+        };
+        *self.length += 1;
+        let new_kv = insert_recursing(edge, key, value);
+
+        // Inserting may have split the node the cursor pointed into, so find the
+        // current element again, right after the new one.
+        self.current = new_kv.right_edge().next_kv().ok();
+    }
+
+    /// Removes the current element from the `BTreeMap`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `BTreeMap`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned. The cursor is not moved in this case.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let current = self.current.take()?;
+        *self.length -= 1;
+        let (old_key, old_val, pos) = remove_kv_tracking(current);
+        self.current = pos.next_kv().ok();
+        Some((old_key, old_val))
+    }
+
+    /// Removes the current element from the `BTreeMap`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the previous element in the `BTreeMap`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned. The cursor is not moved in this case.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_current_and_move_back(&mut self) -> Option<(K, V)> {
+        let current = self.current.take()?;
+        *self.length -= 1;
+        let (old_key, old_val, pos) = remove_kv_tracking(current);
+        self.current = pos.next_back_kv().ok();
+        Some((old_key, old_val))
+    }
+}
@@ -804,6 +804,11 @@ impl<Node, Type> Handle<Node, Type> {
     pub fn into_node(self) -> Node {
         self.node
     }
+
+    /// Returns the position of this handle in the node.
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
 }
 
 impl<BorrowType, K, V, NodeType> Handle<NodeRef<BorrowType, K, V, NodeType>, marker::KV> {
@@ -919,27 +924,42 @@ impl<'a, K, V> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge
     /// Inserts a new key/value pair between the key/value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room.
     ///
-    /// The returned pointer points to the inserted value.
-    pub fn insert(mut self, key: K, val: V) -> (InsertResult<'a, K, V, marker::Leaf>, *mut V) {
+    /// The returned handle points to the inserted pair. It stays valid while the split is
+    /// propagated to the ancestors, as leaf nodes are never moved by that.
+    pub fn insert(
+        mut self,
+        key: K,
+        val: V,
+    ) -> (
+        InsertResult<'a, K, V, marker::Leaf>,
+        Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::KV>,
+    ) {
         if self.node.len() < CAPACITY {
-            let ptr = self.insert_fit(key, val);
+            self.insert_fit(key, val);
             let kv = unsafe { Handle::new_kv(self.node, self.idx) };
-            (InsertResult::Fit(kv), ptr)
+            (InsertResult::Fit(unsafe { ptr::read(&kv) }), kv)
         } else {
             let middle = unsafe { Handle::new_kv(self.node, B) };
-            let (mut left, k, v, mut right) = middle.split();
-            let ptr = if self.idx <= B {
-                unsafe { Handle::new_edge(left.reborrow_mut(), self.idx).insert_fit(key, val) }
+            let (left, k, v, right) = middle.split();
+            let (node, idx) = if self.idx <= B {
+                (unsafe { ptr::read(&left) }, self.idx)
             } else {
-                unsafe {
-                    Handle::new_edge(
-                        right.as_mut().cast_unchecked::<marker::Leaf>(),
-                        self.idx - (B + 1),
-                    )
-                    .insert_fit(key, val)
-                }
+                // The new node is about to be attached to the same tree as `left`, so
+                // refer to that tree's root rather than to `right` itself.
+                let node = NodeRef {
+                    height: 0,
+                    node: right.node.as_ptr(),
+                    root: left.root,
+                    _marker: PhantomData,
+                };
+                (node, self.idx - (B + 1))
+            };
+            let kv = unsafe {
+                let mut edge = Handle::new_edge(node, idx);
+                edge.insert_fit(key, val);
+                Handle::new_kv(edge.node, idx)
             };
-            (InsertResult::Split(left, k, v, right), ptr)
+            (InsertResult::Split(left, k, v, right), kv)
         }
     }
 }
@@ -16,3 +16,18 @@ fn unitialized_zero_size_box() {
         NonNull::<MaybeUninit<String>>::dangling().as_ptr(),
     );
 }
+
+#[test]
+fn box_with_allocator() {
+    use std::alloc::System;
+
+    let b = Box::new_in(String::from("hello"), System);
+    assert_eq!(*b, "hello");
+
+    let (ptr, alloc) = Box::into_raw_with_alloc(b);
+    let b = unsafe { Box::from_raw_in(ptr, alloc) };
+    assert_eq!(b.len(), 5);
+
+    let b: Box<[u8], System> = Box::new_in([1, 2, 3], System);
+    assert_eq!(&*b, [1, 2, 3]);
+}
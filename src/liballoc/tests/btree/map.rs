@@ -1069,3 +1069,141 @@ fn test_into_iter_drop_leak_2() {
         assert_eq!(DROPS.load(Ordering::SeqCst), size);
     }
 }
+
+#[test]
+fn test_cursor() {
+    let map: BTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+    let mut cur = map.lower_bound(Included(&3));
+    assert_eq!(cur.key_value(), Some((&3, &30)));
+    assert_eq!(cur.peek_prev(), Some((&2, &20)));
+    assert_eq!(cur.peek_next(), Some((&4, &40)));
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&2));
+    cur.move_next();
+    cur.move_next();
+    assert_eq!(cur.value(), Some(&40));
+
+    let mut cur = map.lower_bound(Excluded(&9));
+    assert_eq!(cur.key(), None);
+    assert_eq!(cur.peek_next(), Some((&0, &0)));
+    assert_eq!(cur.peek_prev(), Some((&9, &90)));
+    cur.move_next();
+    assert_eq!(cur.key(), Some(&0));
+    cur.move_prev();
+    assert_eq!(cur.key(), None);
+    cur.move_prev();
+    assert_eq!(cur.key(), Some(&9));
+
+    assert_eq!(map.lower_bound(Unbounded).key(), Some(&0));
+    assert_eq!(map.upper_bound(Unbounded).key(), Some(&9));
+    assert_eq!(map.upper_bound(Included(&5)).key(), Some(&5));
+    assert_eq!(map.upper_bound(Excluded(&5)).key(), Some(&4));
+    assert_eq!(map.upper_bound(Excluded(&0)).key(), None);
+
+    let empty = BTreeMap::<i32, i32>::new();
+    let mut cur = empty.lower_bound(Unbounded);
+    assert_eq!(cur.key(), None);
+    cur.move_next();
+    assert_eq!(cur.key(), None);
+}
+
+#[test]
+fn test_cursor_mut() {
+    let mut map: BTreeMap<_, _> = (0..10).map(|i| (i, i * 10)).collect();
+
+    let mut cur = map.lower_bound_mut(Included(&3));
+    *cur.value_mut().unwrap() += 1;
+    *cur.peek_next().unwrap().1 += 2;
+    *cur.peek_prev().unwrap().1 += 3;
+    assert_eq!(cur.key_value_mut(), Some((&3, &mut 31)));
+    assert_eq!(cur.as_cursor().peek_next(), Some((&4, &42)));
+    assert_eq!(cur.as_cursor().peek_prev(), Some((&2, &23)));
+
+    let mut cur = map.lower_bound_mut(Unbounded);
+    cur.insert_before(-1, 0);
+    assert_eq!(cur.key(), Some(&0));
+    let mut cur = map.upper_bound_mut(Unbounded);
+    cur.insert_after(10, 100);
+    assert_eq!(cur.key(), Some(&9));
+    assert_eq!(map.keys().next(), Some(&-1));
+    assert_eq!(map.keys().next_back(), Some(&10));
+    assert_eq!(map.len(), 12);
+}
+
+#[test]
+fn test_cursor_mut_insert() {
+    #[cfg(not(miri))] // Miri is too slow
+    let size = 1000;
+    #[cfg(miri)]
+    let size = 144; // to obtain height 3 tree (having edges to both kinds of nodes)
+
+    let mut map = BTreeMap::new();
+    let mut cur = map.lower_bound_mut(Unbounded);
+    cur.insert_after(0, 0);
+    assert_eq!(cur.key(), None);
+    cur.move_next();
+    for i in 1..size {
+        cur.insert_after(i * 2, i * 2);
+        assert_eq!(cur.key(), Some(&(i * 2 - 2)));
+        cur.move_next();
+        assert_eq!(cur.key(), Some(&(i * 2)));
+    }
+
+    let mut cur = map.lower_bound_mut(Excluded(&0));
+    while let Some(&k) = cur.key() {
+        cur.insert_before(k - 1, k - 1);
+        assert_eq!(cur.key(), Some(&k));
+        assert_eq!(cur.peek_prev(), Some((&(k - 1), &mut (k - 1))));
+        cur.move_next();
+    }
+
+    assert_eq!(map.len(), size as usize * 2 - 1);
+    assert!(map.into_iter().eq((0..size * 2 - 1).map(|i| (i, i))));
+}
+
+#[test]
+fn test_cursor_mut_remove() {
+    #[cfg(not(miri))] // Miri is too slow
+    let size = 1000;
+    #[cfg(miri)]
+    let size = 144; // to obtain height 3 tree (having edges to both kinds of nodes)
+
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+
+    let mut cur = map.lower_bound_mut(Unbounded);
+    while let Some(&k) = cur.key() {
+        if k % 2 == 1 {
+            assert_eq!(cur.remove_current(), Some((k, k)));
+            assert_eq!(cur.key(), if k + 1 < size { Some(&(k + 1)) } else { None });
+        } else {
+            cur.move_next();
+        }
+    }
+    assert_eq!(map.len(), size as usize / 2);
+    assert!(map.keys().cloned().eq((0..size).step_by(2)));
+
+    let mut cur = map.upper_bound_mut(Unbounded);
+    let mut expected: Vec<_> = (0..size).step_by(2).collect();
+    while let Some((k, _)) = cur.remove_current_and_move_back() {
+        assert_eq!(Some(k), expected.pop());
+    }
+    assert!(expected.is_empty());
+    assert!(map.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_cursor_mut_insert_after_unordered() {
+    let mut map: BTreeMap<_, _> = (0..5).map(|i| (i, i)).collect();
+    let mut cur = map.lower_bound_mut(Included(&2));
+    cur.insert_after(3, 3);
+}
+
+#[test]
+#[should_panic]
+fn test_cursor_mut_insert_before_unordered() {
+    let mut map: BTreeMap<_, _> = (0..5).map(|i| (i, i)).collect();
+    let mut cur = map.lower_bound_mut(Included(&2));
+    cur.insert_before(2, 2);
+}
@@ -1,5 +1,6 @@
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
+use std::ops::Bound::{Excluded, Included};
 
 use super::DeterministicRng;
 
@@ -554,3 +555,29 @@ fn test_split_off_large_random_sorted() {
     assert!(set.into_iter().eq(data.clone().into_iter().filter(|x| *x < key)));
     assert!(right.into_iter().eq(data.into_iter().filter(|x| *x >= key)));
 }
+
+#[test]
+fn test_cursor() {
+    let mut set: BTreeSet<_> = (0..10).collect();
+
+    let mut cur = set.lower_bound(Included(&3));
+    assert_eq!(cur.current(), Some(&3));
+    assert_eq!(cur.peek_prev(), Some(&2));
+    assert_eq!(cur.peek_next(), Some(&4));
+    cur.move_prev();
+    cur.move_prev();
+    cur.move_prev();
+    cur.move_prev();
+    assert_eq!(cur.current(), None);
+    assert_eq!(set.upper_bound(Excluded(&3)).current(), Some(&2));
+
+    let mut cur = set.lower_bound_mut(Excluded(&3));
+    assert_eq!(cur.remove_current(), Some(4));
+    assert_eq!(cur.current(), Some(&5));
+    cur.insert_before(4);
+    assert_eq!(cur.remove_current_and_move_back(), Some(5));
+    assert_eq!(cur.current(), Some(&4));
+    cur.insert_after(5);
+    assert_eq!(cur.peek_next(), Some(&5));
+    assert!(set.into_iter().eq(0..10));
+}
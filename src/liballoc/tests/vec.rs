@@ -1411,3 +1411,42 @@ fn vec_macro_repeating_null_raw_fat_pointer() {
         vtable: *mut (),
     }
 }
+
+#[test]
+fn test_vec_with_allocator() {
+    use std::alloc::System;
+
+    let mut v: Vec<String, _> = Vec::new_in(System);
+    v.extend((0..10).map(|i| i.to_string()));
+    v.insert(0, String::from("x"));
+    assert_eq!(v.len(), 11);
+    assert_eq!(v[0], "x");
+
+    let tail = v.split_off(6);
+    assert_eq!(tail, ["5", "6", "7", "8", "9"]);
+
+    let cloned = v.clone();
+    assert_eq!(cloned, v);
+
+    let drained: Vec<String> = v.drain(1..3).collect();
+    assert_eq!(drained, ["0", "1"]);
+    assert_eq!(v, ["x", "2", "3", "4"]);
+
+    let mut iter = tail.into_iter();
+    assert_eq!(iter.next().as_deref(), Some("5"));
+    assert_eq!(iter.next_back().as_deref(), Some("9"));
+    assert_eq!(iter.clone().count(), 3);
+}
+
+#[test]
+fn test_vec_raw_parts_with_allocator() {
+    use std::alloc::System;
+
+    let mut v = Vec::with_capacity_in(4, System);
+    v.push(1u32);
+    v.push(2);
+    let (ptr, len, cap, alloc) = v.into_raw_parts_with_alloc();
+    let v = unsafe { Vec::from_raw_parts_in(ptr, len, cap, alloc) };
+    assert_eq!(v, [1, 2]);
+    assert!(v.capacity() >= 4);
+}
@@ -1645,3 +1645,24 @@ fn test_drain_leak() {
     drop(v);
     assert_eq!(unsafe { DROPS }, 7);
 }
+
+#[test]
+fn test_with_allocator() {
+    use std::alloc::System;
+
+    let mut d = VecDeque::with_capacity_in(4, System);
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(1);
+    d.push_front(0);
+    assert_eq!(d, [0, 1, 2, 3]);
+
+    let tail = d.split_off(2);
+    assert_eq!(tail, [2, 3]);
+    assert_eq!(d.clone(), [0, 1]);
+
+    let v: Vec<i32, _> = Vec::from(tail);
+    assert_eq!(v, [2, 3]);
+    let d: VecDeque<i32, _> = VecDeque::from(v);
+    assert_eq!(d.into_iter().rev().collect::<Vec<_>>(), [3, 2]);
+}
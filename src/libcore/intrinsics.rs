@@ -1021,6 +1021,22 @@ extern "rust-intrinsic" {
     #[rustc_const_unstable(feature = "const_caller_location", issue = "47809")]
     pub fn caller_location() -> &'static crate::panic::Location<'static>;
 
+    /// Allocates a block of memory at compile time.
+    /// At runtime, just returns a null pointer.
+    ///
+    /// The memory is managed by the const evaluator: it must either be freed again with
+    /// `const_deallocate`, or only be reachable through references in the final value, in
+    /// which case it becomes immutable.
+    #[cfg(not(bootstrap))]
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_allocate(size: usize, align: usize) -> *mut u8;
+
+    /// Deallocates memory which was allocated by `const_allocate` at compile time.
+    /// At runtime, does nothing.
+    #[cfg(not(bootstrap))]
+    #[rustc_const_unstable(feature = "const_heap", issue = "none")]
+    pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
+
     /// Creates a value initialized to zero.
     ///
     /// `init` is unsafe because it returns a zeroed-out datum,
@@ -227,6 +227,8 @@ pub mod char;
 pub mod ffi;
 #[cfg(not(test))] // See #65860
 pub mod iter;
+#[unstable(feature = "once_cell", issue = "none")]
+pub mod lazy;
 pub mod option;
 pub mod panic;
 pub mod panicking;
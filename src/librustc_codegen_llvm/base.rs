@@ -27,17 +27,19 @@ use rustc::dep_graph;
 use rustc::middle::codegen_fn_attrs::{CodegenFnAttrFlags, CodegenFnAttrs};
 use rustc::middle::cstore::EncodedMetadata;
 use rustc::middle::exported_symbols;
-use rustc::mir::mono::{Linkage, Visibility};
+use rustc::mir::mono::{Linkage, MonoItem, Visibility};
 use rustc::session::config::DebugInfo;
+use rustc::session::Session;
 use rustc::ty::TyCtxt;
 use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
 use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::*;
-use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
+use rustc_codegen_ssa::{CompiledModule, ModuleCodegen, ModuleKind};
 use rustc_data_structures::small_c_str::SmallCStr;
+use rustc_fs_util::path_to_c_string;
 use rustc_span::symbol::Symbol;
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::time::Instant;
 
 pub fn write_compressed_metadata<'tcx>(
@@ -130,6 +132,10 @@ pub fn compile_codegen_unit(
                 mono_item.define::<Builder<'_, '_, '_>>(&cx);
             }
 
+            if tcx.sess.opts.debugging_opts.print_mono_item_sizes.is_some() {
+                record_mono_item_sizes(&cx, cgu_name, &mono_items);
+            }
+
             // If this codegen unit contains the main function, also create the
             // wrapper here
             if let Some(entry) = maybe_create_entry_wrapper::<Builder<'_, '_, '_>>(&cx) {
@@ -167,6 +173,75 @@ pub fn compile_codegen_unit(
     (module, cost)
 }
 
+/// Records the number of LLVM IR instructions emitted for each mono item of
+/// the codegen unit, for `-Z print-mono-item-sizes`.
+fn record_mono_item_sizes<'tcx>(
+    cx: &CodegenCx<'_, 'tcx>,
+    cgu_name: Symbol,
+    mono_items: &[(MonoItem<'tcx>, (Linkage, Visibility))],
+) {
+    let tcx = cx.tcx;
+    for &(mono_item, _) in mono_items {
+        let def_id = match mono_item {
+            MonoItem::Fn(instance) => instance.def_id(),
+            MonoItem::Static(def_id) => def_id,
+            MonoItem::GlobalAsm(..) => continue,
+        };
+        let symbol_name = mono_item.symbol_name(tcx).name.as_str();
+        let llvm_instructions = match (mono_item, cx.get_defined_value(&symbol_name)) {
+            (MonoItem::Fn(..), Some(llfn)) => unsafe {
+                llvm::LLVMRustGetFunctionInstructionCount(llfn)
+            },
+            _ => 0,
+        };
+        tcx.sess.code_stats.record_mono_item_size(
+            symbol_name.to_string(),
+            mono_item.to_string(tcx, true),
+            tcx.def_path_str(def_id),
+            tcx.crate_name(def_id.krate).to_string(),
+            cgu_name.to_string(),
+            llvm_instructions as u64,
+        );
+    }
+}
+
+/// Reads back the size of every symbol from the object files of `modules`,
+/// for `-Z print-mono-item-sizes`. Object formats that don't record symbol
+/// sizes (e.g. Mach-O) report every symbol as zero-sized.
+pub fn record_object_symbol_sizes(sess: &Session, modules: &[CompiledModule]) {
+    for module in modules {
+        let path = match module.object {
+            Some(ref path) => path,
+            None => continue,
+        };
+        let of = unsafe {
+            llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(path_to_c_string(path).as_ptr())
+                .and_then(llvm::ObjectFile::new)
+        };
+        let of = match of {
+            Some(of) => of,
+            None => {
+                sess.warn(&format!("failed to read object file `{}`", path.display()));
+                continue;
+            }
+        };
+        unsafe {
+            let si = llvm::mk_symbol_iter(of.llof);
+            while llvm::LLVMIsSymbolIteratorAtEnd(of.llof, si.llsi) == llvm::False {
+                let name = CStr::from_ptr(llvm::LLVMGetSymbolName(si.llsi)).to_string_lossy();
+                // Mach-O and 32-bit Windows prefix C symbols with an underscore.
+                let has_prefix = sess.target.target.options.is_like_osx
+                    || (sess.target.target.options.is_like_windows
+                        && sess.target.target.arch == "x86");
+                let name = if has_prefix && name.starts_with('_') { &name[1..] } else { &name };
+                let size = llvm::LLVMGetSymbolSize(si.llsi);
+                sess.code_stats.record_object_symbol_size(&module.name, name, size);
+                llvm::LLVMMoveToNextSymbol(si.llsi);
+            }
+        }
+    }
+}
+
 pub fn set_link_section(llval: &Value, attrs: &CodegenFnAttrs) {
     let sect = match attrs.link_section {
         Some(name) => name,
@@ -137,6 +137,12 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
                 let llfn = self.get_intrinsic(&("llvm.debugtrap"));
                 self.call(llfn, &[], None)
             }
+            // There is no compile-time heap at runtime: `const_allocate` returns a null pointer
+            // and `const_deallocate` does nothing.
+            "const_allocate" => self.const_null(self.type_i8p()),
+            "const_deallocate" => {
+                return;
+            }
             "va_start" => self.va_start(args[0].immediate()),
             "va_end" => self.va_end(args[0].immediate()),
             "va_copy" => {
@@ -274,6 +274,9 @@ impl CodegenBackend for LlvmCodegenBackend {
             .downcast::<rustc_codegen_ssa::back::write::OngoingCodegen<LlvmCodegenBackend>>()
             .expect("Expected LlvmCodegenBackend's OngoingCodegen, found Box<Any>")
             .join(sess);
+        if sess.opts.debugging_opts.print_mono_item_sizes.is_some() {
+            base::record_object_symbol_sizes(sess, &codegen_results.modules);
+        }
         if sess.opts.debugging_opts.incremental_info {
             rustc_codegen_ssa::back::write::dump_incremental_data(&codegen_results);
         }
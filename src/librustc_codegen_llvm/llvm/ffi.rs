@@ -584,6 +584,8 @@ extern "C" {
 }
 #[repr(C)]
 pub struct SectionIterator<'a>(InvariantOpaque<'a>);
+#[repr(C)]
+pub struct SymbolIterator<'a>(InvariantOpaque<'a>);
 extern "C" {
     pub type Pass;
 }
@@ -1558,6 +1560,21 @@ extern "C" {
     /// Returns the current section contents as a string buffer.
     pub fn LLVMGetSectionContents(SI: &SectionIterator<'_>) -> *const c_char;
 
+    /// Enumerates the symbols in an object file.
+    pub fn LLVMGetSymbols(ObjFile: &'a ObjectFile) -> &'a mut SymbolIterator<'a>;
+    /// Destroys a symbol iterator.
+    pub fn LLVMDisposeSymbolIterator(SI: &'a mut SymbolIterator<'a>);
+    /// Returns `true` if the symbol iterator is at the end of the symbol
+    /// list.
+    pub fn LLVMIsSymbolIteratorAtEnd(ObjFile: &'a ObjectFile, SI: &SymbolIterator<'a>) -> Bool;
+    /// Moves the symbol iterator to point to the next symbol.
+    pub fn LLVMMoveToNextSymbol(SI: &SymbolIterator<'_>);
+    /// Returns the name of the current symbol as a NUL-terminated string.
+    pub fn LLVMGetSymbolName(SI: &SymbolIterator<'_>) -> *const c_char;
+    /// Returns the size of the current symbol, or 0 if the object format
+    /// doesn't record it.
+    pub fn LLVMGetSymbolSize(SI: &SymbolIterator<'_>) -> u64;
+
     /// Reads the given file and returns it as a memory buffer. Use
     /// LLVMDisposeMemoryBuffer() to get rid of it.
     pub fn LLVMRustCreateMemoryBufferWithContentsOfFile(
@@ -1995,6 +2012,7 @@ extern "C" {
     pub fn LLVMRustSetLLVMOptions(Argc: c_int, Argv: *const *const c_char);
     pub fn LLVMRustPrintPasses();
     pub fn LLVMRustGetInstructionCount(M: &Module) -> u32;
+    pub fn LLVMRustGetFunctionInstructionCount(Fn: &Value) -> u32;
     pub fn LLVMRustSetNormalizedTarget(M: &Module, triple: *const c_char);
     pub fn LLVMRustAddAlwaysInlinePass(P: &PassManagerBuilder, AddLifetimes: bool);
     pub fn LLVMRustRunRestrictionPass(M: &Module, syms: *const *const c_char, len: size_t);
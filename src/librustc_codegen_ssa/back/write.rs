@@ -1757,6 +1757,10 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
 
         sess.cgu_reuse_tracker.check_expected_reuse(sess.diagnostic());
 
+        if sess.opts.debugging_opts.print_cgu_reuse_stats {
+            sess.cgu_reuse_tracker.print_reuse_stats();
+        }
+
         sess.abort_if_errors();
 
         let work_products =
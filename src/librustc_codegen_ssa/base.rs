@@ -924,6 +924,12 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
         return CguReuse::No;
     }
 
+    // `-Z print-mono-item-sizes` measures each item while it's being
+    // codegened, so cached modules would be missing from the report.
+    if tcx.sess.opts.debugging_opts.print_mono_item_sizes.is_some() {
+        return CguReuse::No;
+    }
+
     let work_product_id = &cgu.work_product_id();
     if tcx.dep_graph.previous_work_product(work_product_id).is_none() {
         // We don't have anything cached for this CGU. This can happen
@@ -414,6 +414,10 @@ pub fn run_compiler(
             linker.link()?
         }
 
+        if let Some(format) = sess.opts.debugging_opts.print_mono_item_sizes {
+            sess.code_stats.print_mono_item_sizes(format);
+        }
+
         if sess.opts.debugging_opts.perf_stats {
             sess.print_perf_stats();
         }
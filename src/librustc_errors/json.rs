@@ -334,8 +334,8 @@ impl DiagnosticSpan {
             file_name: start.file.name.to_string(),
             byte_start: start.file.original_relative_byte_pos(span.lo()).0,
             byte_end: start.file.original_relative_byte_pos(span.hi()).0,
-            line_start: start.line,
-            line_end: end.line,
+            line_start: je.sm.doctest_offset_line(&start.file.name, start.line),
+            line_end: je.sm.doctest_offset_line(&end.file.name, end.line),
             column_start: start.col.0 + 1,
             column_end: end.col.0 + 1,
             is_primary,
@@ -6,7 +6,8 @@ use rustc::lint::Level;
 use rustc::middle::cstore;
 use rustc::session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc::session::config::{rustc_optgroups, ErrorOutputType, ExternLocation, Options, Passes};
-use rustc::session::config::{ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
+use rustc::session::config::{CguPartitioning, SwitchWithOptPath};
+use rustc::session::config::{ExternEntry, LinkerPluginLto, LtoCli, MonoItemSizesFormat};
 use rustc::session::config::{Externs, OutputType, OutputTypes, SymbolManglingVersion};
 use rustc::session::search_paths::SearchPath;
 use rustc::session::{build_session, Session};
@@ -564,6 +565,10 @@ fn test_debugging_options_tracking_hash() {
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.print_mono_items = Some(String::from("abc"));
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.print_mono_item_sizes = Some(MonoItemSizesFormat::Json);
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+    opts.debugging_opts.print_cgu_reuse_stats = true;
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.dump_mir = Some(String::from("abc"));
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
     opts.debugging_opts.dump_mir_dir = String::from("abc");
@@ -627,6 +632,10 @@ fn test_debugging_options_tracking_hash() {
     opts = reference.clone();
     opts.debugging_opts.symbol_mangling_version = SymbolManglingVersion::V0;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.cgu_partitioning = CguPartitioning::Stable;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 }
 
 #[test]
@@ -1,5 +1,5 @@
 use rustc::mir;
-use rustc::ty::layout::HasTyCtxt;
+use rustc::ty::layout::{Align, HasTyCtxt, LayoutOf, Size};
 use rustc::ty::{self, Ty};
 use std::borrow::{Borrow, Cow};
 use std::collections::hash_map::Entry;
@@ -10,7 +10,7 @@ use rustc_data_structures::fx::FxHashMap;
 
 use rustc::mir::AssertMessage;
 use rustc_span::source_map::Span;
-use rustc_span::symbol::Symbol;
+use rustc_span::symbol::{sym, Symbol};
 
 use crate::interpret::{
     self, snapshot, AllocId, Allocation, GlobalId, ImmTy, InterpCx, InterpResult, Memory,
@@ -86,6 +86,14 @@ impl<'mir, 'tcx> InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>> {
     }
 }
 
+/// Converts the alignment argument of the heap intrinsics.
+fn heap_align<'tcx>(align: u64) -> InterpResult<'tcx, Align> {
+    match Align::from_bytes(align) {
+        Ok(align) => Ok(align),
+        Err(msg) => throw_ub_format!("invalid heap allocation alignment: {}", msg),
+    }
+}
+
 /// The number of steps between loop detector snapshots.
 /// Should be a power of two for performance reasons.
 const DETECTOR_SNAPSHOT_PERIOD: isize = 256;
@@ -273,9 +281,36 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         if ecx.emulate_intrinsic(span, instance, args, ret)? {
             return Ok(());
         }
-        // An intrinsic that we do not support
         let intrinsic_name = ecx.tcx.item_name(instance.def_id());
-        Err(ConstEvalErrKind::NeedsRfc(format!("calling intrinsic `{}`", intrinsic_name)).into())
+        // The heap intrinsics only make sense during CTFE, so they are not part of
+        // `emulate_intrinsic`.
+        match (intrinsic_name, ret) {
+            (sym::const_allocate, Some((dest, ret))) => {
+                let size = ecx.read_scalar(args[0])?.to_machine_usize(&*ecx)?;
+                let align = ecx.read_scalar(args[1])?.to_machine_usize(&*ecx)?;
+                let align = heap_align(align)?;
+                let ptr = ecx.memory.allocate(Size::from_bytes(size), align, MemoryKind::Heap);
+                ecx.write_scalar(Scalar::Ptr(ptr), dest)?;
+                ecx.go_to_block(ret);
+                Ok(())
+            }
+            (sym::const_deallocate, Some((_, ret))) => {
+                let ptr = ecx.read_scalar(args[0])?.not_undef()?;
+                let size = ecx.read_scalar(args[1])?.to_machine_usize(&*ecx)?;
+                let align = ecx.read_scalar(args[2])?.to_machine_usize(&*ecx)?;
+                let align = heap_align(align)?;
+                let ptr = ecx.force_ptr(ptr)?;
+                let size_and_align = Some((Size::from_bytes(size), align));
+                ecx.memory.deallocate(ptr, size_and_align, MemoryKind::Heap)?;
+                ecx.go_to_block(ret);
+                Ok(())
+            }
+            // An intrinsic that we do not support
+            _ => {
+                let msg = format!("calling intrinsic `{}`", intrinsic_name);
+                Err(ConstEvalErrKind::NeedsRfc(msg).into())
+            }
+        }
     }
 
     fn assert_panic(
@@ -338,11 +373,12 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         ()
     }
 
-    fn box_alloc(
-        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
-        _dest: PlaceTy<'tcx>,
-    ) -> InterpResult<'tcx> {
-        Err(ConstEvalErrKind::NeedsRfc("heap allocations via `box` keyword".to_string()).into())
+    fn box_alloc(ecx: &mut InterpCx<'mir, 'tcx, Self>, dest: PlaceTy<'tcx>) -> InterpResult<'tcx> {
+        // `box` is only reachable with `-Zunleash-the-miri-inside-of-you`. The allocation is
+        // subject to the same interning rules as the ones from `const_allocate`.
+        let layout = ecx.layout_of(dest.layout.ty.boxed_ty())?;
+        let place = ecx.allocate(layout, MemoryKind::Heap);
+        ecx.write_scalar(place.ptr, dest)
     }
 
     fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
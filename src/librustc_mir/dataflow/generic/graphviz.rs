@@ -8,7 +8,7 @@ use rustc_hir::def_id::DefId;
 use rustc_index::bit_set::{BitSet, HybridBitSet};
 use rustc_index::vec::{Idx, IndexVec};
 
-use super::{Analysis, GenKillSet, Results, ResultsRefCursor};
+use super::{Analysis, Direction, GenKillSet, Results, ResultsRefCursor};
 use crate::util::graphviz_safe_def_name;
 
 pub struct Formatter<'a, 'tcx, A>
@@ -218,8 +218,8 @@ where
 
         // Write the full dataflow state immediately after the terminator if it differs from the
         // state at block entry.
-        self.results.seek_after(terminator_loc);
-        if self.results.get() != &block_entry_state {
+        self.results.seek_to_block_end(block);
+        if self.results.get() != &block_entry_state || A::Direction::is_backward() {
             let after_terminator_name = match terminator.kind {
                 mir::TerminatorKind::Call { destination: Some(_), .. } => "(on unwind)",
                 _ => "(on exit)",
@@ -228,9 +228,14 @@ where
             self.write_row_with_full_state(w, "", after_terminator_name)?;
         }
 
-        // Write any changes caused by terminator-specific effects
+        // Write any changes caused by terminator-specific effects.
+        //
+        // FIXME: Call return effects are applied along the predecessor edge for backward
+        // analyses and are not shown here.
         match terminator.kind {
-            mir::TerminatorKind::Call { destination: Some(_), .. } => {
+            mir::TerminatorKind::Call { destination: Some(_), .. }
+                if A::Direction::is_forward() =>
+            {
                 let num_state_columns = self.num_state_columns();
                 self.write_row(w, "", "(on successful return)", |this, w, fmt| {
                     write!(
@@ -433,7 +438,15 @@ where
         results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
         location: Location,
     ) -> io::Result<()> {
-        if location.statement_index == 0 {
+        if A::Direction::is_backward() {
+            if location == results.body().terminator_loc(location.block) {
+                results.seek_to_block_end(location.block);
+            } else {
+                results.seek_after_primary_effect(location.successor_within_block());
+            }
+
+            self.prev_state.overwrite(results.get());
+        } else if location.statement_index == 0 {
             results.seek_to_block_start(location.block);
             self.prev_state.overwrite(results.get());
         } else {
@@ -443,7 +456,7 @@ where
 
         self.prev_loc = location;
         write!(w, r#"<td {fmt} balign="left" align="left">"#, fmt = fmt)?;
-        results.seek_after(location);
+        results.seek_after_primary_effect(location);
         let curr_state = results.get();
         write_diff(&mut w, results.analysis(), &self.prev_state, curr_state)?;
         self.prev_state.overwrite(curr_state);
@@ -479,7 +492,15 @@ where
         results: &mut ResultsRefCursor<'_, '_, 'tcx, A>,
         location: Location,
     ) -> io::Result<()> {
-        if location.statement_index == 0 {
+        if A::Direction::is_backward() {
+            if location == results.body().terminator_loc(location.block) {
+                results.seek_to_block_end(location.block);
+            } else {
+                results.seek_after_primary_effect(location.successor_within_block());
+            }
+
+            self.prev_state.overwrite(results.get());
+        } else if location.statement_index == 0 {
             results.seek_to_block_start(location.block);
             self.prev_state.overwrite(results.get());
         } else {
@@ -492,7 +513,7 @@ where
         // Before
 
         write!(w, r#"<td {fmt} align="left">"#, fmt = fmt)?;
-        results.seek_before(location);
+        results.seek_before_primary_effect(location);
         let curr_state = results.get();
         write_diff(&mut w, results.analysis(), &self.prev_state, curr_state)?;
         self.prev_state.overwrite(curr_state);
@@ -501,7 +522,7 @@ where
         // After
 
         write!(w, r#"<td {fmt} align="left">"#, fmt = fmt)?;
-        results.seek_after(location);
+        results.seek_after_primary_effect(location);
         let curr_state = results.get();
         write_diff(&mut w, results.analysis(), &self.prev_state, curr_state)?;
         self.prev_state.overwrite(curr_state);
@@ -9,6 +9,11 @@
 //! the appropriate constructor and call `iterate_to_fixpoint`. You can use a `ResultsCursor` to
 //! inspect the fixpoint solution to your dataflow problem.
 //!
+//! Analyses are forward by default. A backward analysis (e.g. liveness) sets its
+//! `AnalysisDomain::Direction` to `Backward`. Its entry set for each block is the state at the
+//! *exit* of that block, and its effects are applied from the terminator up to the first
+//! statement.
+//!
 //! ```ignore(cross-crate-imports)
 //! fn do_my_analysis(tcx: TyCtxt<'tcx>, body: &mir::Body<'tcx>, did: DefId) {
 //!     let analysis = MyAnalysis::new();
@@ -22,7 +27,7 @@
 //!     let mut cursor = ResultsCursor::new(body, results);
 //!
 //!     for (_, statement_index) in body.block_data[START_BLOCK].statements.iter_enumerated() {
-//!         cursor.seek_after(Location { block: START_BLOCK, statement_index });
+//!         cursor.seek_after_primary_effect(Location { block: START_BLOCK, statement_index });
 //!         let state = cursor.get();
 //!         println!("{:?}", state);
 //!     }
@@ -32,6 +37,7 @@
 //! [gen-kill]: https://en.wikipedia.org/wiki/Data-flow_analysis#Bit_vector_problems
 //! [design-meeting]https://github.com/rust-lang/compiler-team/issues/202
 
+use std::cmp::Ordering;
 use std::io;
 
 use rustc::mir::{self, BasicBlock, Location};
@@ -44,11 +50,13 @@ use rustc_index::vec::{Idx, IndexVec};
 use crate::dataflow::BottomValue;
 
 mod cursor;
+mod direction;
 mod engine;
 mod graphviz;
 mod visitor;
 
 pub use self::cursor::{ResultsCursor, ResultsRefCursor};
+pub use self::direction::{Backward, Direction, Forward};
 pub use self::engine::Engine;
 pub use self::visitor::{visit_results, ResultsVisitor};
 pub use self::visitor::{BorrowckFlowState, BorrowckResults};
@@ -72,6 +80,8 @@ where
     }
 
     /// Gets the entry set for the given block.
+    ///
+    /// For backward analyses, this is the state at the *exit* of `block`.
     pub fn entry_set_for_block(&self, block: BasicBlock) -> &BitSet<A::Idx> {
         &self.entry_sets[block]
     }
@@ -102,11 +112,15 @@ where
 /// and referred to as the state vector.
 ///
 /// This trait also defines the initial value for the dataflow state upon entry to the
-/// `START_BLOCK`, as well as some names used to refer to this analysis when debugging.
+/// `START_BLOCK`, the direction of the analysis, as well as some names used to refer to this
+/// analysis when debugging.
 pub trait AnalysisDomain<'tcx>: BottomValue {
     /// The type of the elements in the state vector.
     type Idx: Idx;
 
+    /// The direction of this analysis. Either `Forward` or `Backward`.
+    type Direction: Direction = Forward;
+
     /// A descriptive name for this analysis. Used only for debugging.
     ///
     /// This name should be brief and contain no spaces, periods or other characters that are not
@@ -118,6 +132,9 @@ pub trait AnalysisDomain<'tcx>: BottomValue {
 
     /// Mutates the entry set of the `START_BLOCK` to contain the initial state for dataflow
     /// analysis.
+    ///
+    /// For backward analyses, initial state besides the bottom value is not yet supported. Trying
+    /// to mutate the initial state will result in a panic.
     fn initialize_start_block(&self, body: &mir::Body<'tcx>, state: &mut BitSet<Self::Idx>);
 
     /// Prints an element in the state vector for debugging.
@@ -451,5 +468,68 @@ impl<T: Idx> GenKill<T> for BitSet<T> {
     }
 }
 
+/// Which part of a statement or terminator's effect is meant.
+///
+/// Each statement and terminator has a "before" effect, which is applied first (in the direction
+/// of the analysis), and a "primary" effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Effect {
+    /// The "before" effect (e.g. `apply_before_statement_effect`) for a statement (or terminator).
+    Before,
+
+    /// The "primary" effect (e.g. `apply_statement_effect`) for a statement (or terminator).
+    Primary,
+}
+
+impl Effect {
+    pub const fn at_index(self, statement_index: usize) -> EffectIndex {
+        EffectIndex { effect: self, statement_index }
+    }
+}
+
+/// A point within a basic block: the `Effect` of the statement (or terminator) at
+/// `statement_index`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EffectIndex {
+    statement_index: usize,
+    effect: Effect,
+}
+
+impl EffectIndex {
+    fn next_in_forward_order(self) -> Self {
+        match self.effect {
+            Effect::Before => Effect::Primary.at_index(self.statement_index),
+            Effect::Primary => Effect::Before.at_index(self.statement_index + 1),
+        }
+    }
+
+    fn next_in_backward_order(self) -> Self {
+        match self.effect {
+            Effect::Before => Effect::Primary.at_index(self.statement_index),
+            Effect::Primary => Effect::Before.at_index(self.statement_index - 1),
+        }
+    }
+
+    /// Returns `true` if the effect at `self` should be applied earlier than the effect at `other`
+    /// in forward order.
+    fn precedes_in_forward_order(self, other: Self) -> bool {
+        let ord = self
+            .statement_index
+            .cmp(&other.statement_index)
+            .then_with(|| self.effect.cmp(&other.effect));
+        ord == Ordering::Less
+    }
+
+    /// Returns `true` if the effect at `self` should be applied earlier than the effect at `other`
+    /// in backward order.
+    fn precedes_in_backward_order(self, other: Self) -> bool {
+        let ord = other
+            .statement_index
+            .cmp(&self.statement_index)
+            .then_with(|| self.effect.cmp(&other.effect));
+        ord == Ordering::Less
+    }
+}
+
 #[cfg(test)]
 mod tests;
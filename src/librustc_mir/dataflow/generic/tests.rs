@@ -1,5 +1,7 @@
 //! A test for the logic that updates the state in a `ResultsCursor` during seek.
 
+use std::marker::PhantomData;
+
 use rustc::mir::{self, BasicBlock, Location};
 use rustc::ty;
 use rustc_index::bit_set::BitSet;
@@ -79,20 +81,23 @@ fn mock_body() -> mir::Body<'static> {
 /// | Location               | Before            | After  |
 /// |------------------------|-------------------|--------|
 /// | (on_entry)             | {102}                     ||
-/// | Statement 0            | +0                | +1     |
+/// | statement 0            | +0                | +1     |
 /// | statement 1            | +2                | +3     |
 /// | `Call` terminator      | +4                | +5     |
 /// | (on unwind)            | {102,0,1,2,3,4,5}         ||
 /// | (on successful return) | +6                        ||
 ///
+/// For backward analyses, the effects are numbered starting at the terminator instead.
+///
 /// The `102` in the block's entry set is derived from the basic block index and ensures that the
 /// expected state is unique across all basic blocks. Remember, it is generated by
 /// `mock_entry_sets`, not from actually running `MockAnalysis` to fixpoint.
-struct MockAnalysis<'tcx> {
+struct MockAnalysis<'tcx, D> {
     body: &'tcx mir::Body<'tcx>,
+    dir: PhantomData<D>,
 }
 
-impl MockAnalysis<'tcx> {
+impl<D: Direction> MockAnalysis<'tcx, D> {
     const BASIC_BLOCK_OFFSET: usize = 100;
 
     /// The entry set for each `BasicBlock` is the ID of that block offset by a fixed amount to
@@ -116,20 +121,30 @@ impl MockAnalysis<'tcx> {
 
     /// Returns the index that should be added to the dataflow state at the given target.
     ///
-    /// This index is only unique within a given basic block. `SeekAfter` and
-    /// `SeekAfterAssumeCallReturns` have the same effect unless `target` is a `Call` terminator.
+    /// This index is only unique within a given basic block. `After` and
+    /// `AfterAssumeCallReturns` have the same effect unless `target` is a `Call` terminator.
     fn effect_at_target(&self, target: SeekTarget) -> Option<usize> {
         use SeekTarget::*;
 
+        // Number the statements (and terminator) of a block in the order their effects are
+        // applied.
+        let effect_order = |loc: Location| {
+            if D::is_forward() {
+                loc.statement_index
+            } else {
+                self.body[loc.block].statements.len() - loc.statement_index
+            }
+        };
+
         let idx = match target {
-            BlockStart(_) => return None,
+            BlockEntry(_) => return None,
 
             AfterAssumeCallReturns(loc) if is_call_terminator_non_diverging(self.body, loc) => {
-                loc.statement_index * 2 + 2
+                effect_order(loc) * 2 + 2
             }
 
-            Before(loc) => loc.statement_index * 2,
-            After(loc) | AfterAssumeCallReturns(loc) => loc.statement_index * 2 + 1,
+            Before(loc) => effect_order(loc) * 2,
+            After(loc) | AfterAssumeCallReturns(loc) => effect_order(loc) * 2 + 1,
         };
 
         assert!(idx < Self::BASIC_BLOCK_OFFSET, "Too many statements in basic block");
@@ -139,11 +154,12 @@ impl MockAnalysis<'tcx> {
     /// Returns the expected state at the given `SeekTarget`.
     ///
     /// This is the union of index of the target basic block, the index assigned to the
-    /// target statement or terminator, and the indices of all preceding statements in the target
-    /// basic block.
+    /// target statement or terminator, and the indices of all statements in the target basic
+    /// block whose effects are applied before it.
     ///
-    /// For example, the expected state when calling
-    /// `seek_before(Location { block: 2, statement_index: 2 })` would be `[102, 0, 1, 2, 3, 4]`.
+    /// For example, the expected state for a forward analysis when calling
+    /// `seek_before_primary_effect(Location { block: 2, statement_index: 2 })` would be
+    /// `[102, 0, 1, 2, 3, 4]`.
     fn expected_state_at_target(&self, target: SeekTarget) -> BitSet<usize> {
         let mut ret = BitSet::new_empty(self.bits_per_block(self.body));
         ret.insert(Self::BASIC_BLOCK_OFFSET + target.block().index());
@@ -158,12 +174,13 @@ impl MockAnalysis<'tcx> {
     }
 }
 
-impl BottomValue for MockAnalysis<'tcx> {
+impl<D> BottomValue for MockAnalysis<'tcx, D> {
     const BOTTOM_VALUE: bool = false;
 }
 
-impl AnalysisDomain<'tcx> for MockAnalysis<'tcx> {
+impl<D: Direction> AnalysisDomain<'tcx> for MockAnalysis<'tcx, D> {
     type Idx = usize;
+    type Direction = D;
 
     const NAME: &'static str = "mock";
 
@@ -176,7 +193,7 @@ impl AnalysisDomain<'tcx> for MockAnalysis<'tcx> {
     }
 }
 
-impl Analysis<'tcx> for MockAnalysis<'tcx> {
+impl<D: Direction> Analysis<'tcx> for MockAnalysis<'tcx, D> {
     fn apply_statement_effect(
         &self,
         state: &mut BitSet<Self::Idx>,
@@ -233,7 +250,7 @@ impl Analysis<'tcx> for MockAnalysis<'tcx> {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum SeekTarget {
-    BlockStart(BasicBlock),
+    BlockEntry(BasicBlock),
     Before(Location),
     After(Location),
     AfterAssumeCallReturns(Location),
@@ -244,72 +261,77 @@ impl SeekTarget {
         use SeekTarget::*;
 
         match *self {
-            BlockStart(block) => block,
+            BlockEntry(block) => block,
             Before(loc) | After(loc) | AfterAssumeCallReturns(loc) => loc.block,
         }
     }
 
     /// An iterator over all possible `SeekTarget`s in a given block in order, starting with
-    /// `BlockStart`.
+    /// `BlockEntry`.
     ///
-    /// This includes both `After` and `AfterAssumeCallReturns` for every `Location`.
-    fn iter_in_block(body: &mir::Body<'_>, block: BasicBlock) -> impl Iterator<Item = Self> {
+    /// This includes both `After` and `AfterAssumeCallReturns` for every `Location`. The latter is
+    /// only supported by forward analyses, so it is omitted if `with_call_returns` is `false`.
+    fn iter_in_block(
+        body: &mir::Body<'_>,
+        block: BasicBlock,
+        with_call_returns: bool,
+    ) -> impl Iterator<Item = Self> {
         let statements_and_terminator = (0..=body[block].statements.len())
             .flat_map(|i| (0..3).map(move |j| (i, j)))
-            .map(move |(i, kind)| {
+            .filter_map(move |(i, kind)| {
                 let loc = Location { block, statement_index: i };
                 match kind {
-                    0 => SeekTarget::Before(loc),
-                    1 => SeekTarget::After(loc),
-                    2 => SeekTarget::AfterAssumeCallReturns(loc),
+                    0 => Some(SeekTarget::Before(loc)),
+                    1 => Some(SeekTarget::After(loc)),
+                    2 if with_call_returns => Some(SeekTarget::AfterAssumeCallReturns(loc)),
+                    2 => None,
                     _ => unreachable!(),
                 }
             });
 
-        std::iter::once(SeekTarget::BlockStart(block)).chain(statements_and_terminator)
+        std::iter::once(SeekTarget::BlockEntry(block)).chain(statements_and_terminator)
     }
 }
 
-#[test]
-fn cursor_seek() {
-    let body = mock_body();
-    let body = &body;
-    let analysis = MockAnalysis { body };
+fn test_cursor<D: Direction>(analysis: MockAnalysis<'tcx, D>) {
+    let body = analysis.body;
 
     let mut cursor =
         Results { entry_sets: analysis.mock_entry_sets(), analysis }.into_results_cursor(body);
 
     // Sanity check: the mock call return effect is unique and actually being applied.
-    let call_terminator_loc = Location { block: BasicBlock::from_usize(2), statement_index: 2 };
-    assert!(is_call_terminator_non_diverging(body, call_terminator_loc));
-
-    let call_return_effect = cursor
-        .analysis()
-        .effect_at_target(SeekTarget::AfterAssumeCallReturns(call_terminator_loc))
-        .unwrap();
-    assert_ne!(
-        call_return_effect,
-        cursor.analysis().effect_at_target(SeekTarget::After(call_terminator_loc)).unwrap()
-    );
-
-    cursor.seek_after(call_terminator_loc);
-    assert!(!cursor.get().contains(call_return_effect));
-    cursor.seek_after_assume_call_returns(call_terminator_loc);
-    assert!(cursor.get().contains(call_return_effect));
+    if D::is_forward() {
+        let call_terminator_loc = Location { block: BasicBlock::from_usize(2), statement_index: 2 };
+        assert!(is_call_terminator_non_diverging(body, call_terminator_loc));
+
+        let call_return_effect = cursor
+            .analysis()
+            .effect_at_target(SeekTarget::AfterAssumeCallReturns(call_terminator_loc))
+            .unwrap();
+        assert_ne!(
+            call_return_effect,
+            cursor.analysis().effect_at_target(SeekTarget::After(call_terminator_loc)).unwrap()
+        );
+
+        cursor.seek_after_primary_effect(call_terminator_loc);
+        assert!(!cursor.get().contains(call_return_effect));
+        cursor.seek_after_assume_call_returns(call_terminator_loc);
+        assert!(cursor.get().contains(call_return_effect));
+    }
 
     let every_target = || {
         body.basic_blocks()
             .iter_enumerated()
-            .flat_map(|(bb, _)| SeekTarget::iter_in_block(body, bb))
+            .flat_map(|(bb, _)| SeekTarget::iter_in_block(body, bb, D::is_forward()))
     };
 
     let mut seek_to_target = |targ| {
         use SeekTarget::*;
 
         match targ {
-            BlockStart(block) => cursor.seek_to_block_start(block),
-            Before(loc) => cursor.seek_before(loc),
-            After(loc) => cursor.seek_after(loc),
+            BlockEntry(block) => cursor.seek_to_block_entry(block),
+            Before(loc) => cursor.seek_before_primary_effect(loc),
+            After(loc) => cursor.seek_after_primary_effect(loc),
             AfterAssumeCallReturns(loc) => cursor.seek_after_assume_call_returns(loc),
         }
 
@@ -330,3 +352,19 @@ fn cursor_seek() {
         }
     }
 }
+
+#[test]
+fn backward_cursor() {
+    let body = mock_body();
+    let body = &body;
+    let analysis = MockAnalysis { body, dir: PhantomData::<Backward> };
+    test_cursor(analysis)
+}
+
+#[test]
+fn forward_cursor() {
+    let body = mock_body();
+    let body = &body;
+    let analysis = MockAnalysis { body, dir: PhantomData::<Forward> };
+    test_cursor(analysis)
+}
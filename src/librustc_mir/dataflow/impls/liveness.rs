@@ -0,0 +1,105 @@
+use rustc::mir::visit::{MutatingUseContext, PlaceContext, Visitor};
+use rustc::mir::{self, Local, Location};
+use rustc_index::bit_set::BitSet;
+
+use crate::dataflow::generic::{self as dataflow, GenKill};
+use crate::dataflow::BottomValue;
+use crate::util::liveness::{categorize, DefUse};
+
+/// A [live-variable dataflow analysis][liveness].
+///
+/// This analysis considers references as being used only at the point of the borrow. In other
+/// words, this analysis does not track uses because of references that already exist (see
+/// `src/test/ui/mir-dataflow/liveness-ptr.rs` for an example). You almost never want to use this
+/// analysis without also looking at the results of `MaybeBorrowedLocals`.
+///
+/// [liveness]: https://en.wikipedia.org/wiki/Live_variable_analysis
+pub struct MaybeLiveLocals;
+
+impl MaybeLiveLocals {
+    fn transfer_function<T>(&self, trans: &'a mut T) -> TransferFunction<'a, T> {
+        TransferFunction(trans)
+    }
+}
+
+impl BottomValue for MaybeLiveLocals {
+    // bottom = not live
+    const BOTTOM_VALUE: bool = false;
+}
+
+impl dataflow::AnalysisDomain<'tcx> for MaybeLiveLocals {
+    type Idx = Local;
+    type Direction = dataflow::Backward;
+
+    const NAME: &'static str = "liveness";
+
+    fn bits_per_block(&self, body: &mir::Body<'tcx>) -> usize {
+        body.local_decls.len()
+    }
+
+    fn initialize_start_block(&self, _: &mir::Body<'tcx>, _: &mut BitSet<Self::Idx>) {
+        // No variables are live until we observe a use
+    }
+}
+
+impl dataflow::GenKillAnalysis<'tcx> for MaybeLiveLocals {
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        statement: &mir::Statement<'tcx>,
+        location: Location,
+    ) {
+        self.transfer_function(trans).visit_statement(statement, location);
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        terminator: &mir::Terminator<'tcx>,
+        location: Location,
+    ) {
+        self.transfer_function(trans).visit_terminator(terminator, location);
+    }
+
+    fn call_return_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        _block: mir::BasicBlock,
+        _func: &mir::Operand<'tcx>,
+        _args: &[mir::Operand<'tcx>],
+        dest_place: &mir::Place<'tcx>,
+    ) {
+        if let Some(local) = dest_place.as_local() {
+            trans.kill(local);
+        }
+    }
+}
+
+/// A `Visitor` that defines the transfer function for `MaybeLiveLocals`.
+struct TransferFunction<'a, T>(&'a mut T);
+
+impl<'tcx, T> Visitor<'tcx> for TransferFunction<'_, T>
+where
+    T: GenKill<Local>,
+{
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: Location) {
+        // The return place of a `Call` is only assigned if the call returns successfully. This is
+        // handled in `call_return_effect`, which is applied only along the edge for the successful
+        // return.
+        if let PlaceContext::MutatingUse(MutatingUseContext::Call) = context {
+            if place.as_local().is_some() {
+                return;
+            }
+        }
+
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match categorize(context) {
+            Some(DefUse::Def) => self.0.kill(local),
+            Some(DefUse::Use) | Some(DefUse::Drop) => self.0.gen(local),
+            None => {}
+        }
+    }
+}
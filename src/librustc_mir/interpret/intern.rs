@@ -102,6 +102,11 @@ fn intern_shallow<'rt, 'mir, 'tcx, M: CompileTimeMachine<'mir, 'tcx>>(
     // changes in this function.
     match kind {
         MemoryKind::Stack | MemoryKind::Vtable | MemoryKind::CallerLocation => {}
+        // Heap allocations reached through a typed reference are interned just like stack
+        // memory: they get frozen unless they are (transitively) inside a `static mut` or an
+        // `UnsafeCell` of a static. Heap allocations only reachable via raw pointers are
+        // rejected when handling the leftover allocations.
+        MemoryKind::Heap => {}
     }
     // Set allocation mutability as appropriate. This is used by LLVM to put things into
     // read-only memory, and also by Miri when evluating other constants/statics that
@@ -348,7 +353,16 @@ pub fn intern_const_alloc_recursive<M: CompileTimeMachine<'mir, 'tcx>>(
 
     let mut todo: Vec<_> = leftover_allocations.iter().cloned().collect();
     while let Some(alloc_id) = todo.pop() {
-        if let Some((_, mut alloc)) = ecx.memory.alloc_map.remove(&alloc_id) {
+        if let Some((kind, mut alloc)) = ecx.memory.alloc_map.remove(&alloc_id) {
+            // Heap memory behind raw pointers (this includes `Box`) cannot be part of the final
+            // value: nothing tells us whether it should be frozen, and a `Box` in a constant
+            // would free static memory when dropped.
+            if kind == MemoryKind::Heap {
+                throw_unsup!(ValidationFailure(
+                    "encountered heap allocation that is not behind a reference in final constant"
+                        .into()
+                ))
+            }
             // We can't call the `intern_shallow` method here, as its logic is tailored to safe
             // references and a `leftover_allocations` set (where we only have a todo-list here).
             // So we hand-roll the interning logic here again.
@@ -29,6 +29,10 @@ pub enum MemoryKind<T> {
     Vtable,
     /// Memory allocated by `caller_location` intrinsic. Error if ever deallocated.
     CallerLocation,
+    /// Memory allocated by the `const_allocate` intrinsic or the `box` keyword during CTFE.
+    /// Must be deallocated, or end up in the final value of the constant where interning
+    /// freezes it.
+    Heap,
     /// Additional memory kinds a machine wishes to distinguish from the builtin ones.
     Machine(T),
 }
@@ -40,6 +44,7 @@ impl<T: MayLeak> MayLeak for MemoryKind<T> {
             MemoryKind::Stack => false,
             MemoryKind::Vtable => true,
             MemoryKind::CallerLocation => true,
+            MemoryKind::Heap => false,
             MemoryKind::Machine(k) => k.may_leak(),
         }
     }
@@ -722,6 +727,7 @@ impl<'mir, 'tcx, M: Machine<'mir, 'tcx>> Memory<'mir, 'tcx, M> {
                         MemoryKind::Stack => " (stack)".to_owned(),
                         MemoryKind::Vtable => " (vtable)".to_owned(),
                         MemoryKind::CallerLocation => " (caller_location)".to_owned(),
+                        MemoryKind::Heap => " (heap)".to_owned(),
                         MemoryKind::Machine(m) => format!(" ({:?})", m),
                     };
                     self.dump_alloc_helper(
@@ -91,19 +91,39 @@
 //! Note though that as a side-effect of creating a codegen units per
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
+//!
+//! Alternative Strategies
+//! ----------------------
+//! The per-module scheme described above can be replaced with
+//! `-Z cgu-partitioning`:
+//!
+//! - `size-balanced` distributes the per-module codegen units over
+//!   `-C codegen-units` codegen units such that their estimated sizes are as
+//!   even as possible, splitting modules that are larger than an even share.
+//!   This keeps all codegen units busy for about the same time in LLVM.
+//!
+//! - `stable` assigns each per-module codegen unit to one of
+//!   `-C codegen-units` codegen units based on a hash of its name. Unlike
+//!   merging the smallest codegen units, this doesn't depend on the sizes of
+//!   other modules, so an edit only invalidates the codegen units containing
+//!   the modules it actually touched. `-Z print-cgu-reuse-stats` shows how
+//!   many codegen units were invalidated by an incremental build.
 
 use std::cmp;
 use std::collections::hash_map::Entry;
+use std::hash::Hash;
 use std::sync::Arc;
 
 use rustc::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use rustc::middle::exported_symbols::SymbolExportLevel;
 use rustc::mir::mono::{CodegenUnit, CodegenUnitNameBuilder, Linkage, Visibility};
 use rustc::mir::mono::{InstantiationMode, MonoItem};
+use rustc::session::config::CguPartitioning;
 use rustc::ty::print::characteristic_def_id_of_type;
 use rustc::ty::query::Providers;
 use rustc::ty::{self, DefIdTree, InstanceDef, TyCtxt};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdSet, CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -118,6 +138,14 @@ pub enum PartitioningStrategy {
 
     /// Partition the whole crate into a fixed number of codegen units.
     FixedUnitCount(usize),
+
+    /// Partition the whole crate into a fixed number of codegen units of
+    /// roughly equal estimated size.
+    SizeBalanced(usize),
+
+    /// Partition the whole crate into a fixed number of codegen units, placing
+    /// each source-level module by a hash of its codegen unit name.
+    StableHash(usize),
 }
 
 // Anything we can't find a proper codegen unit for goes into this.
@@ -150,10 +178,23 @@ where
 
     // If the partitioning should produce a fixed count of codegen units, merge
     // until that count is reached.
-    if let PartitioningStrategy::FixedUnitCount(count) = strategy {
-        let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
-        merge_codegen_units(tcx, &mut initial_partitioning, count);
-        debug_dump(tcx, "POST MERGING:", initial_partitioning.codegen_units.iter());
+    match strategy {
+        PartitioningStrategy::PerModule => {}
+        PartitioningStrategy::FixedUnitCount(count) => {
+            let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
+            merge_codegen_units(tcx, &mut initial_partitioning, count);
+            debug_dump(tcx, "POST MERGING:", initial_partitioning.codegen_units.iter());
+        }
+        PartitioningStrategy::SizeBalanced(count) => {
+            let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_balance_cgus");
+            balance_codegen_units(tcx, &mut initial_partitioning, count);
+            debug_dump(tcx, "POST BALANCING:", initial_partitioning.codegen_units.iter());
+        }
+        PartitioningStrategy::StableHash(count) => {
+            let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_hash_cgus");
+            hash_codegen_units(tcx, &mut initial_partitioning, count);
+            debug_dump(tcx, "POST HASHING:", initial_partitioning.codegen_units.iter());
+        }
     }
 
     // In the next step, we use the inlining map to determine which additional
@@ -504,6 +545,118 @@ fn merge_codegen_units<'tcx>(
     }
 }
 
+fn balance_codegen_units<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    target_cgu_count: usize,
+) {
+    assert!(target_cgu_count >= 1);
+    let codegen_units = &mut initial_partitioning.codegen_units;
+
+    // Start off with a deterministic order, see `merge_codegen_units`.
+    codegen_units.sort_by_cached_key(|cgu| cgu.name().as_str());
+
+    // A codegen unit that is larger than an even share can't be balanced
+    // against the others, so split those into pieces first. Every other
+    // codegen unit stays in one piece, keeping the items of a module
+    // available to each other for inlining.
+    let total_size: usize = codegen_units.iter().map(|cgu| cgu.size_estimate()).sum();
+    let max_size = cmp::max(1, (total_size + target_cgu_count - 1) / target_cgu_count);
+
+    let mut pieces = Vec::new();
+    for mut cgu in codegen_units.drain(..) {
+        let mut items: Vec<_> = cgu.items_mut().drain().collect();
+        if cgu.size_estimate() <= max_size {
+            pieces.push((cgu.size_estimate(), items));
+            continue;
+        }
+
+        items.sort_by_cached_key(|&(mono_item, _)| mono_item.symbol_name(tcx));
+        let mut piece = Vec::new();
+        let mut piece_size = 0;
+        for (mono_item, linkage) in items {
+            let size = mono_item.size_estimate(tcx);
+            if !piece.is_empty() && piece_size + size > max_size {
+                pieces.push((piece_size, piece));
+                piece = Vec::new();
+                piece_size = 0;
+            }
+            piece.push((mono_item, linkage));
+            piece_size += size;
+        }
+        pieces.push((piece_size, piece));
+    }
+
+    // Place the pieces largest first, each into the currently smallest codegen
+    // unit. The sort is stable, so pieces of the same size keep their
+    // deterministic order from above.
+    pieces.sort_by_key(|&(size, _)| cmp::Reverse(size));
+
+    let cgu_name_builder = &mut CodegenUnitNameBuilder::new(tcx);
+    let mut balanced: Vec<CodegenUnit<'tcx>> = (0..cmp::min(target_cgu_count, pieces.len()))
+        .map(|index| CodegenUnit::new(numbered_codegen_unit_name(cgu_name_builder, index)))
+        .collect();
+    let mut sizes = vec![0; balanced.len()];
+
+    for (size, items) in pieces {
+        let (index, _) = sizes.iter().enumerate().min_by_key(|&(_, size)| *size).unwrap();
+        sizes[index] += size;
+        balanced[index].items_mut().extend(items);
+    }
+
+    balanced.iter_mut().for_each(|cgu| cgu.estimate_size(tcx));
+    *codegen_units = balanced;
+}
+
+fn hash_codegen_units<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    target_cgu_count: usize,
+) {
+    assert!(target_cgu_count >= 1);
+    let codegen_units = &mut initial_partitioning.codegen_units;
+
+    // Visit the codegen units in a deterministic order, so that the items of
+    // each bucket are always inserted in the same order.
+    codegen_units.sort_by_cached_key(|cgu| cgu.name().as_str());
+
+    let cgu_name_builder = &mut CodegenUnitNameBuilder::new(tcx);
+    let mut buckets: Vec<Option<CodegenUnit<'tcx>>> = (0..target_cgu_count).map(|_| None).collect();
+
+    // Human readable codegen unit names start with the crate name and
+    // disambiguator, which are left out of the hash so that the bucket of a
+    // module only depends on its path. Mangled names are hashes of the full
+    // name, which is just as stable across edits of other modules.
+    let crate_prefix = cgu_name_builder
+        .build_cgu_name_no_mangle(LOCAL_CRATE, &[] as &[&str], None::<&str>)
+        .as_str();
+
+    for mut cgu in codegen_units.drain(..) {
+        let name = cgu.name().as_str();
+        let module_path =
+            if name.starts_with(&*crate_prefix) { &name[crate_prefix.len()..] } else { &name[..] };
+        let mut hasher = StableHasher::new();
+        module_path.hash(&mut hasher);
+        let index = (hasher.finish::<u64>() % target_cgu_count as u64) as usize;
+
+        match buckets[index] {
+            Some(ref mut bucket) => {
+                bucket.modify_size_estimate(cgu.size_estimate());
+                for (k, v) in cgu.items_mut().drain() {
+                    bucket.items_mut().insert(k, v);
+                }
+                debug!("CodegenUnit {} hashed in to CodegenUnit {}", cgu.name(), bucket.name());
+            }
+            None => {
+                cgu.set_name(numbered_codegen_unit_name(cgu_name_builder, index));
+                buckets[index] = Some(cgu);
+            }
+        }
+    }
+
+    *codegen_units = buckets.into_iter().filter_map(|bucket| bucket).collect();
+}
+
 fn place_inlined_mono_items<'tcx>(
     initial_partitioning: PreInliningPartitioning<'tcx>,
     inlining_map: &InliningMap<'tcx>,
@@ -879,10 +1032,19 @@ fn collect_and_partition_mono_items(
     let (codegen_units, _) = tcx.sess.time("partition_and_assert_distinct_symbols", || {
         sync::join(
             || {
-                let strategy = if tcx.sess.opts.incremental.is_some() {
-                    PartitioningStrategy::PerModule
-                } else {
-                    PartitioningStrategy::FixedUnitCount(tcx.sess.codegen_units())
+                let strategy = match tcx.sess.opts.debugging_opts.cgu_partitioning {
+                    CguPartitioning::PerModule if tcx.sess.opts.incremental.is_some() => {
+                        PartitioningStrategy::PerModule
+                    }
+                    CguPartitioning::PerModule => {
+                        PartitioningStrategy::FixedUnitCount(tcx.sess.codegen_units())
+                    }
+                    CguPartitioning::SizeBalanced => {
+                        PartitioningStrategy::SizeBalanced(tcx.sess.codegen_units())
+                    }
+                    CguPartitioning::Stable => {
+                        PartitioningStrategy::StableHash(tcx.sess.codegen_units())
+                    }
                 };
 
                 partition(tcx, items.iter().cloned(), strategy, &inlining_map)
@@ -61,7 +61,7 @@ pub struct Qualifs<'a, 'mir, 'tcx> {
 
 impl Qualifs<'a, 'mir, 'tcx> {
     fn indirectly_mutable(&mut self, local: Local, location: Location) -> bool {
-        self.indirectly_mutable.seek_before(location);
+        self.indirectly_mutable.seek_before_primary_effect(location);
         self.indirectly_mutable.get().contains(local)
     }
 
@@ -73,7 +73,7 @@ impl Qualifs<'a, 'mir, 'tcx> {
             return false;
         }
 
-        self.needs_drop.cursor.seek_before(location);
+        self.needs_drop.cursor.seek_before_primary_effect(location);
         self.needs_drop.cursor.get().contains(local) || self.indirectly_mutable(local, location)
     }
 
@@ -85,7 +85,7 @@ impl Qualifs<'a, 'mir, 'tcx> {
             return false;
         }
 
-        self.has_mut_interior.cursor.seek_before(location);
+        self.has_mut_interior.cursor.seek_before_primary_effect(location);
         self.has_mut_interior.cursor.get().contains(local)
             || self.indirectly_mutable(local, location)
     }
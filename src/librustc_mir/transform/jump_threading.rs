@@ -0,0 +1,250 @@
+//! A pass that threads jumps through `SwitchInt` terminators whose outcome is already known in a
+//! predecessor.
+//!
+//! Code such as the `?` operator or `matches!` often produces MIR like the following, where the
+//! discriminant of `_1` is set to a known variant right before jumping to a block that reads it
+//! back and switches on it:
+//!
+//! ```text
+//! bb1: {
+//!     ((_1 as Break).0: i32) = move _4;
+//!     discriminant(_1) = 1;
+//!     goto -> bb3;
+//! }
+//! bb3: {
+//!     _5 = discriminant(_1);
+//!     switchInt(move _5) -> [0isize: bb4, otherwise: bb5];
+//! }
+//! ```
+//!
+//! Since the switch always goes to `bb5` when it is entered from `bb1`, we copy the statements of
+//! `bb3` into `bb1` and make `bb1` jump to `bb5` directly. `bb3` is left as is for its other
+//! predecessors, and will be removed by `SimplifyCfg` if it becomes unreachable.
+
+use crate::transform::{MirPass, MirSource};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::mir::*;
+use rustc::ty::{self, ParamEnv, Ty, TyCtxt};
+
+/// The maximum number of statements we are willing to copy into a predecessor to thread a jump
+/// through a block.
+const MAX_DUPLICATED_STATEMENTS: usize = 8;
+
+/// The maximum number of consecutive switches threaded for a single predecessor.
+const MAX_THREADING_DEPTH: usize = 8;
+
+pub struct JumpThreading;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, src: MirSource<'tcx>, body: &mut BodyAndCache<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 2 {
+            return;
+        }
+
+        let param_env = tcx.param_env(src.def_id());
+        let mut threaded = 0;
+
+        for pred in body.basic_blocks().indices() {
+            for _ in 0..MAX_THREADING_DEPTH {
+                let (switch_bb, target) = match find_threadable_jump(tcx, param_env, body, pred) {
+                    Some(opportunity) => opportunity,
+                    None => break,
+                };
+
+                debug!("threading {:?} -> {:?} through to {:?}", pred, switch_bb, target);
+                let statements = body[switch_bb].statements.clone();
+                let pred_data = &mut body.basic_blocks_mut()[pred];
+                pred_data.statements.extend(statements);
+                pred_data.terminator_mut().kind = TerminatorKind::Goto { target };
+                threaded += 1;
+            }
+        }
+
+        debug!("threaded {} jumps in {:?}", threaded, src.def_id());
+    }
+}
+
+/// A value that determines which edge a `SwitchInt` takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Switched {
+    /// The switch reads the value of this local.
+    Value(Local),
+
+    /// The switch reads the discriminant of this local.
+    Discriminant(Local),
+}
+
+impl Switched {
+    fn local(self) -> Local {
+        match self {
+            Switched::Value(local) | Switched::Discriminant(local) => local,
+        }
+    }
+}
+
+/// If `pred` ends with a `goto` to a block whose `SwitchInt` terminator always takes the same edge
+/// when entered from `pred`, returns that block and the target of that edge.
+fn find_threadable_jump(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+    pred: BasicBlock,
+) -> Option<(BasicBlock, BasicBlock)> {
+    let pred_data = &body[pred];
+    let switch_bb = match pred_data.terminator().kind {
+        TerminatorKind::Goto { target } if target != pred => target,
+        _ => return None,
+    };
+
+    let switch_data = &body[switch_bb];
+    if switch_data.is_cleanup != pred_data.is_cleanup
+        || switch_data.statements.len() > MAX_DUPLICATED_STATEMENTS
+    {
+        return None;
+    }
+
+    let (discr, switch_ty, values, targets) = match &switch_data.terminator().kind {
+        TerminatorKind::SwitchInt { discr, switch_ty, values, targets } => {
+            (discr, *switch_ty, values, targets)
+        }
+        _ => return None,
+    };
+
+    let discr = match discr {
+        Operand::Copy(place) | Operand::Move(place) => place.as_local()?,
+        Operand::Constant(_) => return None,
+    };
+
+    // Find out what the switch actually reads, looking through the statements of the switching
+    // block.
+    let (switched, switched_ty, def_index) = match switched_in_block(&switch_data.statements, discr)
+    {
+        Some((switched, index)) => (switched, body.local_decls[switched.local()].ty, index),
+        None => (Switched::Value(discr), switch_ty, switch_data.statements.len()),
+    };
+
+    // Nothing in the switching block may change the switched value before it is read, or the
+    // value of `discr` after it is assigned.
+    let (before_def, after_def) = switch_data.statements.split_at(def_index);
+    if before_def.iter().any(|stmt| clobbers(stmt, switched.local()))
+        || after_def.iter().skip(1).any(|stmt| clobbers(stmt, discr))
+    {
+        return None;
+    }
+
+    let value = known_value_at_end_of(tcx, param_env, pred_data, switched, switched_ty)?;
+    let (otherwise, targets) = targets.split_last().unwrap();
+    let target = values
+        .iter()
+        .zip(targets.iter())
+        .find(|&(&v, _)| v == value)
+        .map_or(*otherwise, |(_, &target)| target);
+
+    Some((switch_bb, target))
+}
+
+/// Returns what is read by the switch on `discr` if `discr` is assigned in `statements`, along
+/// with the index of that assignment.
+fn switched_in_block(statements: &[Statement<'_>], discr: Local) -> Option<(Switched, usize)> {
+    let (index, stmt) =
+        statements.iter().enumerate().rev().find(|(_, stmt)| mentions(stmt, discr))?;
+
+    let switched = match &stmt.kind {
+        StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(discr) => {
+            match rvalue {
+                Rvalue::Discriminant(place) => Switched::Discriminant(place.as_local()?),
+                Rvalue::Use(Operand::Copy(place)) | Rvalue::Use(Operand::Move(place)) => {
+                    Switched::Value(place.as_local()?)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    // `_2 = discriminant(_2)` reads and writes the same local.
+    if switched.local() == discr {
+        return None;
+    }
+
+    Some((switched, index))
+}
+
+/// Returns the value that the switch on `switched` will see when it is reached from the end of
+/// `block`, if `block` determines it.
+fn known_value_at_end_of(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    block: &BasicBlockData<'tcx>,
+    switched: Switched,
+    ty: Ty<'tcx>,
+) -> Option<u128> {
+    let local = switched.local();
+    let stmt = block.statements.iter().rev().find(|stmt| clobbers(stmt, local))?;
+
+    match (switched, &stmt.kind) {
+        (
+            Switched::Value(_),
+            StatementKind::Assign(box (place, Rvalue::Use(Operand::Constant(c)))),
+        ) if place.as_local() == Some(local) => c.literal.try_eval_bits(tcx, param_env, ty),
+
+        (Switched::Discriminant(_), StatementKind::SetDiscriminant { place, variant_index })
+            if place.as_local() == Some(local) =>
+        {
+            match ty.kind {
+                ty::Adt(adt_def, _) => {
+                    Some(adt_def.discriminant_for_variant(tcx, *variant_index).val)
+                }
+                _ => None,
+            }
+        }
+
+        (
+            Switched::Discriminant(_),
+            StatementKind::Assign(box (place, Rvalue::Aggregate(kind, _))),
+        ) if place.as_local() == Some(local) => match **kind {
+            AggregateKind::Adt(adt_def, variant_index, ..) if adt_def.is_enum() => {
+                Some(adt_def.discriminant_for_variant(tcx, variant_index).val)
+            }
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+/// Returns `true` if `stmt` may change the value of `local`, or if we cannot tell.
+///
+/// Besides direct uses of `local`, this includes writes through a pointer, which may alias it.
+fn clobbers(stmt: &Statement<'_>, local: Local) -> bool {
+    match &stmt.kind {
+        StatementKind::Assign(box (place, _))
+        | StatementKind::SetDiscriminant { box place, .. }
+            if place.is_indirect() =>
+        {
+            true
+        }
+
+        StatementKind::InlineAsm(..) => true,
+
+        _ => mentions(stmt, local),
+    }
+}
+
+/// Returns `true` if `local` appears anywhere in `stmt`.
+fn mentions(stmt: &Statement<'_>, local: Local) -> bool {
+    struct FindLocal {
+        local: Local,
+        found: bool,
+    }
+
+    impl Visitor<'_> for FindLocal {
+        fn visit_local(&mut self, &local: &Local, _: PlaceContext, _: Location) {
+            self.found |= local == self.local;
+        }
+    }
+
+    let mut visitor = FindLocal { local, found: false };
+    visitor.visit_statement(stmt, Location::START);
+    visitor.found
+}
@@ -27,6 +27,7 @@ pub mod erase_regions;
 pub mod generator;
 pub mod inline;
 pub mod instcombine;
+pub mod jump_threading;
 pub mod no_landing_pads;
 pub mod promote_consts;
 pub mod qualify_min_const_fn;
@@ -313,6 +314,7 @@ fn run_optimization_passes<'tcx>(
             &deaggregator::Deaggregator,
             &copy_prop::CopyPropagation,
             &simplify_branches::SimplifyBranches::new("after-copy-prop"),
+            &jump_threading::JumpThreading,
             &remove_noop_landing_pads::RemoveNoopLandingPads,
             &simplify::SimplifyCfg::new("after-remove-noop-landing-pads"),
             &simplify_try::SimplifyArmIdentity,
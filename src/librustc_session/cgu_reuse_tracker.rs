@@ -117,4 +117,38 @@ impl CguReuseTracker {
             }
         }
     }
+
+    /// Prints how many codegen units had to be recompiled and how many could
+    /// be reused, for `-Z print-cgu-reuse-stats`.
+    pub fn print_reuse_stats(&self) {
+        if let Some(ref data) = self.data {
+            let data = data.lock().unwrap();
+
+            let count = |kind| data.actual_reuse.values().filter(|&&reuse| reuse == kind).count();
+            let total = data.actual_reuse.len();
+            let recompiled = count(CguReuse::No);
+            let percentage =
+                if total == 0 { 0.0 } else { recompiled as f64 * 100.0 / total as f64 };
+            println!(
+                "cgu-reuse: {} codegen units, {} recompiled ({:.1}%), {} reused pre-LTO, \
+                 {} reused post-LTO",
+                total,
+                recompiled,
+                percentage,
+                count(CguReuse::PreLto),
+                count(CguReuse::PostLto)
+            );
+
+            let mut recompiled_names: Vec<_> = data
+                .actual_reuse
+                .iter()
+                .filter(|&(_, &reuse)| reuse == CguReuse::No)
+                .map(|(cgu_name, _)| cgu_name)
+                .collect();
+            recompiled_names.sort();
+            for cgu_name in recompiled_names {
+                println!("cgu-reuse recompiled: `{}`", cgu_name);
+            }
+        }
+    }
 }
@@ -1,5 +1,7 @@
-use rustc_data_structures::fx::FxHashSet;
+use crate::config::MonoItemSizesFormat;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
+use rustc_serialize::json;
 use rustc_target::abi::{Align, Size};
 use std::cmp::{self, Ordering};
 
@@ -45,9 +47,46 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// The code generated for a single mono item in a single codegen unit.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, RustcEncodable)]
+pub struct MonoItemSizeInfo {
+    /// The name of the symbol the item was emitted as.
+    pub symbol_name: String,
+    /// A human readable description of the item, including its generic arguments.
+    pub description: String,
+    /// The path of the (possibly generic) definition the item was instantiated from.
+    pub definition: String,
+    /// The crate containing `definition`.
+    pub krate: String,
+    pub codegen_unit: String,
+    /// The number of LLVM IR instructions, before any LLVM optimizations.
+    pub llvm_instructions: u64,
+    /// The size of the symbol in the emitted object file, if the object format records it.
+    pub object_bytes: u64,
+}
+
+/// The code generated for all instantiations of a definition, or for all items of a crate.
+#[derive(Clone, PartialEq, Eq, Debug, RustcEncodable)]
+pub struct MonoItemSizeTotals {
+    pub name: String,
+    pub instances: u64,
+    pub llvm_instructions: u64,
+    pub object_bytes: u64,
+}
+
+#[derive(RustcEncodable)]
+struct MonoItemSizeReport<'a> {
+    items: Vec<&'a MonoItemSizeInfo>,
+    definitions: Vec<MonoItemSizeTotals>,
+    crates: Vec<MonoItemSizeTotals>,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    mono_item_sizes: Lock<Vec<MonoItemSizeInfo>>,
+    /// Symbol sizes read back from object files, keyed by codegen unit and symbol name.
+    object_symbol_sizes: Lock<FxHashMap<(String, String), u64>>,
 }
 
 impl CodeStats {
@@ -190,4 +229,110 @@ impl CodeStats {
             }
         }
     }
+
+    pub fn record_mono_item_size(
+        &self,
+        symbol_name: String,
+        description: String,
+        definition: String,
+        krate: String,
+        codegen_unit: String,
+        llvm_instructions: u64,
+    ) {
+        self.mono_item_sizes.borrow_mut().push(MonoItemSizeInfo {
+            symbol_name,
+            description,
+            definition,
+            krate,
+            codegen_unit,
+            llvm_instructions,
+            object_bytes: 0,
+        });
+    }
+
+    pub fn record_object_symbol_size(&self, codegen_unit: &str, symbol_name: &str, bytes: u64) {
+        self.object_symbol_sizes
+            .borrow_mut()
+            .insert((codegen_unit.to_string(), symbol_name.to_string()), bytes);
+    }
+
+    pub fn print_mono_item_sizes(&self, format: MonoItemSizesFormat) {
+        let object_symbol_sizes = self.object_symbol_sizes.borrow();
+        let mut items = self.mono_item_sizes.borrow().clone();
+        for item in &mut items {
+            let key = (item.codegen_unit.clone(), item.symbol_name.clone());
+            item.object_bytes = object_symbol_sizes.get(&key).cloned().unwrap_or(0);
+        }
+
+        // Primary sort: large-to-small (object code, then IR).
+        // Secondary sort: description (dictionary order)
+        items.sort_by(|info1, info2| {
+            (info2.object_bytes, info2.llvm_instructions)
+                .cmp(&(info1.object_bytes, info1.llvm_instructions))
+                .then_with(|| info1.description.cmp(&info2.description))
+        });
+
+        let definitions = Self::mono_item_size_totals(&items, |info| &info.definition);
+        let crates = Self::mono_item_size_totals(&items, |info| &info.krate);
+
+        match format {
+            MonoItemSizesFormat::Json => {
+                let report =
+                    MonoItemSizeReport { items: items.iter().collect(), definitions, crates };
+                println!("{}", json::as_pretty_json(&report));
+            }
+            MonoItemSizesFormat::Table => {
+                for info in &items {
+                    println!(
+                        "print-mono-item-size item: `{}`: {} instructions, {} bytes, cgu: `{}`",
+                        info.description,
+                        info.llvm_instructions,
+                        info.object_bytes,
+                        info.codegen_unit
+                    );
+                }
+                for (kind, totals) in &[("definition", &definitions), ("crate", &crates)] {
+                    for total in totals.iter() {
+                        println!(
+                            "print-mono-item-size {}: `{}`: {} instances, {} instructions, \
+                             {} bytes",
+                            kind,
+                            total.name,
+                            total.instances,
+                            total.llvm_instructions,
+                            total.object_bytes
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sums up the sizes of `items` grouped by `key`, sorted large-to-small.
+    fn mono_item_size_totals(
+        items: &[MonoItemSizeInfo],
+        key: impl Fn(&MonoItemSizeInfo) -> &String,
+    ) -> Vec<MonoItemSizeTotals> {
+        let mut totals: FxHashMap<&String, MonoItemSizeTotals> = FxHashMap::default();
+        for info in items {
+            let name = key(info);
+            let total = totals.entry(name).or_insert_with(|| MonoItemSizeTotals {
+                name: name.clone(),
+                instances: 0,
+                llvm_instructions: 0,
+                object_bytes: 0,
+            });
+            total.instances += 1;
+            total.llvm_instructions += info.llvm_instructions;
+            total.object_bytes += info.object_bytes;
+        }
+
+        let mut totals: Vec<_> = totals.into_iter().map(|(_, total)| total).collect();
+        totals.sort_by(|total1, total2| {
+            (total2.object_bytes, total2.llvm_instructions)
+                .cmp(&(total1.object_bytes, total1.llvm_instructions))
+                .then_with(|| total1.name.cmp(&total2.name))
+        });
+        totals
+    }
 }
@@ -167,6 +167,30 @@ pub enum SymbolManglingVersion {
 
 impl_stable_hash_via_hash!(SymbolManglingVersion);
 
+/// The strategy used to partition mono items into codegen units, selected
+/// with `-Z cgu-partitioning`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum CguPartitioning {
+    /// Create one codegen unit per source-level module and, unless compiling
+    /// incrementally, merge the smallest ones until `-C codegen-units` is
+    /// reached.
+    PerModule,
+    /// Distribute the items over `-C codegen-units` codegen units of roughly
+    /// equal estimated size.
+    SizeBalanced,
+    /// Assign each per-module codegen unit to one of `-C codegen-units`
+    /// codegen units based on a hash of its name, so that editing one module
+    /// doesn't move items between the other codegen units.
+    Stable,
+}
+
+/// The output format of `-Z print-mono-item-sizes`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum MonoItemSizesFormat {
+    Table,
+    Json,
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfo {
     None,
@@ -1997,8 +2021,8 @@ impl PpMode {
 /// how the hash should be calculated when adding a new command-line argument.
 crate mod dep_tracking {
     use super::{
-        CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, Sanitizer, SwitchWithOptPath, SymbolManglingVersion,
+        CFGuard, CguPartitioning, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli,
+        OptLevel, OutputTypes, Passes, Sanitizer, SwitchWithOptPath, SymbolManglingVersion,
     };
     use crate::lint;
     use crate::utils::NativeLibraryKind;
@@ -2076,6 +2100,7 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
     impl_dep_tracking_hash_via_hash!(SwitchWithOptPath);
     impl_dep_tracking_hash_via_hash!(SymbolManglingVersion);
+    impl_dep_tracking_hash_via_hash!(CguPartitioning);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
@@ -283,12 +283,16 @@ macro_rules! options {
             Some("one of: `disabled`, `trampolines`, or `aliases`");
         pub const parse_symbol_mangling_version: Option<&str> =
             Some("either `legacy` or `v0` (RFC 2603)");
+        pub const parse_mono_item_sizes_format: Option<&str> =
+            Some("either `table` or `json`");
+        pub const parse_cgu_partitioning: Option<&str> =
+            Some("one of: `per-module`, `size-balanced`, or `stable`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-            SymbolManglingVersion, CFGuard};
+            SymbolManglingVersion, CFGuard, MonoItemSizesFormat, CguPartitioning};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -619,6 +623,28 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_mono_item_sizes_format(
+            slot: &mut Option<MonoItemSizesFormat>,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = match v {
+                None | Some("table") => Some(MonoItemSizesFormat::Table),
+                Some("json") => Some(MonoItemSizesFormat::Json),
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_cgu_partitioning(slot: &mut CguPartitioning, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("per-module") => CguPartitioning::PerModule,
+                Some("size-balanced") => CguPartitioning::SizeBalanced,
+                Some("stable") => CguPartitioning::Stable,
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -826,6 +852,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print layout information for each type encountered"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
+    print_mono_item_sizes: Option<MonoItemSizesFormat> = (None,
+        parse_mono_item_sizes_format, [UNTRACKED],
+        "print the LLVM IR instruction count and object code size of each codegened item, \
+        aggregated by generic definition and by crate (`table` or `json`, default: `table`); \
+        disables reuse of incrementally cached codegen units"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "set the MIR optimization level (0-3, default: 1)"),
     mutable_noalias: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -968,4 +999,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "link the `.rlink` file generated by `-Z no-link`"),
     new_llvm_pass_manager: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "use new LLVM pass manager"),
+    cgu_partitioning: CguPartitioning = (CguPartitioning::PerModule,
+        parse_cgu_partitioning, [TRACKED],
+        "the strategy used to partition items into codegen units \
+        (`per-module`, `size-balanced` or `stable`, default: `per-module`)"),
+    print_cgu_reuse_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print how many codegen units were reused from the incremental cache and how many \
+         had to be recompiled"),
 }
@@ -1045,7 +1045,9 @@ fn build_session_(
     });
     let working_dir = file_path_mapping.map_prefix(working_dir);
 
-    let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph {
+    let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph
+        || sopts.debugging_opts.print_cgu_reuse_stats
+    {
         CguReuseTracker::new()
     } else {
         CguReuseTracker::new_disabled()
@@ -207,8 +207,10 @@ symbols! {
         concat_idents,
         conservative_impl_trait,
         console,
+        const_allocate,
         const_compare_raw_pointers,
         const_constructor,
+        const_deallocate,
         const_eval_limit,
         const_extern_fn,
         const_fn,
@@ -651,6 +653,7 @@ symbols! {
         rustc_peek_maybe_init,
         rustc_peek_maybe_uninit,
         rustc_peek_indirectly_mutable,
+        rustc_peek_liveness,
         rustc_private,
         rustc_proc_macro_decls,
         rustc_promotable,
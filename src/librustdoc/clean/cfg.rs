@@ -8,6 +8,7 @@ use std::mem;
 use std::ops;
 
 use rustc_ast::ast::{LitKind, MetaItem, MetaItemKind, NestedMetaItem};
+use rustc_data_structures::fx::FxHashSet;
 use rustc_feature::Features;
 use rustc_session::parse::ParseSess;
 use rustc_span::symbol::{sym, Symbol};
@@ -117,6 +118,27 @@ impl Cfg {
         }
     }
 
+    /// Removes the `hidden` configuration options from the configuration wherever they appear,
+    /// along with the `not` around them, or returns `None` if nothing else is left of it. For
+    /// example, `any(unix, not(test))` without `test` is `unix`.
+    ///
+    /// The options are only matched exactly, so hiding `feature` doesn't hide `feature = "std"`.
+    pub fn without(&self, hidden: &FxHashSet<Cfg>) -> Option<Cfg> {
+        match *self {
+            Cfg::Cfg(..) if hidden.contains(self) => None,
+            Cfg::False | Cfg::True | Cfg::Cfg(..) => Some(self.clone()),
+            Cfg::Not(ref child) => child.without(hidden).map(|child| !child),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                let mut sub_cfgs = sub_cfgs.iter().filter_map(|sub_cfg| sub_cfg.without(hidden));
+                let first = sub_cfgs.next()?;
+                Some(match *self {
+                    Cfg::All(..) => sub_cfgs.fold(first, |x, y| x & y),
+                    _ => sub_cfgs.fold(first, |x, y| x | y),
+                })
+            }
+        }
+    }
+
     /// Whether the configuration consists of just `Cfg` or `Not`.
     fn is_simple(&self) -> bool {
         match *self {
@@ -182,7 +204,7 @@ impl Cfg {
 
     fn should_use_with_in_description(&self) -> bool {
         match *self {
-            Cfg::Cfg(name, _) if name == sym::target_feature => true,
+            Cfg::Cfg(name, _) if name == sym::target_feature || name == sym::feature => true,
             _ => false,
         }
     }
@@ -413,6 +435,13 @@ impl<'a> fmt::Display for Html<'a> {
                             return write!(fmt, "target feature <code>{}</code>", feat);
                         }
                     }
+                    ("feature", Some(feat)) => {
+                        if self.1 {
+                            return write!(fmt, "<code>{}</code>", feat);
+                        } else {
+                            return write!(fmt, "crate feature <code>{}</code>", feat);
+                        }
+                    }
                     _ => "",
                 };
                 if !human_readable.is_empty() {
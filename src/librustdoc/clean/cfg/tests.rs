@@ -353,6 +353,7 @@ fn test_render_short_html() {
                 .render_short_html(),
             "x86-64 and <code>sse2</code>"
         );
+        assert_eq!(name_value_cfg("feature", "serde").render_short_html(), "<code>serde</code>");
     })
 }
 
@@ -432,5 +433,38 @@ fn test_render_long_html() {
             "This is supported on <strong>x86-64 and target feature \
             <code>sse2</code></strong> only."
         );
+        assert_eq!(
+            name_value_cfg("feature", "serde").render_long_html(),
+            "This is supported with <strong>crate feature <code>serde</code></strong> only."
+        );
+    })
+}
+
+#[test]
+fn test_without() {
+    with_default_globals(|| {
+        let hidden: FxHashSet<Cfg> =
+            vec![word_cfg("test"), name_value_cfg("feature", "std")].into_iter().collect();
+
+        assert_eq!(word_cfg("test").without(&hidden), None);
+        assert_eq!(word_cfg("unix").without(&hidden), Some(word_cfg("unix")));
+        assert_eq!(name_value_cfg("feature", "std").without(&hidden), None);
+        assert_eq!(
+            name_value_cfg("feature", "alloc").without(&hidden),
+            Some(name_value_cfg("feature", "alloc"))
+        );
+        assert_eq!(word_cfg("feature").without(&hidden), Some(word_cfg("feature")));
+        assert_eq!((!word_cfg("test")).without(&hidden), None);
+        assert_eq!((!word_cfg("unix")).without(&hidden), Some(!word_cfg("unix")));
+        assert_eq!((word_cfg("unix") & word_cfg("test")).without(&hidden), Some(word_cfg("unix")));
+        assert_eq!(
+            (word_cfg("unix") | name_value_cfg("feature", "std")).without(&hidden),
+            Some(word_cfg("unix"))
+        );
+        assert_eq!((word_cfg("test") & name_value_cfg("feature", "std")).without(&hidden), None);
+        assert_eq!(
+            (word_cfg("unix") & (word_cfg("windows") | word_cfg("test"))).without(&hidden),
+            Some(word_cfg("unix") & word_cfg("windows"))
+        );
     })
 }
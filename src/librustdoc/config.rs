@@ -29,6 +29,8 @@ use crate::theme;
 pub enum OutputFormat {
     Json,
     Html,
+    Markdown,
+    Man,
 }
 
 impl OutputFormat {
@@ -38,6 +40,15 @@ impl OutputFormat {
             _ => false,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Html => "html",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Man => "man",
+        }
+    }
 }
 
 impl TryFrom<&str> for OutputFormat {
@@ -47,11 +58,46 @@ impl TryFrom<&str> for OutputFormat {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{}`", value)),
         }
     }
 }
 
+/// How the items are grouped in the `--show-coverage` report.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CoverageGroup {
+    File,
+    Kind,
+    Module,
+}
+
+impl TryFrom<&str> for CoverageGroup {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "file" => Ok(CoverageGroup::File),
+            "kind" => Ok(CoverageGroup::Kind),
+            "module" => Ok(CoverageGroup::Module),
+            _ => Err(format!("unknown coverage grouping `{}`", value)),
+        }
+    }
+}
+
+/// Options for the `--show-coverage` report.
+#[derive(Clone, Debug)]
+pub struct CoverageOptions {
+    /// How to group the items in the report.
+    pub group_by: CoverageGroup,
+    /// The minimum percentage of documented items, below which rustdoc fails.
+    pub threshold: Option<f64>,
+    /// The minimum percentage of items with a code example, among the items that should have
+    /// one, below which rustdoc fails.
+    pub examples_threshold: Option<f64>,
+}
+
 /// Configuration options for rustdoc.
 #[derive(Clone)]
 pub struct Options {
@@ -113,6 +159,9 @@ pub struct Options {
     /// For example, using ignore-foo to ignore running the doctest on any target that
     /// contains "foo" as a substring
     pub enable_per_target_ignores: bool,
+    /// Whether to compile compatible doctests together into a single binary, instead of compiling
+    /// each of them separately.
+    pub merge_doctests: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from $sysroot/bin/rustc.
@@ -127,6 +176,11 @@ pub struct Options {
     pub document_private: bool,
     /// Document items that have `doc(hidden)`.
     pub document_hidden: bool,
+    /// Whether to show the `#[cfg]` attributes of items in their `doc(cfg)` badges.
+    pub doc_cfg_auto: bool,
+    /// The cfgs to leave out of the badges inferred from `#[cfg]` attributes, as `name` or
+    /// `name="value"`.
+    pub doc_cfg_hide: Vec<(String, Option<String>)>,
     /// Any passes manually selected by the user.
     ///
     /// Be aware: This option can come both from the CLI and from crate attributes!
@@ -137,13 +191,18 @@ pub struct Options {
     /// Whether to run the `calculate-doc-coverage` pass, which counts the number of public items
     /// with and without documentation.
     pub show_coverage: bool,
+    /// Options for the report of `--show-coverage`.
+    pub coverage: CoverageOptions,
+    /// A JSON description of a previous version of the crate, written by `--output-format json`,
+    /// whose public API should be compared with the one of the crate being documented.
+    pub compare_api: Option<PathBuf>,
 
     // Options that alter generated documentation pages
     /// Crate version to note on the sidebar of generated docs.
     pub crate_version: Option<String>,
     /// Collected options specific to outputting final pages.
     pub render_options: RenderOptions,
-    /// Output format rendering (used only for "show-coverage" option for the moment)
+    /// The format to render the documentation in, or the format of the `--show-coverage` report.
     pub output_format: Option<OutputFormat>,
 }
 
@@ -179,14 +238,19 @@ impl fmt::Debug for Options {
             .field("default_passes", &self.default_passes)
             .field("document_private", &self.document_private)
             .field("document_hidden", &self.document_hidden)
+            .field("doc_cfg_auto", &self.doc_cfg_auto)
+            .field("doc_cfg_hide", &self.doc_cfg_hide)
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
             .field("show_coverage", &self.show_coverage)
+            .field("coverage", &self.coverage)
+            .field("compare_api", &self.compare_api)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .field("runtool", &self.runtool)
             .field("runtool_args", &self.runtool_args)
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
+            .field("merge_doctests", &self.merge_doctests)
             .finish()
     }
 }
@@ -250,6 +314,18 @@ pub struct RenderOptions {
     pub generate_search_filter: bool,
     /// Option (disabled by default) to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// Document items that have lower than `pub` visibility.
+    ///
+    /// Be aware: This option can come both from the CLI and from crate attributes!
+    pub document_private: bool,
+    /// Whether the identifiers in source pages should link to the items they refer to.
+    pub generate_link_to_definition: bool,
+    /// Whether to index the full text of the documentation for the search, and not just the names
+    /// and summaries of the items.
+    pub full_text_search: bool,
+    /// Source files, like the crate's examples and tests, to look for calls to the crate's
+    /// functions and methods in, to show them on the pages of these items.
+    pub scrape_examples: Vec<PathBuf>,
 }
 
 impl Options {
@@ -473,6 +549,45 @@ impl Options {
             });
 
         let show_coverage = matches.opt_present("show-coverage");
+        let compare_api = matches.opt_str("compare-api").map(PathBuf::from);
+
+        if show_coverage && compare_api.is_some() {
+            diag.struct_err("--show-coverage and --compare-api cannot be used together").emit();
+            return Err(1);
+        }
+
+        let group_by = match matches.opt_str("coverage-group-by") {
+            Some(s) => match CoverageGroup::try_from(s.as_str()) {
+                Ok(group_by) => group_by,
+                Err(e) => {
+                    diag.struct_err(&e).emit();
+                    return Err(1);
+                }
+            },
+            None => CoverageGroup::File,
+        };
+        let threshold = match parse_coverage_threshold(&matches, "coverage-threshold") {
+            Ok(threshold) => threshold,
+            Err(e) => {
+                diag.struct_err(&e).emit();
+                return Err(1);
+            }
+        };
+        let examples_threshold =
+            match parse_coverage_threshold(&matches, "coverage-examples-threshold") {
+                Ok(threshold) => threshold,
+                Err(e) => {
+                    diag.struct_err(&e).emit();
+                    return Err(1);
+                }
+            };
+        for flag in &["coverage-group-by", "coverage-threshold", "coverage-examples-threshold"] {
+            if matches.opt_present(flag) && !show_coverage {
+                diag.struct_err(&format!("--{} requires --show-coverage", flag)).emit();
+                return Err(1);
+            }
+        }
+        let coverage = CoverageOptions { group_by, threshold, examples_threshold };
 
         let default_passes = if matches.opt_present("no-defaults") {
             passes::DefaultPassOption::None
@@ -494,14 +609,30 @@ impl Options {
         let output_format = match matches.opt_str("output-format") {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(o) => {
-                    if o.is_json() && !show_coverage {
-                        diag.struct_err("json output format isn't supported for doc generation")
-                            .emit();
+                    if o != OutputFormat::Html
+                        && !show_coverage
+                        && compare_api.is_none()
+                        && !nightly_options::is_unstable_enabled(&matches)
+                    {
+                        diag.struct_err(&format!(
+                            "the -Z unstable-options flag must be passed to enable \
+                             --output-format {} for doc generation",
+                            o.as_str()
+                        ))
+                        .emit();
                         return Err(1);
                     } else if !o.is_json() && show_coverage {
-                        diag.struct_err(
-                            "html output format isn't supported for the --show-coverage option",
-                        )
+                        diag.struct_err(&format!(
+                            "{} output format isn't supported for the --show-coverage option",
+                            o.as_str()
+                        ))
+                        .emit();
+                        return Err(1);
+                    } else if !o.is_json() && compare_api.is_some() {
+                        diag.struct_err(&format!(
+                            "{} output format isn't supported for the --compare-api option",
+                            o.as_str()
+                        ))
                         .emit();
                         return Err(1);
                     }
@@ -531,6 +662,10 @@ impl Options {
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let generate_redirect_pages = matches.opt_present("generate-redirect-pages");
+        let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
+        let full_text_search = matches.opt_present("full-text-search");
+        let scrape_examples =
+            matches.opt_strs("scrape-examples").iter().map(PathBuf::from).collect();
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
         let debugging_options_strs = matches.opt_strs("Z");
@@ -539,8 +674,17 @@ impl Options {
         let runtool = matches.opt_str("runtool");
         let runtool_args = matches.opt_strs("runtool-arg");
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
+        let merge_doctests = matches.opt_present("merge-doctests");
         let document_private = matches.opt_present("document-private-items");
         let document_hidden = matches.opt_present("document-hidden-items");
+        let doc_cfg_auto = matches.opt_present("doc-cfg-auto");
+        let doc_cfg_hide = match parse_doc_cfg_hide(&matches) {
+            Ok(hide) => hide,
+            Err(err) => {
+                diag.struct_err(err).emit();
+                return Err(1);
+            }
+        };
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -569,14 +713,19 @@ impl Options {
             default_passes,
             document_private,
             document_hidden,
+            doc_cfg_auto,
+            doc_cfg_hide,
             manual_passes,
             display_warnings,
             show_coverage,
+            coverage,
+            compare_api,
             crate_version,
             persist_doctests,
             runtool,
             runtool_args,
             enable_per_target_ignores,
+            merge_doctests,
             test_builder,
             render_options: RenderOptions {
                 output,
@@ -597,6 +746,10 @@ impl Options {
                 markdown_playground_url,
                 generate_search_filter,
                 generate_redirect_pages,
+                document_private,
+                generate_link_to_definition,
+                full_text_search,
+                scrape_examples,
             },
             output_format,
         })
@@ -614,7 +767,11 @@ fn check_deprecated_options(matches: &getopts::Matches, diag: &rustc_errors::Han
 
     for flag in deprecated_flags.iter() {
         if matches.opt_present(flag) {
-            if *flag == "output-format" && matches.opt_present("show-coverage") {
+            if *flag == "output-format"
+                && (matches.opt_present("show-coverage")
+                    || matches.opt_present("compare-api")
+                    || matches.opt_str("output-format").as_deref() == Some("json"))
+            {
                 continue;
             }
             let mut err =
@@ -659,3 +816,34 @@ fn parse_extern_html_roots(
 
     Ok(externs)
 }
+
+/// Extracts the percentage given to the `--coverage-threshold` or `--coverage-examples-threshold`
+/// option `name`, if any.
+fn parse_coverage_threshold(matches: &getopts::Matches, name: &str) -> Result<Option<f64>, String> {
+    match matches.opt_str(name) {
+        Some(s) => match s.trim_end_matches('%').parse::<f64>() {
+            Ok(percentage) if percentage >= 0.0 && percentage <= 100.0 => Ok(Some(percentage)),
+            _ => Err(format!("--{} must be a percentage between 0 and 100, found `{}`", name, s)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Extracts `--doc-cfg-hide` arguments from `matches` and returns the names and values of the
+/// cfgs. The value may be given with or without quotes, like `feature="std"` or `feature=std`.
+fn parse_doc_cfg_hide(
+    matches: &getopts::Matches,
+) -> Result<Vec<(String, Option<String>)>, &'static str> {
+    let mut cfgs = Vec::new();
+    for arg in &matches.opt_strs("doc-cfg-hide") {
+        let mut parts = arg.splitn(2, '=');
+        let name = parts.next().unwrap().trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err("--doc-cfg-hide must be of the form name or name=\"value\"");
+        }
+        let value = parts.next().map(|value| value.trim().trim_matches('"').to_string());
+        cfgs.push((name.to_string(), value));
+    }
+
+    Ok(cfgs)
+}
@@ -19,7 +19,7 @@ use rustc_attr as attr;
 use rustc_errors::emitter::{Emitter, EmitterWriter};
 use rustc_errors::json::JsonEmitter;
 use rustc_span::source_map;
-use rustc_span::symbol::sym;
+use rustc_span::symbol::{sym, Symbol};
 use rustc_span::DUMMY_SP;
 
 use rustc_data_structures::sync::{self, Lrc};
@@ -28,9 +28,10 @@ use std::mem;
 use std::rc::Rc;
 
 use crate::clean;
+use crate::clean::cfg::Cfg;
 use crate::clean::{AttributesExt, MAX_DEF_ID};
-use crate::config::{Options as RustdocOptions, RenderOptions};
-use crate::html::render::RenderInfo;
+use crate::config::{CoverageOptions, Options as RustdocOptions, RenderOptions};
+use crate::html::render::{span_map, RenderInfo};
 
 use crate::passes::{self, Condition::*, ConditionalPass};
 
@@ -65,6 +66,12 @@ pub struct DocContext<'tcx> {
     // FIXME(eddyb) make this a `ty::TraitRef<'tcx>` set.
     pub generated_synthetics: RefCell<FxHashSet<(Ty<'tcx>, DefId)>>,
     pub auto_traits: Vec<DefId>,
+    /// Whether to show the `#[cfg]` attributes of items in their `doc(cfg)` badges.
+    pub doc_cfg_auto: bool,
+    /// The cfgs to leave out of the badges inferred from `#[cfg]` attributes.
+    pub doc_cfg_hide: FxHashSet<Cfg>,
+    /// Options for the report of `--show-coverage`.
+    pub coverage: CoverageOptions,
 }
 
 impl<'tcx> DocContext<'tcx> {
@@ -225,9 +232,12 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         mut default_passes,
         mut document_private,
         document_hidden,
+        doc_cfg_auto,
+        doc_cfg_hide,
         mut manual_passes,
+        coverage,
         display_warnings,
-        render_options,
+        mut render_options,
         output_format,
         ..
     } = options;
@@ -387,6 +397,11 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 let mut renderinfo = RenderInfo::default();
                 renderinfo.access_levels = access_levels;
                 renderinfo.output_format = output_format;
+                if render_options.generate_link_to_definition
+                    && !output_format.map_or(false, |o| o.is_json())
+                {
+                    renderinfo.span_map = span_map::collect_spans(tcx);
+                }
 
                 let mut ctxt = DocContext {
                     tcx,
@@ -407,6 +422,14 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                         .cloned()
                         .filter(|trait_def_id| tcx.trait_is_auto(*trait_def_id))
                         .collect(),
+                    doc_cfg_auto,
+                    doc_cfg_hide: doc_cfg_hide
+                        .iter()
+                        .map(|(name, value)| {
+                            Cfg::Cfg(Symbol::intern(name), value.as_deref().map(Symbol::intern))
+                        })
+                        .collect(),
+                    coverage,
                 };
                 debug!("crate: {:?}", tcx.hir().krate());
 
@@ -497,6 +520,9 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
 
                 ctxt.sess().abort_if_errors();
 
+                // `#![doc(document_private_items)]` can turn this on from inside the crate.
+                render_options.document_private = document_private;
+
                 (krate, ctxt.renderinfo.into_inner(), render_options)
             })
         })
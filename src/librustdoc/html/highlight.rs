@@ -12,11 +12,12 @@ use std::io;
 use std::io::prelude::*;
 
 use rustc_ast::token::{self, Token};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_parse::lexer;
 use rustc_session::parse::ParseSess;
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::{kw, sym};
-use rustc_span::{FileName, Span};
+use rustc_span::{BytePos, FileName, Span};
 
 /// Highlights `src`, returning the HTML output.
 pub fn render_with_highlighting(
@@ -24,6 +25,30 @@ pub fn render_with_highlighting(
     class: Option<&str>,
     playground_button: Option<&str>,
     tooltip: Option<(&str, &str)>,
+) -> String {
+    highlight(src, class, playground_button, tooltip, None)
+}
+
+/// Highlights the contents of a source file for its page, adding `links` to its identifiers.
+crate fn render_source(src: &str, links: &SourceLinks) -> String {
+    highlight(src, None, None, None, Some(links))
+}
+
+/// Links to add to the identifiers of a source file, by the byte range of each identifier.
+#[derive(Default)]
+crate struct SourceLinks {
+    /// The URL of the definition that each identifier refers to.
+    crate links: FxHashMap<(u32, u32), String>,
+    /// For the identifiers that define an item, the URLs of the places where the item is used.
+    crate usages: FxHashMap<(u32, u32), Vec<String>>,
+}
+
+fn highlight(
+    src: &str,
+    class: Option<&str>,
+    playground_button: Option<&str>,
+    tooltip: Option<(&str, &str)>,
+    links: Option<&SourceLinks>,
 ) -> String {
     debug!("highlighting: ================\n{}\n==============", src);
     let mut out = Vec::new();
@@ -42,8 +67,9 @@ pub fn render_with_highlighting(
         .source_map()
         .new_source_file(FileName::Custom(String::from("rustdoc-highlighting")), src.to_owned());
     let highlight_result = rustc_driver::catch_fatal_errors(|| {
+        let file_start = sf.start_pos;
         let lexer = lexer::StringReader::new(&sess, sf, None);
-        let mut classifier = Classifier::new(lexer, sess.source_map());
+        let mut classifier = Classifier::new(lexer, sess.source_map(), file_start, links);
 
         let mut highlighted_source = vec![];
         if classifier.write_source(&mut highlighted_source).is_err() {
@@ -77,6 +103,9 @@ struct Classifier<'a> {
     lexer: lexer::StringReader<'a>,
     peek_token: Option<Token>,
     source_map: &'a SourceMap,
+    /// The position of the start of the highlighted source in `source_map`.
+    file_start: BytePos,
+    links: Option<&'a SourceLinks>,
 
     // State of the classifier.
     in_attribute: bool,
@@ -166,11 +195,18 @@ impl From<io::Error> for HighlightError {
 }
 
 impl<'a> Classifier<'a> {
-    fn new(lexer: lexer::StringReader<'a>, source_map: &'a SourceMap) -> Classifier<'a> {
+    fn new(
+        lexer: lexer::StringReader<'a>,
+        source_map: &'a SourceMap,
+        file_start: BytePos,
+        links: Option<&'a SourceLinks>,
+    ) -> Classifier<'a> {
         Classifier {
             lexer,
             peek_token: None,
             source_map,
+            file_start,
+            links,
             in_attribute: false,
             in_macro: false,
             in_macro_nonterminal: false,
@@ -389,11 +425,37 @@ impl<'a> Classifier<'a> {
 
         // Anything that didn't return above is the simple case where we the
         // class just spans a single token, so we can use the `string` method.
-        out.string(Escape(&self.snip(token.span)), klass)?;
+        let snip = self.snip(token.span);
+        match self.link(&token, &snip) {
+            Some(link) => out.string(link, klass)?,
+            None => out.string(Escape(&snip), klass)?,
+        }
 
         Ok(())
     }
 
+    /// Returns the identifier `snip` wrapped in a link to its definition, or in a list of its
+    /// usages if it defines an item.
+    fn link(&self, token: &Token, snip: &str) -> Option<String> {
+        let links = match (&token.kind, self.links) {
+            (token::Ident(..), Some(links)) => links,
+            _ => return None,
+        };
+        let range = ((token.span.lo() - self.file_start).0, (token.span.hi() - self.file_start).0);
+
+        if let Some(href) = links.links.get(&range) {
+            Some(format!("<a href=\"{}\">{}</a>", Escape(href), Escape(snip)))
+        } else if let Some(usages) = links.usages.get(&range) {
+            Some(format!(
+                "<a class=\"definition\" data-usages=\"{}\">{}</a>",
+                Escape(&usages.join(" ")),
+                Escape(snip)
+            ))
+        } else {
+            None
+        }
+    }
+
     // Helper function to get a snippet from the source_map.
     fn snip(&self, sp: Span) -> String {
         self.source_map.span_to_snippet(sp).unwrap()
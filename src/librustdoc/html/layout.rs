@@ -113,6 +113,7 @@ pub fn render<T: Print, S: Print>(
     <script>\
         window.rootPath = \"{root_path}\";\
         window.currentCrate = \"{krate}\";\
+        window.resourcesSuffix = \"{suffix}\";\
     </script>\
     <script src=\"{root_path}aliases{suffix}.js\"></script>\
     <script src=\"{static_root_path}main{suffix}.js\"></script>\
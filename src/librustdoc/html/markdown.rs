@@ -38,7 +38,7 @@ use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag};
 #[cfg(test)]
 mod tests;
 
-fn opts() -> Options {
+crate fn opts() -> Options {
     Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES
 }
 
@@ -144,6 +144,21 @@ fn map_line(s: &str) -> Line<'_> {
 /// Convert chars from a title for an id.
 ///
 /// "Hello, world!" -> "hello-world"
+/// Removes the lines that are hidden from the documentation, like `# fn main() {`, from Rust code.
+crate fn strip_hidden_lines(code: &str) -> String {
+    code.lines().filter_map(|line| map_line(line).for_html()).collect::<Vec<_>>().join("\n")
+}
+
+/// Whether a code block of the given kind is Rust code, which is the case by default.
+crate fn is_rust_code_block(kind: &CodeBlockKind<'_>) -> bool {
+    match *kind {
+        CodeBlockKind::Fenced(ref lang) if !lang.is_empty() => {
+            LangString::parse(lang, ErrorCodes::No, false).rust
+        }
+        _ => true,
+    }
+}
+
 fn slugify(c: char) -> Option<char> {
     if c.is_alphanumeric() || c == '-' || c == '_' {
         if c.is_ascii() { Some(c.to_ascii_lowercase()) } else { Some(c) }
@@ -874,6 +889,34 @@ pub fn plain_summary_line(md: &str) -> String {
     s
 }
 
+/// Splits the text of the given Markdown into lowercase words, for the full-text search index.
+///
+/// Code blocks are left out, but inline code is kept.
+crate fn plain_text_words(md: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut in_code_block = false;
+    for event in Parser::new_ext(md, opts()) {
+        let text = match event {
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                continue;
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                in_code_block = false;
+                continue;
+            }
+            Event::Text(text) | Event::Code(text) if !in_code_block => text,
+            _ => continue,
+        };
+        words.extend(
+            text.split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|word| !word.is_empty())
+                .map(|word| word.to_lowercase()),
+        );
+    }
+    words
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -1,4 +1,4 @@
-use super::plain_summary_line;
+use super::{plain_summary_line, plain_text_words};
 use super::{ErrorCodes, IdMap, Ignore, LangString, Markdown, MarkdownHtml};
 use rustc_span::edition::{Edition, DEFAULT_EDITION};
 use std::cell::RefCell;
@@ -223,6 +223,20 @@ fn test_plain_summary_line() {
     t("## header", "header");
 }
 
+#[test]
+fn test_plain_text_words() {
+    fn t(input: &str, expect: &[&str]) {
+        let output = plain_text_words(input);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("Hello [Rust](https://www.rust-lang.org)!", &["hello", "rust"]);
+    t("# Panics\n\nPanics if `self.len() == 0`.", &["panics", "panics", "if", "self", "len", "0"]);
+    t("Some `snake_case` text", &["some", "snake_case", "text"]);
+    t("Before\n\n```\nlet x = 1;\n```\n\nafter", &["before", "after"]);
+    t("Üppig *fett*", &["üppig", "fett"]);
+}
+
 #[test]
 fn test_markdown_html_escape() {
     fn t(input: &str, expect: &str) {
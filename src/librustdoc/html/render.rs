@@ -37,6 +37,7 @@ use std::fmt::{self, Formatter, Write};
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::io::{self, BufReader};
+use std::mem;
 use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
 use std::str;
@@ -77,9 +78,12 @@ use crate::html::{highlight, layout, static_files};
 mod tests;
 
 mod cache;
+mod scrape_examples;
+crate mod span_map;
 
-use cache::Cache;
+crate use cache::Cache;
 crate use cache::ExternalLocation::{self, *};
+use scrape_examples::{CallTarget, ScrapedExamples};
 
 /// A pair of name and its optional document.
 pub type NameDoc = (String, Option<String>);
@@ -201,6 +205,9 @@ crate struct SharedContext {
     pub edition: Edition,
     pub codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// The calls to the crate's functions and methods found in the files given to
+    /// `--scrape-examples`.
+    scraped_examples: ScrapedExamples,
 }
 
 impl Context {
@@ -247,7 +254,7 @@ pub struct Impl {
 }
 
 impl Impl {
-    fn inner_impl(&self) -> &clean::Impl {
+    crate fn inner_impl(&self) -> &clean::Impl {
         match self.impl_item.inner {
             clean::ImplItem(ref impl_) => impl_,
             _ => panic!("non-impl item found in impl"),
@@ -271,6 +278,8 @@ pub struct RenderInfo {
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
     pub output_format: Option<OutputFormat>,
+    /// The links to add to source pages, if `--generate-link-to-definition` was passed.
+    crate span_map: span_map::SpanMap,
 }
 
 // Helper structs for rendering items/sidebars and carrying along contextual
@@ -287,6 +296,9 @@ struct IndexItem {
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    /// The words of the whole documentation of the item, if the full-text search is enabled.
+    /// These go into a separate file since they are much bigger than the rest of the index.
+    words: Option<String>,
 }
 
 impl Serialize for IndexItem {
@@ -391,7 +403,7 @@ pub fn initial_ids() -> Vec<String> {
 pub fn run(
     mut krate: clean::Crate,
     options: RenderOptions,
-    renderinfo: RenderInfo,
+    mut renderinfo: RenderInfo,
     diag: &rustc_errors::Handler,
     edition: Edition,
 ) -> Result<(), Error> {
@@ -410,6 +422,8 @@ pub fn run(
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        full_text_search,
+        scrape_examples: example_files,
         ..
     } = options;
 
@@ -481,13 +495,19 @@ pub fn run(
         edition,
         codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
         playground,
+        scraped_examples: scrape_examples::scrape(&krate.name, &example_files, diag),
     };
 
     let dst = output;
     scx.ensure_dir(&dst)?;
-    krate = sources::render(&dst, &mut scx, krate)?;
-    let (new_crate, index, cache) =
-        Cache::from_krate(renderinfo, &extern_html_root_urls, &dst, krate);
+    // The sources are only rendered once the cache exists, since their links to other crates are
+    // looked up in it, but the cache takes the impls out of the crate.
+    let (new_crate, mut source_files) = sources::collect(krate);
+    // The files that examples were taken from get a source page too, for the examples to link to.
+    source_files.extend(scx.scraped_examples.files.iter().map(|file| file.path.clone()));
+    let span_map = mem::take(&mut renderinfo.span_map);
+    let (new_crate, index, full_text_index, cache) =
+        Cache::from_krate(renderinfo, &extern_html_root_urls, &dst, new_crate, full_text_search);
     krate = new_crate;
     let cache = Arc::new(cache);
     let mut cx = Context {
@@ -504,9 +524,17 @@ pub fn run(
     CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
     CURRENT_DEPTH.with(|s| s.set(0));
 
+    sources::render(
+        &cx.dst,
+        Arc::get_mut(&mut cx.shared).unwrap(),
+        &krate.name,
+        source_files,
+        &span_map,
+    )?;
+
     // Write shared runs within a flock; disable thread dispatching of IO temporarily.
     Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
-    write_shared(&cx, &krate, index, &md_opts, diag)?;
+    write_shared(&cx, &krate, index, full_text_index, &md_opts, diag)?;
     Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(false);
 
     // And finally render the whole crate's documentation
@@ -525,6 +553,7 @@ fn write_shared(
     cx: &Context,
     krate: &clean::Crate,
     search_index: String,
+    full_text_index: Option<String>,
     options: &RenderOptions,
     diag: &rustc_errors::Handler,
 ) -> Result<(), Error> {
@@ -872,6 +901,13 @@ themePicker.onblur = handleThemeButtonsBlur;
         v.push_str("\naddSearchOptions(searchIndex);initSearch(searchIndex);");
         cx.shared.fs.write(&dst, &v)?;
     }
+    // The full-text index is split by crate, and each part is only loaded when it's searched.
+    if let Some(full_text_index) = full_text_index {
+        let dir = cx.dst.join("search-fulltext");
+        cx.shared.ensure_dir(&dir)?;
+        let dst = dir.join(&format!("{}{}.js", krate.name, cx.shared.resource_suffix));
+        cx.shared.fs.write(&dst, &full_text_index)?;
+    }
     if options.enable_index_page {
         if let Some(index_page) = options.index_page.clone() {
             let mut md_opts = options.clone();
@@ -1657,38 +1693,7 @@ fn print_item(cx: &Context, item: &clean::Item, buf: &mut Buffer) {
 
     write!(buf, "</span>"); // out-of-band
     write!(buf, "<span class='in-band'>");
-    let name = match item.inner {
-        clean::ModuleItem(ref m) => {
-            if m.is_crate {
-                "Crate "
-            } else {
-                "Module "
-            }
-        }
-        clean::FunctionItem(..) | clean::ForeignFunctionItem(..) => "Function ",
-        clean::TraitItem(..) => "Trait ",
-        clean::StructItem(..) => "Struct ",
-        clean::UnionItem(..) => "Union ",
-        clean::EnumItem(..) => "Enum ",
-        clean::TypedefItem(..) => "Type Definition ",
-        clean::MacroItem(..) => "Macro ",
-        clean::ProcMacroItem(ref mac) => match mac.kind {
-            MacroKind::Bang => "Macro ",
-            MacroKind::Attr => "Attribute Macro ",
-            MacroKind::Derive => "Derive Macro ",
-        },
-        clean::PrimitiveItem(..) => "Primitive Type ",
-        clean::StaticItem(..) | clean::ForeignStaticItem(..) => "Static ",
-        clean::ConstantItem(..) => "Constant ",
-        clean::ForeignTypeItem => "Foreign Type ",
-        clean::KeywordItem(..) => "Keyword ",
-        clean::OpaqueTyItem(..) => "Opaque Type ",
-        clean::TraitAliasItem(..) => "Trait Alias ",
-        _ => {
-            // We don't generate pages for any other type.
-            unreachable!();
-        }
-    };
+    let name = page_kind(item);
     buf.write_str(name);
     if !item.is_primitive() && !item.is_keyword() {
         let cur = &cx.current;
@@ -1739,6 +1744,42 @@ fn item_path(ty: ItemType, name: &str) -> String {
     }
 }
 
+/// Returns the kind of item that starts the title of its page, like `"Struct "`.
+crate fn page_kind(item: &clean::Item) -> &'static str {
+    match item.inner {
+        clean::ModuleItem(ref m) => {
+            if m.is_crate {
+                "Crate "
+            } else {
+                "Module "
+            }
+        }
+        clean::FunctionItem(..) | clean::ForeignFunctionItem(..) => "Function ",
+        clean::TraitItem(..) => "Trait ",
+        clean::StructItem(..) => "Struct ",
+        clean::UnionItem(..) => "Union ",
+        clean::EnumItem(..) => "Enum ",
+        clean::TypedefItem(..) => "Type Definition ",
+        clean::MacroItem(..) => "Macro ",
+        clean::ProcMacroItem(ref mac) => match mac.kind {
+            MacroKind::Bang => "Macro ",
+            MacroKind::Attr => "Attribute Macro ",
+            MacroKind::Derive => "Derive Macro ",
+        },
+        clean::PrimitiveItem(..) => "Primitive Type ",
+        clean::StaticItem(..) | clean::ForeignStaticItem(..) => "Static ",
+        clean::ConstantItem(..) => "Constant ",
+        clean::ForeignTypeItem => "Foreign Type ",
+        clean::KeywordItem(..) => "Keyword ",
+        clean::OpaqueTyItem(..) => "Opaque Type ",
+        clean::TraitAliasItem(..) => "Trait Alias ",
+        _ => {
+            // We don't generate pages for any other type.
+            unreachable!();
+        }
+    }
+}
+
 fn full_path(cx: &Context, item: &clean::Item) -> String {
     let mut s = cx.current.join("::");
     s.push_str("::");
@@ -2343,7 +2384,91 @@ fn item_function(w: &mut Buffer, cx: &Context, it: &clean::Item, f: &clean::Func
         decl = Function { decl: &f.decl, header_len, indent: 0, asyncness: f.header.asyncness }
             .print()
     );
-    document(w, cx, it)
+    document(w, cx, it);
+    if let Some(&(ref path, _)) = cx.cache.paths.get(&it.def_id) {
+        render_call_locations(w, cx, it, CallTarget::Function(path));
+    }
+}
+
+/// The number of examples shown for an item, the others are only linked to.
+const MAX_SCRAPED_EXAMPLES: usize = 5;
+
+/// Renders the calls to the function or method `it`, described by `target`, found in the files
+/// given to `--scrape-examples`.
+fn render_call_locations(w: &mut Buffer, cx: &Context, it: &clean::Item, target: CallTarget<'_>) {
+    if !it.def_id.is_local() {
+        return;
+    }
+    let examples = &cx.shared.scraped_examples;
+    let calls = examples.calls_to(&target);
+    if calls.is_empty() {
+        return;
+    }
+
+    let root_path = cx.root_path();
+    write!(w, "<details class='scraped-examples'><summary>Examples found in repository</summary>");
+    let mut more = Vec::new();
+    for (i, call) in calls.into_iter().enumerate() {
+        let file = &examples.files[call.file];
+        let (snippet, line_lo, line_hi) = file.snippet(call);
+        let lines = if line_lo == line_hi {
+            line_lo.to_string()
+        } else {
+            format!("{}-{}", line_lo, line_hi)
+        };
+        let title = format!("{} (line {})", file.path.display(), line_lo);
+        // The files only have a source page if the crate's sources are included.
+        let title = match cx.shared.local_sources.get(&file.path) {
+            Some(path) => format!(
+                "<a href='{root}src/{krate}/{path}#{lines}'>{title}</a>",
+                root = root_path,
+                krate = cx.shared.layout.krate,
+                path = path,
+                lines = lines,
+                title = Escape(&title),
+            ),
+            None => Escape(&title).to_string(),
+        };
+        if i < MAX_SCRAPED_EXAMPLES {
+            write!(
+                w,
+                "<div class='scraped-example'><div class='scraped-example-title'>{}</div>{}</div>",
+                title,
+                highlight::render_with_highlighting(&snippet, Some("scraped-example"), None, None)
+            );
+        } else {
+            more.push(title);
+        }
+    }
+    if !more.is_empty() {
+        write!(w, "<p class='more-scraped-examples'>More examples: {}</p>", more.join(", "));
+    }
+    write!(w, "</details>");
+}
+
+/// Whether the inherent associated function `name` of the type `did` returns the type itself, like
+/// constructors do.
+fn returns_self(cx: &Context, did: DefId, name: &str) -> bool {
+    let impls = match cx.cache.impls.get(&did) {
+        Some(impls) => impls,
+        None => return false,
+    };
+    impls
+        .iter()
+        .filter(|i| i.trait_did().is_none())
+        .flat_map(|i| &i.inner_impl().items)
+        .filter(|item| item.name.as_ref().map_or(false, |n| n == name))
+        .any(|item| match item.inner {
+            clean::MethodItem(clean::Method {
+                decl: clean::FnDecl { output: clean::Return(ref ty), .. },
+                ..
+            }) => match *ty {
+                clean::Generic(ref name) => name == "Self",
+                clean::ResolvedPath { did: ty_did, .. } => ty_did == did,
+                _ => false,
+            },
+            _ => false,
+        })
 }
 
 fn render_implementor(
@@ -3713,6 +3838,13 @@ fn render_impl(
 
     let traits = &cx.cache.traits;
     let trait_ = i.trait_did().map(|did| &traits[&did]);
+    // The type that the inherent methods are called on, and its full path.
+    let self_ty = match i.inner_impl().for_ {
+        clean::ResolvedPath { did, .. } if trait_.is_none() => {
+            cx.cache.paths.get(&did).map(|&(ref path, _)| (did, path))
+        }
+        _ => None,
+    };
 
     write!(w, "<div class='impl-items'>");
     for trait_item in &i.inner_impl().items {
@@ -3727,6 +3859,16 @@ fn render_impl(
             trait_,
             show_def_docs,
         );
+        match (self_ty, &trait_item.inner, &trait_item.name) {
+            (Some((did, self_ty)), clean::MethodItem(..), Some(name))
+                if render_mode == RenderMode::Normal && show_def_docs =>
+            {
+                let returns_self = |name: &str| returns_self(cx, did, name);
+                let target = CallTarget::Method { self_ty, name, returns_self: &returns_self };
+                render_call_locations(w, cx, trait_item, target);
+            }
+            _ => {}
+        }
     }
 
     fn render_default_items(
@@ -4400,7 +4542,7 @@ fn sidebar_enum(buf: &mut Buffer, it: &clean::Item, e: &clean::Enum) {
     }
 }
 
-fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
+crate fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
     match *ty {
         ItemType::ExternCrate | ItemType::Import => ("reexports", "Re-exports"),
         ItemType::Module => ("modules", "Modules"),
@@ -1,11 +1,13 @@
 use crate::clean::{self, AttributesExt, GetDefId};
 use crate::fold::DocFolder;
+use crate::html::markdown::plain_text_words;
 use rustc::middle::privacy::AccessLevels;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::def_id::{CrateNum, DefId, CRATE_DEF_INDEX};
 use rustc_span::source_map::FileName;
 use rustc_span::symbol::sym;
 use std::collections::BTreeMap;
+use std::iter;
 use std::mem;
 use std::path::{Path, PathBuf};
 
@@ -121,6 +123,10 @@ crate struct Cache {
     /// Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
     /// we need the alias element to have an array of items.
     pub(super) aliases: FxHashMap<String, Vec<IndexItem>>,
+
+    /// Whether the words of the documentation of the items are put in the search index, for
+    /// `--full-text-search`.
+    full_text_search: bool,
 }
 
 impl Cache {
@@ -129,7 +135,8 @@ impl Cache {
         extern_html_root_urls: &BTreeMap<String, String>,
         dst: &Path,
         mut krate: clean::Crate,
-    ) -> (clean::Crate, String, Cache) {
+        full_text_search: bool,
+    ) -> (clean::Crate, String, Option<String>, Cache) {
         // Crawl the crate to build various caches used for the output
         let RenderInfo {
             inlined: _,
@@ -168,6 +175,7 @@ impl Cache {
             owned_box_did,
             masked_crates: mem::take(&mut krate.masked_crates),
             aliases: Default::default(),
+            full_text_search,
         };
 
         // Cache where all our extern crates are located
@@ -213,9 +221,9 @@ impl Cache {
         }
 
         // Build our search index
-        let index = build_index(&krate, &mut cache);
+        let (index, full_text_index) = build_index(&krate, &mut cache);
 
-        (krate, index, cache)
+        (krate, index, full_text_index, cache)
     }
 }
 
@@ -323,6 +331,7 @@ impl DocFolder for Cache {
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            words: get_index_words(&item, self.full_text_search),
                         });
                     }
                 }
@@ -513,6 +522,7 @@ impl Cache {
                     parent: None,
                     parent_idx: None,
                     search_type: get_index_search_type(&item),
+                    words: None,
                 });
             }
         }
@@ -558,13 +568,15 @@ fn extern_location(
         .unwrap_or(Unknown) // Well, at least we tried.
 }
 
-/// Builds the search index from the collected metadata
-fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
+/// Builds the search index from the collected metadata, along with its full-text part if
+/// `--full-text-search` was passed.
+fn build_index(krate: &clean::Crate, cache: &mut Cache) -> (String, Option<String>) {
     let mut defid_to_pathid = FxHashMap::default();
     let mut crate_items = Vec::with_capacity(cache.search_index.len());
     let mut crate_paths = vec![];
 
-    let Cache { ref mut search_index, ref orphan_impl_items, ref paths, .. } = *cache;
+    let Cache { ref mut search_index, ref orphan_impl_items, ref paths, full_text_search, .. } =
+        *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -578,6 +590,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                words: get_index_words(&item, full_text_search),
             });
         }
     }
@@ -617,6 +630,20 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
         .map(|module| shorten(plain_summary_line(module.doc_value())))
         .unwrap_or(String::new());
 
+    // The words are indexed by the position of the item in the crate's part of the search index,
+    // where the crate itself comes first.
+    let full_text = if full_text_search {
+        let crate_words = krate.module.as_ref().and_then(|module| get_index_words(module, true));
+        let words = iter::once(crate_words.as_ref())
+            .chain(crate_items.iter().map(|&item| item.words.as_ref()))
+            .enumerate()
+            .filter_map(|(i, words)| Some((i, words?)))
+            .collect::<Vec<_>>();
+        Some(words)
+    } else {
+        None
+    };
+
     #[derive(Serialize)]
     struct CrateData<'a> {
         doc: String,
@@ -624,19 +651,41 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
         items: Vec<&'a IndexItem>,
         #[serde(rename = "p")]
         paths: Vec<(ItemType, String)>,
+        /// Whether the crate has a full-text index, which is loaded separately.
+        #[serde(rename = "f")]
+        full_text: bool,
     }
 
     // Collect the index into a string
-    format!(
+    let index = format!(
         r#"searchIndex["{}"] = {};"#,
         krate.name,
         serde_json::to_string(&CrateData {
             doc: crate_doc,
             items: crate_items,
             paths: crate_paths,
+            full_text: full_text.is_some(),
         })
         .expect("failed serde conversion")
-    )
+    );
+    let full_text_index = full_text.map(|words| {
+        format!(
+            r#"addFullTextIndex("{}", {});"#,
+            krate.name,
+            serde_json::to_string(&words).expect("failed serde conversion")
+        )
+    });
+    (index, full_text_index)
+}
+
+/// Returns the words of the documentation of the item for the full-text search index, if it is
+/// enabled and the item is documented.
+fn get_index_words(item: &clean::Item, full_text_search: bool) -> Option<String> {
+    if !full_text_search {
+        return None;
+    }
+    let words = plain_text_words(&item.collapsed_doc_value()?);
+    if words.is_empty() { None } else { Some(words.join(" ")) }
 }
 
 fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
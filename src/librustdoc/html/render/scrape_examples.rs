@@ -0,0 +1,457 @@
+//! Finding calls to the crate's functions and methods in other source files, like the crate's
+//! examples and tests, for `--scrape-examples`.
+//!
+//! These files are only parsed, not compiled, so the paths in them are resolved with their `use`
+//! and `extern crate` items alone, and only the calls through paths that lead into the crate are
+//! kept. A method call like `x.bar()` is only kept when the type of `x` can be told without
+//! type-checking: when `x` is a struct expression, a call to an associated function of the type
+//! that returns the type itself, like `Foo::new()`, or a variable or parameter whose type or
+//! initializer tells it.
+
+use rustc_ast::ast;
+use rustc_ast::token;
+use rustc_ast::visit::{self, FnKind, Visitor};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::Handler;
+use rustc_parse::{maybe_new_parser_from_source_str, stream_to_parser};
+use rustc_session::parse::ParseSess;
+use rustc_span::symbol::kw;
+use rustc_span::{FileName, Span};
+
+use std::fs;
+use std::path::PathBuf;
+
+/// The number of lines shown before and after a call.
+const CONTEXT_LINES: usize = 2;
+
+/// One of the files that calls were looked for in.
+crate struct ExampleFile {
+    crate path: PathBuf,
+    crate contents: String,
+}
+
+impl ExampleFile {
+    /// Returns the lines of the file around `call`, along with the 1-based numbers of the first
+    /// and last lines of the call itself.
+    crate fn snippet(&self, call: &CallSite) -> (String, usize, usize) {
+        let line_lo = self.contents[..call.lo].matches('\n').count() + 1;
+        let line_hi = line_lo + self.contents[call.lo..call.hi].matches('\n').count();
+        let first = line_lo.saturating_sub(CONTEXT_LINES).max(1);
+        let last = line_hi + CONTEXT_LINES;
+        let snippet =
+            self.contents.lines().skip(first - 1).take(last - first + 1).collect::<Vec<_>>();
+        (snippet.join("\n"), line_lo, line_hi)
+    }
+}
+
+/// The full paths in the crate that a path in one of the files may lead to, starting with the
+/// name of the crate. There are several of them when the path is only found through glob imports.
+type Candidates = Vec<Vec<String>>;
+
+/// What is called by a call.
+enum Callee {
+    /// A function or associated function called through a path, like `foobar::bar()` or
+    /// `Foo::bar(&x)`.
+    Path(Candidates),
+    /// A method of the type of the receiver.
+    Method(Receiver),
+}
+
+/// What the type of the receiver of a method call is known to be.
+#[derive(Clone)]
+enum Receiver {
+    /// The type at one of these paths.
+    Type(Candidates),
+    /// The type returned by the function at one of these paths.
+    ReturnOf(Candidates),
+}
+
+/// A call to a function or method in one of the files.
+crate struct CallSite {
+    /// The index of the file in `ScrapedExamples::files`.
+    crate file: usize,
+    /// What the call calls, as far as it is known.
+    callee: Callee,
+    /// The byte range of the call in the file.
+    lo: usize,
+    hi: usize,
+}
+
+/// A function or method of the crate, to look for the calls to.
+crate enum CallTarget<'a> {
+    /// A function, by its full path.
+    Function(&'a [String]),
+    /// An inherent method, by the full path of its type and its name. `returns_self` tells whether
+    /// the associated function of the type with the given name returns the type itself, like
+    /// constructors do.
+    Method { self_ty: &'a [String], name: &'a str, returns_self: &'a dyn Fn(&str) -> bool },
+}
+
+/// Whether `path` is the path of the associated function `name` of the type at `self_ty`.
+fn is_assoc_fn(path: &[String], self_ty: &[String], name: &str) -> bool {
+    path.len() == self_ty.len() + 1 && path.starts_with(self_ty) && path[self_ty.len()] == name
+}
+
+/// The calls found in the files given to `--scrape-examples`.
+#[derive(Default)]
+crate struct ScrapedExamples {
+    crate files: Vec<ExampleFile>,
+    /// The calls, by the name of the function or method called.
+    calls: FxHashMap<String, Vec<CallSite>>,
+}
+
+impl ScrapedExamples {
+    /// Returns the calls to `target`, in the order they appear in the files.
+    crate fn calls_to(&self, target: &CallTarget<'_>) -> Vec<&CallSite> {
+        let name = match *target {
+            CallTarget::Function(path) => match path.last() {
+                Some(name) => name.as_str(),
+                None => return Vec::new(),
+            },
+            CallTarget::Method { name, .. } => name,
+        };
+        self.calls.get(name).map_or_else(Vec::new, |calls| {
+            calls
+                .iter()
+                .filter(|call| match (&call.callee, target) {
+                    (Callee::Path(paths), CallTarget::Function(path)) => {
+                        paths.iter().any(|p| p == path)
+                    }
+                    (Callee::Path(paths), CallTarget::Method { self_ty, name, .. }) => {
+                        paths.iter().any(|p| is_assoc_fn(p, self_ty, name))
+                    }
+                    (Callee::Method(Receiver::Type(tys)), CallTarget::Method { self_ty, .. }) => {
+                        tys.iter().any(|ty| ty == self_ty)
+                    }
+                    (
+                        Callee::Method(Receiver::ReturnOf(fns)),
+                        CallTarget::Method { self_ty, returns_self, .. },
+                    ) => fns.iter().any(|f| {
+                        f.len() == self_ty.len() + 1
+                            && f.starts_with(self_ty)
+                            && returns_self(f[self_ty.len()].as_str())
+                    }),
+                    (Callee::Method(_), CallTarget::Function(_)) => false,
+                })
+                .collect()
+        })
+    }
+}
+
+/// Parses the given files and collects the calls to the functions and methods of the crate
+/// `crate_name` in them. The files that can't be read or parsed are skipped with a warning.
+crate fn scrape(crate_name: &str, paths: &[PathBuf], diag: &Handler) -> ScrapedExamples {
+    let mut examples = ScrapedExamples::default();
+    for path in paths {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                diag.struct_warn(&format!("couldn't read example `{}`: {}", path.display(), e))
+                    .emit();
+                continue;
+            }
+        };
+        // Remove the utf-8 BOM if any and normalize the line endings, like the source map does,
+        // so that the offsets of the calls match.
+        let contents = contents.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+        // The files are only searched for calls, so their errors are not reported.
+        let sess = ParseSess::with_silent_emitter();
+        let filename = FileName::Real(path.clone());
+        let krate = match maybe_new_parser_from_source_str(&sess, filename, contents.clone()) {
+            Ok(mut parser) => parser.parse_crate_mod().map_err(|mut err| err.cancel()).ok(),
+            Err(errs) => {
+                for mut err in errs {
+                    err.cancel();
+                }
+                None
+            }
+        };
+        let krate = match krate {
+            Some(krate) => krate,
+            None => {
+                diag.struct_warn(&format!("couldn't parse example `{}`", path.display())).emit();
+                continue;
+            }
+        };
+
+        let mut imports = Imports::new(crate_name);
+        visit::walk_crate(&mut imports, &krate);
+        let mut finder = CallFinder {
+            sess: &sess,
+            file: examples.files.len(),
+            imports: &imports,
+            locals: FxHashMap::default(),
+            calls: &mut examples.calls,
+        };
+        visit::walk_crate(&mut finder, &krate);
+        examples.files.push(ExampleFile { path: path.clone(), contents });
+    }
+    examples
+}
+
+/// The names brought into scope in one of the files by its `use` and `extern crate` items.
+///
+/// The scopes of the items aren't tracked: the names are the same in the whole file.
+struct Imports<'a> {
+    crate_name: &'a str,
+    /// The full paths that the names may lead to.
+    names: FxHashMap<String, Candidates>,
+    /// The full paths of the modules and types imported with a glob.
+    globs: Candidates,
+}
+
+impl<'a> Imports<'a> {
+    fn new(crate_name: &'a str) -> Imports<'a> {
+        let mut names = FxHashMap::default();
+        names.insert(crate_name.to_string(), vec![vec![crate_name.to_string()]]);
+        Imports { crate_name, names, globs: Vec::new() }
+    }
+
+    /// Returns the full paths in the crate that `path` may lead to.
+    fn resolve(&self, path: &ast::Path) -> Candidates {
+        let mut segments = path.segments.iter().map(|segment| segment.ident.name);
+        let mut first = segments.next();
+        if first == Some(kw::PathRoot) {
+            first = segments.next();
+        }
+        let first = match first {
+            Some(first) if !first.is_path_segment_keyword() => first.to_string(),
+            // `self::`, `super::`, `crate::` and `Self::` lead into the file itself.
+            _ => return Vec::new(),
+        };
+        let rest: Vec<_> = segments.map(|name| name.to_string()).collect();
+        let prefixes = match self.names.get(&first) {
+            Some(paths) => paths.clone(),
+            None => self
+                .globs
+                .iter()
+                .map(|glob| {
+                    let mut path = glob.clone();
+                    path.push(first.clone());
+                    path
+                })
+                .collect(),
+        };
+        prefixes
+            .into_iter()
+            .map(|mut path| {
+                path.extend(rest.iter().cloned());
+                path
+            })
+            .collect()
+    }
+
+    fn add_use_tree(&mut self, tree: &ast::UseTree, prefix: &[ast::PathSegment]) {
+        let mut path = ast::Path { span: tree.prefix.span, segments: prefix.to_vec() };
+        path.segments.extend(tree.prefix.segments.iter().cloned());
+        match tree.kind {
+            ast::UseTreeKind::Simple(rename, ..) => {
+                // `use foo::{self}` imports `foo`.
+                if path.segments.len() > 1
+                    && path.segments.last().map(|segment| segment.ident.name) == Some(kw::SelfLower)
+                {
+                    path.segments.pop();
+                }
+                let name = match rename.or_else(|| path.segments.last().map(|s| s.ident)) {
+                    Some(name) => name.to_string(),
+                    None => return,
+                };
+                // The imports of things outside of the crate are kept too, since they hide the
+                // crate's items with the same name.
+                let paths = self.resolve(&path);
+                self.names.insert(name, paths);
+            }
+            ast::UseTreeKind::Nested(ref trees) => {
+                for &(ref tree, _) in trees {
+                    self.add_use_tree(tree, &path.segments);
+                }
+            }
+            ast::UseTreeKind::Glob => {
+                let paths = self.resolve(&path);
+                self.globs.extend(paths);
+            }
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for Imports<'_> {
+    fn visit_item(&mut self, item: &'ast ast::Item) {
+        match item.kind {
+            ast::ItemKind::Use(ref tree) => self.add_use_tree(tree, &[]),
+            ast::ItemKind::ExternCrate(orig_name) => {
+                let paths = if orig_name.unwrap_or(item.ident.name).as_str() == self.crate_name {
+                    vec![vec![self.crate_name.to_string()]]
+                } else {
+                    Vec::new()
+                };
+                self.names.insert(item.ident.to_string(), paths);
+            }
+            _ => {}
+        }
+        visit::walk_item(self, item);
+    }
+
+    fn visit_mac(&mut self, _mac: &'ast ast::Mac) {
+        // Macro invocations aren't expanded, so the items they would define aren't known.
+    }
+}
+
+struct CallFinder<'a> {
+    sess: &'a ParseSess,
+    file: usize,
+    imports: &'a Imports<'a>,
+    /// What the types of the variables in scope are known to be.
+    locals: FxHashMap<ast::Name, Receiver>,
+    calls: &'a mut FxHashMap<String, Vec<CallSite>>,
+}
+
+impl CallFinder<'_> {
+    fn add_call(&mut self, name: ast::Ident, callee: Callee, span: Span) {
+        let source_map = self.sess.source_map();
+        let lo = source_map.lookup_byte_offset(span.lo()).pos.0 as usize;
+        let hi = source_map.lookup_byte_offset(span.hi()).pos.0 as usize;
+        self.calls.entry(name.to_string()).or_default().push(CallSite {
+            file: self.file,
+            callee,
+            lo,
+            hi,
+        });
+    }
+
+    /// Returns the crate paths that the type `ty` may have, if it is a path to a type of the
+    /// crate or a reference to one.
+    fn ty_paths(&self, ty: &ast::Ty) -> Option<Candidates> {
+        let paths = match ty.kind {
+            ast::TyKind::Path(None, ref path) => self.imports.resolve(path),
+            ast::TyKind::Rptr(_, ref mut_ty) => return self.ty_paths(&mut_ty.ty),
+            ast::TyKind::Paren(ref ty) => return self.ty_paths(ty),
+            _ => return None,
+        };
+        Some(paths).filter(|paths| !paths.is_empty())
+    }
+
+    /// Returns what the type of the value of `expr` is known to be.
+    fn receiver(&self, expr: &ast::Expr) -> Option<Receiver> {
+        let receiver = match expr.kind {
+            ast::ExprKind::Paren(ref expr) | ast::ExprKind::AddrOf(_, _, ref expr) => {
+                return self.receiver(expr);
+            }
+            ast::ExprKind::Path(None, ref path) => {
+                return match *path.segments {
+                    [ref segment] => self.locals.get(&segment.ident.name).cloned(),
+                    _ => None,
+                };
+            }
+            ast::ExprKind::Struct(ref path, ..) => Receiver::Type(self.imports.resolve(path)),
+            ast::ExprKind::Call(ref callee, _) => match callee.kind {
+                ast::ExprKind::Path(None, ref path) => {
+                    Receiver::ReturnOf(self.imports.resolve(path))
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match receiver {
+            Receiver::Type(ref paths) | Receiver::ReturnOf(ref paths) if paths.is_empty() => None,
+            receiver => Some(receiver),
+        }
+    }
+
+    /// Records the type of the variable bound by `pat`, if it is known. The variables bound by
+    /// other patterns are forgotten, since they hide the ones with the same name.
+    fn bind(&mut self, pat: &ast::Pat, receiver: Option<Receiver>) {
+        if let ast::PatKind::Ident(_, ident, None) = pat.kind {
+            if let Some(receiver) = receiver {
+                self.locals.insert(ident.name, receiver);
+                return;
+            }
+        }
+        let locals = &mut self.locals;
+        pat.walk(&mut |pat| {
+            if let ast::PatKind::Ident(_, ident, _) = pat.kind {
+                locals.remove(&ident.name);
+            }
+            true
+        });
+    }
+}
+
+impl<'ast> Visitor<'ast> for CallFinder<'_> {
+    fn visit_expr(&mut self, expr: &'ast ast::Expr) {
+        match expr.kind {
+            ast::ExprKind::Call(ref callee, _) => {
+                if let ast::ExprKind::Path(None, ref path) = callee.kind {
+                    let paths = self.imports.resolve(path);
+                    if let (Some(last), false) = (path.segments.last(), paths.is_empty()) {
+                        self.add_call(last.ident, Callee::Path(paths), expr.span);
+                    }
+                }
+            }
+            ast::ExprKind::MethodCall(ref segment, ref args) => {
+                if let Some(receiver) = args.first().and_then(|receiver| self.receiver(receiver)) {
+                    self.add_call(segment.ident, Callee::Method(receiver), expr.span);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast ast::Arm) {
+        let locals = self.locals.clone();
+        self.bind(&arm.pat, None);
+        visit::walk_arm(self, arm);
+        self.locals = locals;
+    }
+
+    fn visit_local(&mut self, local: &'ast ast::Local) {
+        // The initializer is evaluated before the variables are bound.
+        if let Some(ref init) = local.init {
+            self.visit_expr(init);
+        }
+        let receiver = match local.ty {
+            Some(ref ty) => self.ty_paths(ty).map(Receiver::Type),
+            None => local.init.as_ref().and_then(|init| self.receiver(init)),
+        };
+        self.bind(&local.pat, receiver);
+    }
+
+    fn visit_block(&mut self, block: &'ast ast::Block) {
+        let locals = self.locals.clone();
+        visit::walk_block(self, block);
+        self.locals = locals;
+    }
+
+    fn visit_fn(&mut self, kind: FnKind<'ast>, span: Span, _: ast::NodeId) {
+        let locals = self.locals.clone();
+        // Unlike closures, functions don't see the variables of the functions they are in.
+        if let FnKind::Fn(..) = kind {
+            self.locals.clear();
+        }
+        for param in &kind.decl().inputs {
+            let receiver = self.ty_paths(&param.ty).map(Receiver::Type);
+            self.bind(&param.pat, receiver);
+        }
+        visit::walk_fn(self, kind, span);
+        self.locals = locals;
+    }
+
+    fn visit_mac(&mut self, mac: &'ast ast::Mac) {
+        // Macros aren't expanded, but the arguments of the most common ones, like `println!` or
+        // `assert_eq!`, are expressions separated by commas.
+        let mut parser = stream_to_parser(self.sess, mac.args.inner_tokens(), None);
+        loop {
+            match parser.parse_expr() {
+                Ok(expr) => Visitor::visit_expr(self, &expr),
+                Err(mut err) => {
+                    err.cancel();
+                    break;
+                }
+            }
+            if !parser.eat(&token::Comma) {
+                break;
+            }
+        }
+    }
+}
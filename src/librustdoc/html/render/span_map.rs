@@ -0,0 +1,151 @@
+//! Collection of the links from identifiers in the crate's source code to their definitions, for
+//! `--generate-link-to-definition`.
+//!
+//! This has to happen while the type context is still around, so it is done in `core::run_core`
+//! and the result is passed to the renderer through `RenderInfo`. Since rustdoc replaces function
+//! bodies with `loop {}` before resolving names, only the paths outside of function bodies are
+//! resolved: types in signatures and fields, `use` declarations, impl headers and bounds.
+
+use rustc::hir::map::Map;
+use rustc::ty::{DefIdTree, TyCtxt};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_hir::def::Res;
+use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc_hir::{HirId, Node};
+use rustc_span::{FileName, Span};
+
+use std::path::PathBuf;
+
+/// A span in one of the crate's source files.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+crate struct SourceLocation {
+    crate file: PathBuf,
+    /// The byte range of the span, from the start of the file.
+    crate lo: u32,
+    crate hi: u32,
+    /// The 1-based line on which the span starts.
+    crate line: usize,
+}
+
+/// Where an identifier in the source code links to.
+#[derive(Clone, Debug)]
+crate enum LinkFromSrc {
+    /// An item of the local crate, defined at the given location.
+    Local(SourceLocation),
+    /// An item of another crate.
+    External(DefId),
+}
+
+/// The links of the identifiers of one source file, by the byte range of each identifier.
+#[derive(Debug, Default)]
+crate struct FileSpans {
+    crate links: FxHashMap<(u32, u32), LinkFromSrc>,
+    /// For the identifiers that define an item, the places where the item is used.
+    crate usages: FxHashMap<(u32, u32), Vec<SourceLocation>>,
+}
+
+/// The links of the identifiers of all the source files of the crate.
+#[derive(Debug, Default)]
+crate struct SpanMap {
+    crate files: FxHashMap<PathBuf, FileSpans>,
+}
+
+/// Resolves the paths in the local crate to the items they refer to.
+crate fn collect_spans(tcx: TyCtxt<'_>) -> SpanMap {
+    let mut visitor = SpanMapVisitor { tcx, map: SpanMap::default() };
+    intravisit::walk_crate(&mut visitor, tcx.hir().krate());
+    visitor.map
+}
+
+struct SpanMapVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    map: SpanMap,
+}
+
+impl SpanMapVisitor<'tcx> {
+    /// Links the identifier at `span` to the definition of `did`.
+    fn link(&mut self, span: Span, did: DefId) {
+        // Identifiers produced by macros don't appear in the source code as such.
+        if span.from_expansion() {
+            return;
+        }
+        let from = match self.location(span) {
+            Some(from) => from,
+            None => return,
+        };
+
+        let link = if did.is_local() {
+            let target = match self.definition_span(did).and_then(|span| self.location(span)) {
+                Some(target) => target,
+                None => return,
+            };
+            if target == from {
+                return;
+            }
+            let usages = &mut self.map.files.entry(target.file.clone()).or_default().usages;
+            usages.entry((target.lo, target.hi)).or_default().push(from.clone());
+            LinkFromSrc::Local(target)
+        } else {
+            LinkFromSrc::External(did)
+        };
+
+        let links = &mut self.map.files.entry(from.file.clone()).or_default().links;
+        links.insert((from.lo, from.hi), link);
+    }
+
+    /// Returns the span of the name of the local item `did` where it is defined.
+    fn definition_span(&self, did: DefId) -> Option<Span> {
+        let hir = self.tcx.hir();
+        let hir_id = hir.as_local_hir_id(did)?;
+        match hir.find(hir_id)? {
+            Node::Variant(variant) => Some(variant.ident.span),
+            Node::Field(field) => Some(field.ident.span),
+            Node::GenericParam(param) => Some(param.name.ident().span),
+            // Link the constructors of tuple structs and variants to the struct or variant itself.
+            Node::Ctor(_) => self.definition_span(self.tcx.parent(did)?),
+            node => Some(node.ident().map_or_else(|| hir.span(hir_id), |ident| ident.span)),
+        }
+    }
+
+    fn location(&self, span: Span) -> Option<SourceLocation> {
+        let source_map = self.tcx.sess.source_map();
+        let start = source_map.lookup_byte_offset(span.lo());
+        let file = match start.sf.name {
+            FileName::Real(ref path) => path.clone(),
+            _ => return None,
+        };
+        let lo = start.pos.0;
+        Some(SourceLocation {
+            file,
+            lo,
+            hi: lo + (span.hi().0 - span.lo().0),
+            line: source_map.lookup_char_pos(span.lo()).line,
+        })
+    }
+}
+
+impl Visitor<'tcx> for SpanMapVisitor<'tcx> {
+    type Map = Map<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<'_, Self::Map> {
+        NestedVisitorMap::All(&self.tcx.hir())
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path<'tcx>, _id: HirId) {
+        // Only the last segment has the resolution of the whole path, the others may have their
+        // own if they name a module or a type.
+        if let Some((last, rest)) = path.segments.split_last() {
+            for segment in rest {
+                if let Some(Res::Def(_, did)) = segment.res {
+                    self.link(segment.ident.span, did);
+                }
+            }
+            if let Res::Def(_, did) = path.res {
+                self.link(last.ident.span, did);
+            }
+        }
+        intravisit::walk_path(self, path);
+    }
+}
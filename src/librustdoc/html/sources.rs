@@ -1,75 +1,101 @@
 use crate::clean;
 use crate::docfs::PathError;
 use crate::fold::DocFolder;
-use crate::html::format::Buffer;
+use crate::html::format::{href, Buffer};
 use crate::html::highlight;
 use crate::html::layout;
-use crate::html::render::{Error, SharedContext, BASIC_KEYWORDS};
+use crate::html::render::span_map::{LinkFromSrc, SourceLocation, SpanMap};
+use crate::html::render::{Error, SharedContext, BASIC_KEYWORDS, CURRENT_DEPTH};
+use rustc_data_structures::fx::FxHashSet;
 use rustc_span::source_map::FileName;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
 
+/// Finds the source files of the local items of `krate`, for `render` to emit once the cache has
+/// been built.
+crate fn collect(krate: clean::Crate) -> (clean::Crate, Vec<PathBuf>) {
+    let mut folder = SourceCollector { files: Vec::new(), seen: FxHashSet::default() };
+    let krate = folder.fold_crate(krate);
+    (krate, folder.files)
+}
+
+/// Renders the source files found by `collect` to HTML pages.
 crate fn render(
     dst: &Path,
     scx: &mut SharedContext,
-    krate: clean::Crate,
-) -> Result<clean::Crate, Error> {
+    krate_name: &str,
+    files: Vec<PathBuf>,
+    span_map: &SpanMap,
+) -> Result<(), Error> {
     info!("emitting source files");
-    let dst = dst.join("src").join(&krate.name);
+    let dst = dst.join("src").join(krate_name);
     scx.ensure_dir(&dst)?;
-    let mut folder = SourceCollector { dst, scx };
-    Ok(folder.fold_crate(krate))
-}
+    if !scx.include_sources {
+        return Ok(());
+    }
 
-/// Helper struct to render all source code to HTML pages
-struct SourceCollector<'a> {
-    scx: &'a mut SharedContext,
+    let mut emitter =
+        SourceEmitter { dst, krate_name, files: files.iter().cloned().collect(), span_map, scx };
+    for file in &files {
+        // If it turns out that we couldn't read this file, then we probably
+        // can't read any of the files (generating html output from json or
+        // something like that), so just don't include sources for the
+        // entire crate. The other option is maintaining this mapping on a
+        // per-file basis, but that's probably not worth it...
+        if let Err(e) = emitter.emit_source(file) {
+            println!(
+                "warning: source code was requested to be rendered, \
+                      but processing `{}` had an error: {}",
+                file.display(),
+                e
+            );
+            println!("         skipping rendering of source code");
+            emitter.scx.include_sources = false;
+            break;
+        }
+    }
+    Ok(())
+}
 
-    /// Root destination to place all HTML output into
-    dst: PathBuf,
+/// Helper struct to find all the source files of the crate's items
+struct SourceCollector {
+    /// The files found so far, in the order they were found in.
+    files: Vec<PathBuf>,
+    seen: FxHashSet<PathBuf>,
 }
 
-impl<'a> DocFolder for SourceCollector<'a> {
+impl DocFolder for SourceCollector {
     fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
-        // If we're including source files, and we haven't seen this file yet,
-        // then we need to render it out to the filesystem.
-        if self.scx.include_sources
+        // skip non-local items
+        if item.def_id.is_local() {
             // skip all synthetic "files"
-            && item.source.filename.is_real()
-            // skip non-local items
-            && item.def_id.is_local()
-        {
-            // If it turns out that we couldn't read this file, then we probably
-            // can't read any of the files (generating html output from json or
-            // something like that), so just don't include sources for the
-            // entire crate. The other option is maintaining this mapping on a
-            // per-file basis, but that's probably not worth it...
-            self.scx.include_sources = match self.emit_source(&item.source.filename) {
-                Ok(()) => true,
-                Err(e) => {
-                    println!(
-                        "warning: source code was requested to be rendered, \
-                              but processing `{}` had an error: {}",
-                        item.source.filename, e
-                    );
-                    println!("         skipping rendering of source code");
-                    false
+            if let FileName::Real(ref path) = item.source.filename {
+                if self.seen.insert(path.clone()) {
+                    self.files.push(path.clone());
                 }
-            };
+            }
         }
         self.fold_item_recur(item)
     }
 }
 
-impl<'a> SourceCollector<'a> {
-    /// Renders the given filename into its corresponding HTML source file.
-    fn emit_source(&mut self, filename: &FileName) -> Result<(), Error> {
-        let p = match *filename {
-            FileName::Real(ref file) => file,
-            _ => return Ok(()),
-        };
-        if self.scx.local_sources.contains_key(&**p) {
+/// Helper struct to render all source code to HTML pages
+struct SourceEmitter<'a> {
+    scx: &'a mut SharedContext,
+
+    /// Root destination to place all HTML output into
+    dst: PathBuf,
+    krate_name: &'a str,
+    /// All the files that get a page, which are the only ones that identifiers can link to.
+    files: FxHashSet<PathBuf>,
+    span_map: &'a SpanMap,
+}
+
+impl<'a> SourceEmitter<'a> {
+    /// Renders the given file into its corresponding HTML source file.
+    fn emit_source(&mut self, p: &Path) -> Result<(), Error> {
+        if self.scx.local_sources.contains_key(p) {
             // We've already emitted this source
             return Ok(());
         }
@@ -101,11 +127,12 @@ impl<'a> SourceCollector<'a> {
         cur.push(&fname);
         href.push_str(&fname.to_string_lossy());
 
+        let links = self.links(p, &root_path);
         let title = format!(
             "{} -- source",
             cur.file_name().expect("failed to get file name").to_string_lossy()
         );
-        let desc = format!("Source to the Rust file `{}`.", filename);
+        let desc = format!("Source to the Rust file `{}`.", p.display());
         let page = layout::Page {
             title: &title,
             css_class: "source",
@@ -121,13 +148,65 @@ impl<'a> SourceCollector<'a> {
             &self.scx.layout,
             &page,
             "",
-            |buf: &mut _| print_src(buf, &contents),
+            |buf: &mut _| print_src(buf, &contents, &links),
             &self.scx.themes,
         );
         self.scx.fs.write(&cur, v.as_bytes())?;
-        self.scx.local_sources.insert(p.clone(), href);
+        self.scx.local_sources.insert(p.to_path_buf(), href);
         Ok(())
     }
+
+    /// Returns the URLs that the identifiers of the file `p` link to, relative to its page.
+    fn links(&self, p: &Path, root_path: &str) -> highlight::SourceLinks {
+        let mut links = highlight::SourceLinks::default();
+        let spans = match self.span_map.files.get(p) {
+            Some(spans) => spans,
+            None => return links,
+        };
+
+        // `href` returns paths relative to the current page.
+        let depth = root_path.matches("../").count();
+        let old_depth = CURRENT_DEPTH.with(|d| d.replace(depth));
+        for (&range, link) in &spans.links {
+            let href = match link {
+                LinkFromSrc::Local(location) => self.source_href(root_path, location),
+                LinkFromSrc::External(did) => href(*did).map(|(url, ..)| url),
+            };
+            if let Some(href) = href {
+                links.links.insert(range, href);
+            }
+        }
+        CURRENT_DEPTH.with(|d| d.set(old_depth));
+
+        for (&range, usages) in &spans.usages {
+            let mut hrefs: Vec<_> =
+                usages.iter().filter_map(|usage| self.source_href(root_path, usage)).collect();
+            hrefs.sort();
+            hrefs.dedup();
+            if !hrefs.is_empty() {
+                links.usages.insert(range, hrefs);
+            }
+        }
+        links
+    }
+
+    /// Returns the URL of the line of `location` in its source page, if it has one.
+    fn source_href(&self, root_path: &str, location: &SourceLocation) -> Option<String> {
+        if !self.files.contains(&location.file) {
+            return None;
+        }
+        let mut components = Vec::new();
+        clean_path(&self.scx.src_root, &location.file, true, |component| {
+            components.push(component.to_string_lossy().into_owned());
+        });
+        Some(format!(
+            "{}src/{}/{}.html#{}",
+            root_path,
+            self.krate_name,
+            components.join("/"),
+            location.line
+        ))
+    }
 }
 
 /// Takes a path to a source file and cleans the path to it. This canonicalizes
@@ -159,7 +238,7 @@ where
 
 /// Wrapper struct to render the source code of a file. This will do things like
 /// adding line numbers to the left-hand side.
-fn print_src(buf: &mut Buffer, s: &str) {
+fn print_src(buf: &mut Buffer, s: &str, links: &highlight::SourceLinks) {
     let lines = s.lines().count();
     let mut cols = 0;
     let mut tmp = lines;
@@ -172,5 +251,5 @@ fn print_src(buf: &mut Buffer, s: &str) {
         write!(buf, "<span id=\"{0}\">{0:1$}</span>\n", i, cols);
     }
     write!(buf, "</pre>");
-    write!(buf, "{}", highlight::render_with_highlighting(s, None, None, None));
+    write!(buf, "{}", highlight::render_source(s, links));
 }
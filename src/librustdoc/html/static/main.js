@@ -530,7 +530,13 @@ function getSearchElement() {
         var NAME = 0;
         var INPUTS_DATA = 0;
         var OUTPUT_DATA = 1;
+        // Characters which separate the words of the documentation in the full-text index.
+        var FULL_TEXT_SEPARATOR = /[\s!-\/:-@\[-\^`{-~]+/;
         var params = getQueryStringParams();
+        // The full-text indexes of the crates that have one, loaded on the first search. Each of
+        // them has the position of the crate in `searchIndex`, and, once it's loaded, a list of
+        // the ids of the documented items along with the words of their documentation.
+        var fullTextIndex = {};
 
         // Populate search bar with query string search term when provided,
         // but only if the input bar is empty. This avoid the obnoxious issue
@@ -1183,11 +1189,73 @@ function getSearchElement() {
                 }
             }
 
+            // Looks for the words of the query in the documentation of the items, using the
+            // full-text indexes loaded so far. A quoted query has to appear as a phrase. The items
+            // whose name matches too come first, then the ones where the query appears the most.
+            function searchFullText(nameResults) {
+                var quoted = valLower.length > 1 && valLower.charAt(0) === "\"" &&
+                    valLower.charAt(valLower.length - 1) === "\"";
+                var words = valLower.split(FULL_TEXT_SEPARATOR).filter(function(word) {
+                    return word.length > 0;
+                });
+                if (words.length === 0) {
+                    return [];
+                }
+                var phrase = " " + words.join(" ") + " ";
+                var found = [];
+
+                for (var crate in fullTextIndex) {
+                    if (!fullTextIndex.hasOwnProperty(crate) || !fullTextIndex[crate].entries ||
+                        (filterCrates !== undefined && crate !== filterCrates)) {
+                        continue;
+                    }
+                    var entries = fullTextIndex[crate].entries;
+                    for (var j = 0; j < entries.length; ++j) {
+                        var text = entries[j].words;
+                        var occurrences = 0;
+                        for (var pos = text.indexOf(phrase); pos !== -1;
+                             pos = text.indexOf(phrase, pos + 1)) {
+                            occurrences += 1;
+                        }
+                        if (occurrences === 0 && (quoted || !words.every(function(word, k) {
+                            // The last word may still be being typed.
+                            return text.indexOf(" " + word +
+                                                (k === words.length - 1 ? "" : " ")) !== -1;
+                        }))) {
+                            continue;
+                        }
+                        if (!typePassesFilter(typeFilter, searchIndex[entries[j].id].ty)) {
+                            continue;
+                        }
+                        found.push({
+                            id: entries[j].id,
+                            lev: 0,
+                            nameHit: nameResults.indexOf(searchIndex[entries[j].id]) !== -1,
+                            occurrences: occurrences,
+                            length: text.length,
+                        });
+                    }
+                }
+
+                found.sort(function(a, b) {
+                    if (a.nameHit !== b.nameHit) {
+                        return a.nameHit ? -1 : 1;
+                    }
+                    if (a.occurrences !== b.occurrences) {
+                        return b.occurrences - a.occurrences;
+                    }
+                    // shorter documentation is more likely to be about the query
+                    return a.length - b.length;
+                });
+                return transformResults(found);
+            }
+
             var ret = {
                 "in_args": sortResults(results_in_args, true),
                 "returned": sortResults(results_returned, true),
                 "others": sortResults(results),
             };
+            ret.docs = searchFullText(ret.others);
             if (ALIASES && ALIASES[window.currentCrate] &&
                     ALIASES[window.currentCrate][query.raw]) {
                 var aliases = ALIASES[window.currentCrate][query.raw];
@@ -1305,7 +1373,7 @@ function getSearchElement() {
             search_input.onkeydown = function(e) {
                 // "actives" references the currently highlighted item in each search tab.
                 // Each array in "actives" represents a tab.
-                var actives = [[], [], []];
+                var actives = [[], [], [], []];
                 // "current" is used to know which tab we're looking into.
                 var current = 0;
                 onEachLazy(document.getElementById("results").childNodes, function(e) {
@@ -1342,10 +1410,11 @@ function getSearchElement() {
                             actives[currentTab][0].getElementsByTagName("a")[0].href;
                     }
                 } else if (e.which === 9) { // tab
+                    var lastTab = document.getElementById("titles").childNodes.length - 1;
                     if (e.shiftKey) {
-                        printTab(currentTab > 0 ? currentTab - 1 : 2);
+                        printTab(currentTab > 0 ? currentTab - 1 : lastTab);
                     } else {
-                        printTab(currentTab > 1 ? 0 : currentTab + 1);
+                        printTab(currentTab < lastTab ? currentTab + 1 : 0);
                     }
                     e.preventDefault();
                 } else if (e.which === 16) { // shift
@@ -1503,6 +1572,10 @@ function getSearchElement() {
             var ret_others = addTab(results.others, query);
             var ret_in_args = addTab(results.in_args, query, false);
             var ret_returned = addTab(results.returned, query, false);
+            var ret_docs = hasFullTextIndex() ? addTab(results.docs, query, false) : null;
+            if (currentTab === 3 && ret_docs === null) {
+                currentTab = 0;
+            }
 
             var output = "<h1>Results for " + escape(query.query) +
                 (query.type ? " (type: " + escape(query.type) + ")" : "") + "</h1>" +
@@ -1510,8 +1583,10 @@ function getSearchElement() {
                 makeTabHeader(0, "In Names", ret_others[1]) +
                 makeTabHeader(1, "In Parameters", ret_in_args[1]) +
                 makeTabHeader(2, "In Return Types", ret_returned[1]) +
+                (ret_docs !== null ? makeTabHeader(3, "In Documentation", ret_docs[1]) : "") +
                 "</div><div id=\"results\">" +
-                ret_others[0] + ret_in_args[0] + ret_returned[0] + "</div>";
+                ret_others[0] + ret_in_args[0] + ret_returned[0] +
+                (ret_docs !== null ? ret_docs[0] : "") + "</div>";
 
             var search = getSearchElement();
             search.innerHTML = output;
@@ -1530,6 +1605,9 @@ function getSearchElement() {
             elems[0].onclick = function() { printTab(0); };
             elems[1].onclick = function() { printTab(1); };
             elems[2].onclick = function() { printTab(2); };
+            if (ret_docs !== null) {
+                elems[3].onclick = function() { printTab(3); };
+            }
             printTab(currentTab);
         }
 
@@ -1579,6 +1657,7 @@ function getSearchElement() {
                 "in_args": [],
                 "returned": [],
                 "others": [],
+                "docs": [],
             };
 
             for (var i = 0; i < queries.length; ++i) {
@@ -1589,6 +1668,7 @@ function getSearchElement() {
                     results.in_args.push(tmp.in_args);
                     results.returned.push(tmp.returned);
                     results.others.push(tmp.others);
+                    results.docs.push(tmp.docs);
                 }
             }
             if (queries.length > 1) {
@@ -1596,12 +1676,14 @@ function getSearchElement() {
                     "in_args": mergeArrays(results.in_args),
                     "returned": mergeArrays(results.returned),
                     "others": mergeArrays(results.others),
+                    "docs": mergeArrays(results.docs),
                 };
             } else {
                 return {
                     "in_args": results.in_args[0],
                     "returned": results.returned[0],
                     "others": results.others[0],
+                    "docs": results.docs[0],
                 };
             }
         }
@@ -1647,9 +1729,52 @@ function getSearchElement() {
             }
 
             var filterCrates = getFilterCrates();
+            loadFullTextIndexes(filterCrates);
             showResults(execSearch(query, index, filterCrates));
         }
 
+        function hasFullTextIndex() {
+            return Object.keys(fullTextIndex).length > 0;
+        }
+
+        // Loads the full-text indexes of the searched crates, if they have one and it wasn't
+        // requested already. Each one then calls `addFullTextIndex`.
+        function loadFullTextIndexes(filterCrates) {
+            for (var crate in fullTextIndex) {
+                if (!fullTextIndex.hasOwnProperty(crate) || fullTextIndex[crate].requested ||
+                    (filterCrates !== undefined && crate !== filterCrates)) {
+                    continue;
+                }
+                fullTextIndex[crate].requested = true;
+                var script = document.createElement("script");
+                script.src = rootPath + "search-fulltext/" + crate + window.resourcesSuffix +
+                    ".js";
+                document.head.appendChild(script);
+            }
+        }
+
+        // Builds the entries that `searchFullText` looks at from the full-text index of a crate,
+        // whose items start at `start` in `searchIndex`.
+        function buildFullTextEntries(start, rawEntries) {
+            // an array of [(Number) position of the item in the crate's part of the index,
+            //              (String) the words of its documentation, separated by spaces]
+            return rawEntries.map(function(entry) {
+                return {id: start + entry[0], words: " " + entry[1] + " "};
+            });
+        }
+
+        window.addFullTextIndex = function(crate, rawEntries) {
+            var crateIndex = fullTextIndex[crate];
+            if (crateIndex === undefined) {
+                return;
+            }
+            crateIndex.entries = buildFullTextEntries(crateIndex.start, rawEntries);
+            // Show the new results if there's a search going on.
+            if (search_input.value.trim().length !== 0) {
+                search(undefined, true);
+            }
+        };
+
         function buildIndex(rawSearchIndex) {
             searchIndex = [];
             var searchWords = [];
@@ -1658,6 +1783,9 @@ function getSearchElement() {
             for (var crate in rawSearchIndex) {
                 if (!rawSearchIndex.hasOwnProperty(crate)) { continue; }
 
+                if (rawSearchIndex[crate].f) {
+                    fullTextIndex[crate] = {start: searchIndex.length, requested: false};
+                }
                 searchWords.push(crate);
                 searchIndex.push({
                     crate: crate,
@@ -2503,7 +2631,7 @@ function getSearchElement() {
 
     // In the search display, allows to switch between tabs.
     function printTab(nb) {
-        if (nb === 0 || nb === 1 || nb === 2) {
+        if (nb === 0 || nb === 1 || nb === 2 || nb === 3) {
             currentTab = nb;
         }
         var nb_copy = nb;
@@ -2674,6 +2802,9 @@ function getSearchElement() {
             "You can look for items with an exact name by putting double quotes around \
              your request: <code>\"string\"</code>",
             "Look for items inside another one by searching for a path: <code>vec::Vec</code>",
+            "If the documentation was generated with its full text indexed, look for the words \
+             of your request in it in the \"In Documentation\" tab. Put double quotes around \
+             your request to look for it as a phrase.",
         ].map(x => "<p>" + x + "</p>").join("");
         var div_infos = document.createElement("div");
         addClass(div_infos, "infos");
@@ -141,3 +141,37 @@ function createSourceSidebar() {
 
     main.insertBefore(sidebar, main.firstChild);
 }
+
+// In source pages rendered with `--generate-link-to-definition`, clicking the name of an item
+// where it is defined shows the places where it is used.
+document.addEventListener("click", function(ev) {
+    var popup = document.getElementById("usages-popup");
+    if (popup !== null && !popup.contains(ev.target)) {
+        popup.parentNode.removeChild(popup);
+    }
+
+    var target = ev.target;
+    if (target.tagName !== "A" || !hasClass(target, "definition")) {
+        return;
+    }
+    ev.preventDefault();
+
+    popup = document.createElement("div");
+    popup.id = "usages-popup";
+    var title = document.createElement("div");
+    title.className = "title";
+    title.innerText = "Used in";
+    popup.appendChild(title);
+    target.getAttribute("data-usages").split(" ").forEach(function(href) {
+        var link = document.createElement("a");
+        link.href = href;
+        // Turn `../../src/krate/dir/file.rs.html#12` into `dir/file.rs:12`.
+        link.innerText = href.replace(/^(\.\.\/)*src\/[^\/]*\//, "").replace(".html#", ":");
+        popup.appendChild(link);
+    });
+
+    var rect = target.getBoundingClientRect();
+    popup.style.left = (rect.left + window.pageXOffset) + "px";
+    popup.style.top = (rect.bottom + window.pageYOffset) + "px";
+    document.body.appendChild(popup);
+});
@@ -0,0 +1,400 @@
+//! These from impls are used to create the JSON types which get serialized. They're very close to
+//! the `clean` types but with some fields removed or stringified to simplify the output and not
+//! expose unstable compiler internals.
+
+use rustc_ast_pretty::pprust;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_hir::Mutability;
+use rustc_span::source_map::FileName;
+
+use crate::clean;
+use crate::doctree;
+use crate::html::item_type::ItemType;
+use crate::json::types::*;
+
+crate fn from_def_id(did: DefId) -> Id {
+    Id(format!("{}:{}", did.krate.as_u32(), did.index.as_u32()))
+}
+
+crate fn span(span: clean::Span) -> Option<Span> {
+    let clean::Span { filename, loline, locol, hiline, hicol, .. } = span;
+    match filename {
+        FileName::Real(filename) => {
+            Some(Span { filename, begin: (loline, locol), end: (hiline, hicol) })
+        }
+        _ => None,
+    }
+}
+
+crate fn attrs(attrs: &clean::Attributes) -> Vec<String> {
+    attrs.other_attrs.iter().map(pprust::attribute_to_string).collect()
+}
+
+crate fn header(header: hir::FnHeader) -> Header {
+    Header {
+        is_const: header.constness == hir::Constness::Const,
+        is_unsafe: header.unsafety == hir::Unsafety::Unsafe,
+        is_async: header.asyncness == hir::IsAsync::Async,
+        abi: header.abi.name().to_string(),
+    }
+}
+
+impl From<clean::Deprecation> for Deprecation {
+    fn from(deprecation: clean::Deprecation) -> Self {
+        let clean::Deprecation { since, note } = deprecation;
+        Deprecation { since, note }
+    }
+}
+
+impl From<clean::Visibility> for Visibility {
+    fn from(v: clean::Visibility) -> Self {
+        use clean::Visibility::*;
+        match v {
+            Public => Visibility::Public,
+            Inherited => Visibility::Default,
+            Crate => Visibility::Crate,
+            Restricted(did, path) => {
+                Visibility::Restricted { parent: from_def_id(did), path: path.whole_name() }
+            }
+        }
+    }
+}
+
+impl From<clean::GenericArgs> for GenericArgs {
+    fn from(args: clean::GenericArgs) -> Self {
+        use clean::GenericArgs::*;
+        match args {
+            AngleBracketed { args, bindings } => GenericArgs::AngleBracketed {
+                args: args.into_iter().map(Into::into).collect(),
+                bindings: bindings.into_iter().map(Into::into).collect(),
+            },
+            Parenthesized { inputs, output } => GenericArgs::Parenthesized {
+                inputs: inputs.into_iter().map(Into::into).collect(),
+                output: output.map(Into::into),
+            },
+        }
+    }
+}
+
+impl From<clean::GenericArg> for GenericArg {
+    fn from(arg: clean::GenericArg) -> Self {
+        use clean::GenericArg::*;
+        match arg {
+            Lifetime(l) => GenericArg::Lifetime(l.0),
+            Type(t) => GenericArg::Type(t.into()),
+            Const(c) => GenericArg::Const(c.into()),
+        }
+    }
+}
+
+impl From<clean::Constant> for Constant {
+    fn from(constant: clean::Constant) -> Self {
+        let clean::Constant { type_, expr, value, is_literal } = constant;
+        Constant { type_: type_.into(), expr, value, is_literal }
+    }
+}
+
+impl From<clean::TypeBinding> for TypeBinding {
+    fn from(binding: clean::TypeBinding) -> Self {
+        TypeBinding { name: binding.name, binding: binding.kind.into() }
+    }
+}
+
+impl From<clean::TypeBindingKind> for TypeBindingKind {
+    fn from(kind: clean::TypeBindingKind) -> Self {
+        use clean::TypeBindingKind::*;
+        match kind {
+            Equality { ty } => TypeBindingKind::Equality(ty.into()),
+            Constraint { bounds } => {
+                TypeBindingKind::Constraint(bounds.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+impl From<clean::Generics> for Generics {
+    fn from(generics: clean::Generics) -> Self {
+        Generics {
+            params: generics.params.into_iter().map(Into::into).collect(),
+            where_predicates: generics.where_predicates.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<clean::GenericParamDef> for GenericParamDef {
+    fn from(generic_param: clean::GenericParamDef) -> Self {
+        GenericParamDef { name: generic_param.name, kind: generic_param.kind.into() }
+    }
+}
+
+impl From<clean::GenericParamDefKind> for GenericParamDefKind {
+    fn from(kind: clean::GenericParamDefKind) -> Self {
+        use clean::GenericParamDefKind::*;
+        match kind {
+            Lifetime => GenericParamDefKind::Lifetime,
+            Type { bounds, default, synthetic, .. } => GenericParamDefKind::Type {
+                bounds: bounds.into_iter().map(Into::into).collect(),
+                default: default.map(Into::into),
+                synthetic: synthetic.is_some(),
+            },
+            Const { ty, .. } => GenericParamDefKind::Const(ty.into()),
+        }
+    }
+}
+
+impl From<clean::WherePredicate> for WherePredicate {
+    fn from(predicate: clean::WherePredicate) -> Self {
+        use clean::WherePredicate::*;
+        match predicate {
+            BoundPredicate { ty, bounds } => WherePredicate::BoundPredicate {
+                type_: ty.into(),
+                bounds: bounds.into_iter().map(Into::into).collect(),
+            },
+            RegionPredicate { lifetime, bounds } => WherePredicate::RegionPredicate {
+                lifetime: lifetime.0,
+                bounds: bounds.into_iter().map(Into::into).collect(),
+            },
+            EqPredicate { lhs, rhs } => {
+                WherePredicate::EqPredicate { lhs: lhs.into(), rhs: rhs.into() }
+            }
+        }
+    }
+}
+
+impl From<clean::GenericBound> for GenericBound {
+    fn from(bound: clean::GenericBound) -> Self {
+        use clean::GenericBound::*;
+        match bound {
+            TraitBound(clean::PolyTrait { trait_, generic_params }, modifier) => {
+                GenericBound::TraitBound {
+                    trait_: trait_.into(),
+                    generic_params: generic_params.into_iter().map(Into::into).collect(),
+                    modifier: modifier.into(),
+                }
+            }
+            Outlives(lifetime) => GenericBound::Outlives(lifetime.0),
+        }
+    }
+}
+
+impl From<hir::TraitBoundModifier> for TraitBoundModifier {
+    fn from(modifier: hir::TraitBoundModifier) -> Self {
+        match modifier {
+            hir::TraitBoundModifier::None => TraitBoundModifier::None,
+            hir::TraitBoundModifier::Maybe => TraitBoundModifier::Maybe,
+            hir::TraitBoundModifier::MaybeConst => TraitBoundModifier::MaybeConst,
+        }
+    }
+}
+
+impl From<clean::Type> for Type {
+    fn from(ty: clean::Type) -> Self {
+        use clean::Type::*;
+        match ty {
+            ResolvedPath { path, param_names, did, is_generic: _ } => Type::ResolvedPath {
+                name: path.whole_name(),
+                id: from_def_id(did),
+                args: path.segments.last().map(|segment| Box::new(segment.args.clone().into())),
+                param_names: param_names
+                    .map(|v| v.into_iter().map(Into::into).collect())
+                    .unwrap_or_default(),
+            },
+            Generic(s) => Type::Generic(s),
+            Primitive(p) => Type::Primitive(p.as_str().to_string()),
+            BareFunction(f) => Type::FunctionPointer(Box::new((*f).into())),
+            Tuple(t) => Type::Tuple(t.into_iter().map(Into::into).collect()),
+            Slice(t) => Type::Slice(Box::new((*t).into())),
+            Array(t, s) => Type::Array { type_: Box::new((*t).into()), len: s },
+            ImplTrait(g) => Type::ImplTrait(g.into_iter().map(Into::into).collect()),
+            Never => Type::Never,
+            Infer => Type::Infer,
+            RawPointer(mutability, type_) => Type::RawPointer {
+                mutable: mutability == Mutability::Mut,
+                type_: Box::new((*type_).into()),
+            },
+            BorrowedRef { lifetime, mutability, type_ } => Type::BorrowedRef {
+                lifetime: lifetime.map(|l| l.0),
+                mutable: mutability == Mutability::Mut,
+                type_: Box::new((*type_).into()),
+            },
+            QPath { name, self_type, trait_ } => Type::QualifiedPath {
+                name,
+                self_type: Box::new((*self_type).into()),
+                trait_: Box::new((*trait_).into()),
+            },
+        }
+    }
+}
+
+impl From<clean::BareFunctionDecl> for FunctionPointer {
+    fn from(bare_decl: clean::BareFunctionDecl) -> Self {
+        let clean::BareFunctionDecl { unsafety, generic_params, decl, abi } = bare_decl;
+        FunctionPointer {
+            is_unsafe: unsafety == hir::Unsafety::Unsafe,
+            generic_params: generic_params.into_iter().map(Into::into).collect(),
+            decl: decl.into(),
+            abi: abi.name().to_string(),
+        }
+    }
+}
+
+impl From<clean::FnDecl> for FnDecl {
+    fn from(decl: clean::FnDecl) -> Self {
+        let clean::FnDecl { inputs, output, c_variadic, attrs: _ } = decl;
+        FnDecl {
+            inputs: inputs.values.into_iter().map(|arg| (arg.name, arg.type_.into())).collect(),
+            output: match output {
+                clean::FnRetTy::Return(t) => Some(t.into()),
+                clean::FnRetTy::DefaultReturn => None,
+            },
+            c_variadic,
+        }
+    }
+}
+
+impl From<clean::Function> for Function {
+    fn from(function: clean::Function) -> Self {
+        let clean::Function { decl, generics, header, all_types: _, ret_types: _ } = function;
+        Function { decl: decl.into(), generics: generics.into(), header: self::header(header) }
+    }
+}
+
+impl From<clean::Method> for Method {
+    fn from(method: clean::Method) -> Self {
+        let clean::Method { decl, generics, header, .. } = method;
+        Method {
+            decl: decl.into(),
+            generics: generics.into(),
+            header: self::header(header),
+            has_body: true,
+        }
+    }
+}
+
+impl From<clean::TyMethod> for Method {
+    fn from(method: clean::TyMethod) -> Self {
+        let clean::TyMethod { header, decl, generics, .. } = method;
+        Method {
+            decl: decl.into(),
+            generics: generics.into(),
+            header: self::header(header),
+            has_body: false,
+        }
+    }
+}
+
+impl From<clean::TraitAlias> for TraitAlias {
+    fn from(alias: clean::TraitAlias) -> Self {
+        TraitAlias {
+            generics: alias.generics.into(),
+            params: alias.bounds.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<doctree::StructType> for StructType {
+    fn from(struct_type: doctree::StructType) -> Self {
+        use doctree::StructType::*;
+        match struct_type {
+            Plain => StructType::Plain,
+            Tuple => StructType::Tuple,
+            Unit => StructType::Unit,
+        }
+    }
+}
+
+impl From<clean::Import> for Import {
+    fn from(import: clean::Import) -> Self {
+        use clean::Import::*;
+        match import {
+            Simple(name, source) => Import {
+                source: source.path.whole_name(),
+                name,
+                id: source.did.map(from_def_id),
+                glob: false,
+            },
+            Glob(source) => Import {
+                source: source.path.whole_name(),
+                name: source.path.last_name().to_string(),
+                id: source.did.map(from_def_id),
+                glob: true,
+            },
+        }
+    }
+}
+
+impl From<clean::ProcMacro> for ProcMacro {
+    fn from(mac: clean::ProcMacro) -> Self {
+        ProcMacro { kind: mac.kind.into(), helpers: mac.helpers }
+    }
+}
+
+impl From<rustc_span::hygiene::MacroKind> for MacroKind {
+    fn from(kind: rustc_span::hygiene::MacroKind) -> Self {
+        use rustc_span::hygiene::MacroKind::*;
+        match kind {
+            Bang => MacroKind::Bang,
+            Attr => MacroKind::Attr,
+            Derive => MacroKind::Derive,
+        }
+    }
+}
+
+impl From<clean::Typedef> for Typedef {
+    fn from(typedef: clean::Typedef) -> Self {
+        let clean::Typedef { type_, generics, item_type: _ } = typedef;
+        Typedef { type_: type_.into(), generics: generics.into() }
+    }
+}
+
+impl From<clean::OpaqueTy> for OpaqueTy {
+    fn from(opaque: clean::OpaqueTy) -> Self {
+        OpaqueTy {
+            bounds: opaque.bounds.into_iter().map(Into::into).collect(),
+            generics: opaque.generics.into(),
+        }
+    }
+}
+
+impl From<clean::Static> for Static {
+    fn from(stat: clean::Static) -> Self {
+        Static {
+            type_: stat.type_.into(),
+            mutable: stat.mutability == Mutability::Mut,
+            expr: stat.expr,
+        }
+    }
+}
+
+impl From<ItemType> for ItemKind {
+    fn from(kind: ItemType) -> Self {
+        match kind {
+            ItemType::Module => ItemKind::Module,
+            ItemType::ExternCrate => ItemKind::ExternCrate,
+            ItemType::Import => ItemKind::Import,
+            ItemType::Struct => ItemKind::Struct,
+            ItemType::Union => ItemKind::Union,
+            ItemType::Enum => ItemKind::Enum,
+            ItemType::Function => ItemKind::Function,
+            ItemType::Typedef => ItemKind::Typedef,
+            ItemType::OpaqueTy => ItemKind::OpaqueTy,
+            ItemType::Static => ItemKind::Static,
+            ItemType::Constant => ItemKind::Constant,
+            ItemType::Trait => ItemKind::Trait,
+            ItemType::Impl => ItemKind::Impl,
+            ItemType::TyMethod | ItemType::Method => ItemKind::Method,
+            ItemType::StructField => ItemKind::StructField,
+            ItemType::Variant => ItemKind::Variant,
+            ItemType::Macro => ItemKind::Macro,
+            ItemType::Primitive => ItemKind::Primitive,
+            ItemType::AssocConst => ItemKind::AssocConst,
+            ItemType::AssocType => ItemKind::AssocType,
+            ItemType::ForeignType => ItemKind::ForeignType,
+            ItemType::Keyword => ItemKind::Keyword,
+            ItemType::TraitAlias => ItemKind::TraitAlias,
+            ItemType::ProcAttribute => ItemKind::ProcAttribute,
+            ItemType::ProcDerive => ItemKind::ProcDerive,
+        }
+    }
+}
@@ -0,0 +1,923 @@
+//! Comparison of the public API of two versions of a crate, for `--compare-api`.
+//!
+//! The previous version of the crate is read back from the file that `--output-format json` wrote
+//! for it, and the crate being documented is converted to the same representation. Items are then
+//! matched up by their path, since IDs are not stable between builds, and every difference is
+//! classified as either breaking or additive.
+//!
+//! Only what can be seen from the declarations is compared: a function whose behavior changed, or
+//! a type whose layout changed behind private fields, is not reported.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use rustc_data_structures::fx::FxHashSet;
+use serde::Serialize;
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::html::render::RenderInfo;
+use crate::json::types::*;
+
+/// Compares the public API of `krate` with the one described by the file at `baseline`, and prints
+/// a report of the changes to stdout.
+crate fn run(
+    krate: clean::Crate,
+    options: &RenderOptions,
+    renderinfo: RenderInfo,
+    baseline: &Path,
+) -> Result<(), String> {
+    let json = renderinfo.output_format.map_or(false, |o| o.is_json());
+    let old = read_baseline(baseline)?;
+    let new = super::convert_crate(krate, options, renderinfo);
+
+    let report = compare(&old, &new);
+    if json {
+        println!("{}", serde_json::to_string(&report).expect("failed to serialize the report"));
+    } else {
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+fn read_baseline(path: &Path) -> Result<Crate, String> {
+    let error = |e: &dyn fmt::Display| {
+        format!("couldn't read the API baseline \"{}\": {}", path.display(), e)
+    };
+
+    let file = File::open(path).map_err(|e| error(&e))?;
+    let value: serde_json::Value =
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| error(&e))?;
+
+    // Check the version first, as a file in another format is unlikely to deserialize at all.
+    let version = value.get("format_version").and_then(|v| v.as_u64());
+    if version != Some(u64::from(FORMAT_VERSION)) {
+        return Err(error(&format_args!(
+            "expected version {} of the JSON format, found {}",
+            FORMAT_VERSION,
+            version.map_or_else(|| "no version".to_string(), |v| v.to_string()),
+        )));
+    }
+
+    serde_json::from_value(value).map_err(|e| error(&e))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+crate enum Severity {
+    /// The change can break code that uses the crate, so it requires a new major version.
+    Breaking,
+    /// The change only extends the API, so it requires a new minor version.
+    Additive,
+}
+
+#[derive(Debug, Serialize)]
+crate struct Change {
+    severity: Severity,
+    /// The path of the changed item, e.g. `foo::Bar::baz`.
+    path: String,
+    description: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+crate struct Report {
+    /// The changes, breaking ones first, then ordered by path.
+    changes: Vec<Change>,
+}
+
+impl Report {
+    fn add(&mut self, severity: Severity, path: &str, description: impl Into<String>) {
+        self.changes.push(Change {
+            severity,
+            path: path.to_string(),
+            description: description.into(),
+        })
+    }
+
+    fn breaking(&mut self, path: &str, description: impl Into<String>) {
+        self.add(Severity::Breaking, path, description)
+    }
+
+    fn additive(&mut self, path: &str, description: impl Into<String>) {
+        self.add(Severity::Additive, path, description)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.changes.iter().filter(|c| c.severity == severity).count()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let severity = match change.severity {
+                Severity::Breaking => "breaking",
+                Severity::Additive => "additive",
+            };
+            writeln!(f, "{}: `{}`: {}", severity, change.path, change.description)?;
+        }
+
+        let (breaking, additive) = (self.count(Severity::Breaking), self.count(Severity::Additive));
+        if breaking == 0 && additive == 0 {
+            writeln!(f, "no changes to the public API")
+        } else {
+            writeln!(f, "{} breaking and {} additive change(s)", breaking, additive)
+        }
+    }
+}
+
+/// Compares the public APIs of `old` and `new`.
+crate fn compare(old: &Crate, new: &Crate) -> Report {
+    let old = Api::new(old);
+    let new = Api::new(new);
+    let mut report = Report::default();
+
+    let removed: Vec<&String> = old.items.keys().filter(|p| !new.items.contains_key(*p)).collect();
+    let added: Vec<&String> = new.items.keys().filter(|p| !old.items.contains_key(*p)).collect();
+
+    for (path, old_item) in &old.items {
+        if let Some(new_item) = new.items.get(path) {
+            compare_items(&mut report, path, (&old, old_item), (&new, new_item));
+        }
+    }
+
+    // Only report the outermost item that was added or removed, and not everything inside it.
+    let removed: Vec<&String> =
+        removed.iter().copied().filter(|p| !is_inside_any(p, &removed)).collect();
+    let added: Vec<&String> = added.iter().copied().filter(|p| !is_inside_any(p, &added)).collect();
+
+    // An item that was removed while an item of the same kind and with the same signature was
+    // added next to it was most likely renamed.
+    let mut renamed_to: FxHashSet<&String> = FxHashSet::default();
+    for &path in &removed {
+        let old_signature = old.signature(old.items[path].item);
+        let rename = added.iter().copied().find(|&new_path| {
+            !renamed_to.contains(new_path)
+                && parent_path(new_path) == parent_path(path)
+                && new.signature(new.items[new_path].item) == old_signature
+        });
+        match rename {
+            Some(new_path) => {
+                renamed_to.insert(new_path);
+                report.breaking(path, format!("renamed to `{}`", new_path));
+            }
+            None => {
+                let kind = kind_name(old.items[path].item);
+                report.breaking(path, format!("{} removed", kind));
+            }
+        }
+    }
+
+    for &path in added.iter().filter(|&&p| !renamed_to.contains(p)) {
+        let (severity, description) = addition(&new.items[path]);
+        report.add(severity, path, description);
+    }
+
+    report.changes.sort_by(|a, b| (a.severity, &a.path).cmp(&(b.severity, &b.path)));
+    report
+}
+
+/// An item of the public API.
+struct ApiItem<'a> {
+    item: &'a Item,
+    /// The item that this one is declared in, for fields, variants, methods and trait items.
+    parent: Option<&'a Item>,
+}
+
+/// The public API of one version of the crate, by path.
+struct Api<'a> {
+    krate: &'a Crate,
+    items: BTreeMap<String, ApiItem<'a>>,
+}
+
+impl<'a> Api<'a> {
+    fn new(krate: &'a Crate) -> Self {
+        let mut api = Api { krate, items: BTreeMap::new() };
+        let mut visited = FxHashSet::default();
+        if let Some(root) = api.get(&krate.root) {
+            let name = root.name.clone().unwrap_or_default();
+            api.add_module(root, &name, &mut visited);
+        }
+        api
+    }
+
+    fn get(&self, id: &Id) -> Option<&'a Item> {
+        self.krate.index.get(id)
+    }
+
+    fn add_module(&mut self, module: &'a Item, path: &str, visited: &mut FxHashSet<&'a Id>) {
+        let items = match &module.inner {
+            ItemEnum::Module(m) => &m.items,
+            _ => return,
+        };
+        // Glob re-exports can make a module reachable from itself.
+        if !visited.insert(&module.id) {
+            return;
+        }
+
+        let krate = self.krate;
+        for item in items.iter().filter_map(|id| krate.index.get(id)) {
+            if !is_public(item) {
+                continue;
+            }
+            match &item.inner {
+                ItemEnum::Import(import) => {
+                    let target = match import.id.as_ref().and_then(|id| self.get(id)) {
+                        Some(target) => target,
+                        // Re-exports of items from other crates are not part of this crate's API.
+                        None => continue,
+                    };
+                    if import.glob {
+                        self.add_module(target, path, visited);
+                    } else {
+                        self.add(target, None, &format!("{}::{}", path, import.name), visited);
+                    }
+                }
+                ItemEnum::ExternCrate { .. } => {}
+                _ => {
+                    if let Some(name) = &item.name {
+                        self.add(item, None, &format!("{}::{}", path, name), visited);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add(
+        &mut self,
+        item: &'a Item,
+        parent: Option<&'a Item>,
+        path: &str,
+        visited: &mut FxHashSet<&'a Id>,
+    ) {
+        if self.items.contains_key(path) {
+            return;
+        }
+        self.items.insert(path.to_string(), ApiItem { item, parent });
+
+        match &item.inner {
+            ItemEnum::Module(_) => self.add_module(item, path, visited),
+            ItemEnum::Struct(Struct { fields, impls, .. })
+            | ItemEnum::Union(Union { fields, impls, .. }) => {
+                self.add_children(item, fields, path, true, visited);
+                self.add_inherent_items(item, impls, path, visited);
+            }
+            ItemEnum::Enum(Enum { variants, impls, .. }) => {
+                self.add_children(item, variants, path, false, visited);
+                self.add_inherent_items(item, impls, path, visited);
+            }
+            ItemEnum::Variant(Variant::Struct(fields)) => {
+                self.add_children(item, fields, path, false, visited);
+            }
+            ItemEnum::Trait(Trait { items, .. }) => {
+                self.add_children(item, items, path, false, visited);
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the items in `children` as part of `parent`. Unless `public_only` is false, private
+    /// items are left out.
+    fn add_children(
+        &mut self,
+        parent: &'a Item,
+        children: &[Id],
+        path: &str,
+        public_only: bool,
+        visited: &mut FxHashSet<&'a Id>,
+    ) {
+        let krate = self.krate;
+        for child in children.iter().filter_map(|id| krate.index.get(id)) {
+            if public_only && !is_public(child) {
+                continue;
+            }
+            if let Some(name) = &child.name {
+                self.add(child, Some(parent), &format!("{}::{}", path, name), visited);
+            }
+        }
+    }
+
+    /// Adds the public items of the inherent impls of `ty`.
+    fn add_inherent_items(
+        &mut self,
+        ty: &'a Item,
+        impls: &[Id],
+        path: &str,
+        visited: &mut FxHashSet<&'a Id>,
+    ) {
+        let krate = self.krate;
+        for impl_ in impls.iter().filter_map(|id| krate.index.get(id)) {
+            if let ItemEnum::Impl(Impl { trait_: None, items, .. }) = &impl_.inner {
+                self.add_children(ty, items, path, true, visited);
+            }
+        }
+    }
+
+    /// Returns the traits implemented by the type with the given impls, with the bounds under
+    /// which they are implemented. Auto traits are returned separately.
+    fn implemented_traits(&self, impls: &[Id]) -> (Bounds, Bounds) {
+        let mut traits = BTreeMap::new();
+        let mut auto_traits = BTreeMap::new();
+        for impl_ in impls.iter().filter_map(|id| self.get(id)) {
+            let impl_ = match &impl_.inner {
+                ItemEnum::Impl(impl_) => impl_,
+                _ => continue,
+            };
+            // Blanket impls follow from the other impls, so they are not compared by themselves.
+            if impl_.negative || impl_.blanket_impl.is_some() {
+                continue;
+            }
+            if let Some(trait_) = &impl_.trait_ {
+                let map = if impl_.synthetic { &mut auto_traits } else { &mut traits };
+                map.insert(self.ty(trait_), self.where_bounds(&impl_.generics));
+            }
+        }
+        (traits, auto_traits)
+    }
+
+    /// Returns a string describing the declaration of `item`, without its name.
+    fn signature(&self, item: &Item) -> String {
+        let detail = match &item.inner {
+            ItemEnum::Function(f) => self.fn_signature(&f.decl, &f.header, &f.generics),
+            ItemEnum::Method(m) => self.fn_signature(&m.decl, &m.header, &m.generics),
+            ItemEnum::StructField(ty) => self.ty(ty),
+            ItemEnum::Variant(v) => self.variant(v),
+            ItemEnum::Typedef(t) => self.ty(&t.type_),
+            ItemEnum::Constant(c) => self.ty(&c.type_),
+            ItemEnum::Static(s) => self.ty(&s.type_),
+            ItemEnum::AssocConst { type_, .. } => self.ty(type_),
+            ItemEnum::Struct(s) => self.field_list(&s.fields),
+            ItemEnum::Union(u) => self.field_list(&u.fields),
+            ItemEnum::Enum(e) => self.field_list(&e.variants),
+            ItemEnum::Trait(t) => self.field_list(&t.items),
+            _ => String::new(),
+        };
+        format!("{} {}", kind_name(item), detail)
+    }
+
+    fn field_list(&self, ids: &[Id]) -> String {
+        let items = ids.iter().filter_map(|id| self.get(id));
+        items.map(|item| self.signature(item)).collect::<Vec<_>>().join(", ")
+    }
+
+    fn variant(&self, variant: &Variant) -> String {
+        match variant {
+            Variant::Plain => String::new(),
+            Variant::Tuple(tys) => format!("({})", self.types(tys)),
+            Variant::Struct(_) => "{ .. }".to_string(),
+        }
+    }
+
+    fn fn_signature(&self, decl: &FnDecl, header: &Header, generics: &Generics) -> String {
+        format!("{}{}{}", self.header(header), self.params(generics), self.decl(decl))
+    }
+
+    fn header(&self, header: &Header) -> String {
+        let mut s = String::new();
+        if header.is_const {
+            s.push_str("const ");
+        }
+        if header.is_async {
+            s.push_str("async ");
+        }
+        if header.is_unsafe {
+            s.push_str("unsafe ");
+        }
+        if header.abi != "Rust" {
+            s.push_str(&format!("extern \"{}\" ", header.abi));
+        }
+        s
+    }
+
+    /// Formats a function declaration, leaving out the names of the arguments.
+    fn decl(&self, decl: &FnDecl) -> String {
+        let mut inputs: Vec<_> = decl.inputs.iter().map(|(_, ty)| self.ty(ty)).collect();
+        if decl.c_variadic {
+            inputs.push("...".to_string());
+        }
+        let mut s = format!("fn({})", inputs.join(", "));
+        if let Some(output) = &decl.output {
+            s.push_str(&format!(" -> {}", self.ty(output)));
+        }
+        s
+    }
+
+    /// Formats the generic parameters, without their bounds. Synthetic parameters for
+    /// `impl Trait` arguments are left out, since they are part of the argument types.
+    fn params(&self, generics: &Generics) -> String {
+        let params = self.param_list(generics);
+        if params.is_empty() {
+            return String::new();
+        }
+        let params: Vec<_> = params.into_iter().map(|(param, _)| param).collect();
+        format!("<{}>", params.join(", "))
+    }
+
+    /// Returns the generic parameters, and whether they have a default.
+    fn param_list(&self, generics: &Generics) -> Vec<(String, bool)> {
+        generics
+            .params
+            .iter()
+            .filter_map(|param| match &param.kind {
+                GenericParamDefKind::Lifetime => Some((param.name.clone(), false)),
+                GenericParamDefKind::Type { synthetic: true, .. } => None,
+                GenericParamDefKind::Type { default, .. } => {
+                    Some((param.name.clone(), default.is_some()))
+                }
+                GenericParamDefKind::Const(ty) => {
+                    Some((format!("const {}: {}", param.name, self.ty(ty)), false))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns every bound in `generics` as a separate `T: Bound` string.
+    fn where_bounds(&self, generics: &Generics) -> BTreeSet<String> {
+        let mut bounds = BTreeSet::new();
+        for param in &generics.params {
+            if let GenericParamDefKind::Type { bounds: param_bounds, synthetic: false, .. } =
+                &param.kind
+            {
+                for bound in param_bounds {
+                    bounds.insert(format!("{}: {}", param.name, self.bound(bound)));
+                }
+            }
+        }
+        for predicate in &generics.where_predicates {
+            match predicate {
+                WherePredicate::BoundPredicate { type_, bounds: predicate_bounds } => {
+                    for bound in predicate_bounds {
+                        bounds.insert(format!("{}: {}", self.ty(type_), self.bound(bound)));
+                    }
+                }
+                WherePredicate::RegionPredicate { lifetime, bounds: predicate_bounds } => {
+                    for bound in predicate_bounds {
+                        bounds.insert(format!("{}: {}", lifetime, self.bound(bound)));
+                    }
+                }
+                WherePredicate::EqPredicate { lhs, rhs } => {
+                    bounds.insert(format!("{} == {}", self.ty(lhs), self.ty(rhs)));
+                }
+            }
+        }
+        bounds
+    }
+
+    fn bound(&self, bound: &GenericBound) -> String {
+        match bound {
+            GenericBound::TraitBound { trait_, generic_params, modifier } => {
+                let mut s = String::new();
+                if !generic_params.is_empty() {
+                    let params: Vec<_> = generic_params.iter().map(|p| p.name.as_str()).collect();
+                    s.push_str(&format!("for<{}> ", params.join(", ")));
+                }
+                match modifier {
+                    TraitBoundModifier::None => {}
+                    TraitBoundModifier::Maybe => s.push('?'),
+                    TraitBoundModifier::MaybeConst => s.push_str("?const "),
+                }
+                s.push_str(&self.ty(trait_));
+                s
+            }
+            GenericBound::Outlives(lifetime) => lifetime.clone(),
+        }
+    }
+
+    fn bounds(&self, bounds: &[GenericBound]) -> String {
+        bounds.iter().map(|b| self.bound(b)).collect::<Vec<_>>().join(" + ")
+    }
+
+    fn types(&self, tys: &[Type]) -> String {
+        tys.iter().map(|ty| self.ty(ty)).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Formats `ty`, naming the types it refers to by their canonical path, so that the result
+    /// doesn't depend on how the types were imported.
+    fn ty(&self, ty: &Type) -> String {
+        match ty {
+            Type::ResolvedPath { name, id, args, param_names } => {
+                let mut s = match self.krate.paths.get(id) {
+                    Some(summary) => summary.path.join("::"),
+                    None => name.clone(),
+                };
+                if let Some(args) = args {
+                    s.push_str(&self.generic_args(args));
+                }
+                if !param_names.is_empty() {
+                    s = format!("dyn {} + {}", s, self.bounds(param_names));
+                }
+                s
+            }
+            Type::Generic(s) | Type::Primitive(s) => s.clone(),
+            Type::FunctionPointer(f) => {
+                let mut s = String::new();
+                if !f.generic_params.is_empty() {
+                    let params: Vec<_> = f.generic_params.iter().map(|p| p.name.as_str()).collect();
+                    s.push_str(&format!("for<{}> ", params.join(", ")));
+                }
+                if f.is_unsafe {
+                    s.push_str("unsafe ");
+                }
+                if f.abi != "Rust" {
+                    s.push_str(&format!("extern \"{}\" ", f.abi));
+                }
+                s.push_str(&self.decl(&f.decl));
+                s
+            }
+            Type::Tuple(tys) if tys.len() == 1 => format!("({},)", self.ty(&tys[0])),
+            Type::Tuple(tys) => format!("({})", self.types(tys)),
+            Type::Slice(ty) => format!("[{}]", self.ty(ty)),
+            Type::Array { type_, len } => format!("[{}; {}]", self.ty(type_), len),
+            Type::ImplTrait(bounds) => format!("impl {}", self.bounds(bounds)),
+            Type::Never => "!".to_string(),
+            Type::Infer => "_".to_string(),
+            Type::RawPointer { mutable, type_ } => {
+                format!("*{} {}", if *mutable { "mut" } else { "const" }, self.ty(type_))
+            }
+            Type::BorrowedRef { lifetime, mutable, type_ } => {
+                let lifetime = lifetime.as_ref().map_or(String::new(), |l| format!("{} ", l));
+                let mutable = if *mutable { "mut " } else { "" };
+                format!("&{}{}{}", lifetime, mutable, self.ty(type_))
+            }
+            Type::QualifiedPath { name, self_type, trait_ } => {
+                format!("<{} as {}>::{}", self.ty(self_type), self.ty(trait_), name)
+            }
+        }
+    }
+
+    fn generic_args(&self, args: &GenericArgs) -> String {
+        match args {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                let mut list: Vec<_> = args
+                    .iter()
+                    .map(|arg| match arg {
+                        GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                        GenericArg::Type(ty) => self.ty(ty),
+                        GenericArg::Const(c) => c.expr.clone(),
+                    })
+                    .collect();
+                list.extend(bindings.iter().map(|binding| match &binding.binding {
+                    TypeBindingKind::Equality(ty) => format!("{} = {}", binding.name, self.ty(ty)),
+                    TypeBindingKind::Constraint(bounds) => {
+                        format!("{}: {}", binding.name, self.bounds(bounds))
+                    }
+                }));
+                if list.is_empty() {
+                    String::new()
+                } else {
+                    format!("<{}>", list.join(", "))
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                let mut s = format!("({})", self.types(inputs));
+                if let Some(output) = output {
+                    s.push_str(&format!(" -> {}", self.ty(output)));
+                }
+                s
+            }
+        }
+    }
+}
+
+/// Maps each implemented trait to the bounds under which it is implemented.
+type Bounds = BTreeMap<String, BTreeSet<String>>;
+
+/// Reports the differences between two versions of the item at `path`.
+fn compare_items(
+    report: &mut Report,
+    path: &str,
+    (old_api, old): (&Api<'_>, &ApiItem<'_>),
+    (new_api, new): (&Api<'_>, &ApiItem<'_>),
+) {
+    let (old_kind, new_kind) = (kind_name(old.item), kind_name(new.item));
+    if old_kind != new_kind {
+        report.breaking(path, format!("changed from {} to {}", old_kind, new_kind));
+        return;
+    }
+
+    match (&old.item.inner, &new.item.inner) {
+        (ItemEnum::Function(o), ItemEnum::Function(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_fns(report, path, (old_api, &o.decl, &o.header), (new_api, &n.decl, &n.header));
+        }
+        (ItemEnum::Method(o), ItemEnum::Method(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_fns(report, path, (old_api, &o.decl, &o.header), (new_api, &n.decl, &n.header));
+            if o.has_body && !n.has_body {
+                report.breaking(path, "no longer has a default implementation");
+            } else if !o.has_body && n.has_body {
+                report.additive(path, "now has a default implementation");
+            }
+        }
+        (ItemEnum::Struct(o), ItemEnum::Struct(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_impls(report, path, (old_api, &o.impls), (new_api, &n.impls));
+            if !o.fields_stripped && n.fields_stripped {
+                report.breaking(path, "now has private fields");
+            }
+            compare_non_exhaustive(report, path, old.item, new.item);
+        }
+        (ItemEnum::Union(o), ItemEnum::Union(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_impls(report, path, (old_api, &o.impls), (new_api, &n.impls));
+            if !o.fields_stripped && n.fields_stripped {
+                report.breaking(path, "now has private fields");
+            }
+        }
+        (ItemEnum::Enum(o), ItemEnum::Enum(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_impls(report, path, (old_api, &o.impls), (new_api, &n.impls));
+            compare_non_exhaustive(report, path, old.item, new.item);
+        }
+        (ItemEnum::Variant(o), ItemEnum::Variant(n)) => {
+            let (o, n) = (old_api.variant(o), new_api.variant(n));
+            if o != n {
+                report.breaking(path, format!("changed from `{}` to `{}`", o, n));
+            }
+        }
+        (ItemEnum::Trait(o), ItemEnum::Trait(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            let old_bounds: BTreeSet<_> = o.bounds.iter().map(|b| old_api.bound(b)).collect();
+            let new_bounds: BTreeSet<_> = n.bounds.iter().map(|b| new_api.bound(b)).collect();
+            for bound in new_bounds.difference(&old_bounds) {
+                report.breaking(path, format!("added supertrait `{}`", bound));
+            }
+            for bound in old_bounds.difference(&new_bounds) {
+                report.breaking(path, format!("removed supertrait `{}`", bound));
+            }
+            if !o.is_unsafe && n.is_unsafe {
+                report.breaking(path, "is now an unsafe trait");
+            } else if o.is_unsafe && !n.is_unsafe {
+                report.breaking(path, "is no longer an unsafe trait");
+            }
+        }
+        (ItemEnum::Typedef(o), ItemEnum::Typedef(n)) => {
+            compare_generics(report, path, (old_api, &o.generics), (new_api, &n.generics));
+            compare_types(report, path, (old_api, &o.type_), (new_api, &n.type_));
+        }
+        (ItemEnum::StructField(o), ItemEnum::StructField(n)) => {
+            compare_types(report, path, (old_api, o), (new_api, n));
+        }
+        (ItemEnum::Constant(o), ItemEnum::Constant(n)) => {
+            compare_types(report, path, (old_api, &o.type_), (new_api, &n.type_));
+        }
+        (ItemEnum::Static(o), ItemEnum::Static(n)) => {
+            compare_types(report, path, (old_api, &o.type_), (new_api, &n.type_));
+            if o.mutable != n.mutable {
+                report.breaking(path, "changed mutability");
+            }
+        }
+        (
+            ItemEnum::AssocConst { type_: old_ty, default: old_default },
+            ItemEnum::AssocConst { type_: new_ty, default: new_default },
+        ) => {
+            compare_types(report, path, (old_api, old_ty), (new_api, new_ty));
+            compare_defaults(report, path, old_default.is_some(), new_default.is_some());
+        }
+        (
+            ItemEnum::AssocType { bounds: old_bounds, default: old_default },
+            ItemEnum::AssocType { bounds: new_bounds, default: new_default },
+        ) => {
+            let old_bounds: BTreeSet<_> = old_bounds.iter().map(|b| old_api.bound(b)).collect();
+            let new_bounds: BTreeSet<_> = new_bounds.iter().map(|b| new_api.bound(b)).collect();
+            for bound in new_bounds.difference(&old_bounds) {
+                report.breaking(path, format!("added bound `{}`", bound));
+            }
+            for bound in old_bounds.difference(&new_bounds) {
+                report.breaking(path, format!("removed bound `{}`", bound));
+            }
+            compare_defaults(report, path, old_default.is_some(), new_default.is_some());
+        }
+        _ => {}
+    }
+}
+
+fn compare_fns(
+    report: &mut Report,
+    path: &str,
+    (old_api, old_decl, old_header): (&Api<'_>, &FnDecl, &Header),
+    (new_api, new_decl, new_header): (&Api<'_>, &FnDecl, &Header),
+) {
+    let (old_sig, new_sig) = (old_api.decl(old_decl), new_api.decl(new_decl));
+    if old_sig != new_sig {
+        report.breaking(path, format!("signature changed from `{}` to `{}`", old_sig, new_sig));
+    }
+
+    match (old_header.is_const, new_header.is_const) {
+        (true, false) => report.breaking(path, "is no longer a `const fn`"),
+        (false, true) => report.additive(path, "is now a `const fn`"),
+        _ => {}
+    }
+    match (old_header.is_unsafe, new_header.is_unsafe) {
+        (false, true) => report.breaking(path, "is now unsafe to call"),
+        (true, false) => report.additive(path, "is no longer unsafe to call"),
+        _ => {}
+    }
+    if old_header.is_async != new_header.is_async {
+        report.breaking(path, "changed whether it is `async`");
+    }
+    if old_header.abi != new_header.abi {
+        report.breaking(
+            path,
+            format!("ABI changed from \"{}\" to \"{}\"", old_header.abi, new_header.abi),
+        );
+    }
+}
+
+fn compare_generics(
+    report: &mut Report,
+    path: &str,
+    (old_api, old): (&Api<'_>, &Generics),
+    (new_api, new): (&Api<'_>, &Generics),
+) {
+    let (old_params, new_params) = (old_api.param_list(old), new_api.param_list(new));
+    if old_params != new_params {
+        // Adding parameters with defaults at the end doesn't affect existing uses.
+        let only_added_defaults = new_params.starts_with(&old_params)
+            && new_params[old_params.len()..].iter().all(|&(_, has_default)| has_default);
+        if only_added_defaults {
+            for (param, _) in &new_params[old_params.len()..] {
+                report
+                    .additive(path, format!("added generic parameter `{}` with a default", param));
+            }
+        } else {
+            report.breaking(
+                path,
+                format!(
+                    "generic parameters changed from `{}` to `{}`",
+                    old_api.params(old),
+                    new_api.params(new),
+                ),
+            );
+        }
+    }
+
+    // Bounds restrict the users of an item, except for `?Sized` which relaxes them.
+    let (old_bounds, new_bounds) = (old_api.where_bounds(old), new_api.where_bounds(new));
+    for bound in new_bounds.difference(&old_bounds) {
+        if bound.contains(": ?") {
+            report.additive(path, format!("added bound `{}`", bound));
+        } else {
+            report.breaking(path, format!("added bound `{}`", bound));
+        }
+    }
+    for bound in old_bounds.difference(&new_bounds) {
+        if bound.contains(": ?") {
+            report.breaking(path, format!("removed bound `{}`", bound));
+        } else {
+            report.additive(path, format!("removed bound `{}`", bound));
+        }
+    }
+}
+
+fn compare_impls(
+    report: &mut Report,
+    path: &str,
+    (old_api, old): (&Api<'_>, &[Id]),
+    (new_api, new): (&Api<'_>, &[Id]),
+) {
+    let (old_traits, old_auto_traits) = old_api.implemented_traits(old);
+    let (new_traits, new_auto_traits) = new_api.implemented_traits(new);
+    compare_implemented_traits(report, path, "trait", &old_traits, &new_traits);
+    compare_implemented_traits(report, path, "auto trait", &old_auto_traits, &new_auto_traits);
+}
+
+fn compare_implemented_traits(
+    report: &mut Report,
+    path: &str,
+    kind: &str,
+    old: &Bounds,
+    new: &Bounds,
+) {
+    for (trait_, old_bounds) in old {
+        match new.get(trait_) {
+            None => report.breaking(path, format!("no longer implements {} `{}`", kind, trait_)),
+            Some(new_bounds) => {
+                for bound in new_bounds.difference(old_bounds) {
+                    report.breaking(
+                        path,
+                        format!("now only implements {} `{}` if `{}`", kind, trait_, bound),
+                    );
+                }
+            }
+        }
+    }
+    for trait_ in new.keys().filter(|t| !old.contains_key(*t)) {
+        report.additive(path, format!("now implements {} `{}`", kind, trait_));
+    }
+}
+
+fn compare_types(
+    report: &mut Report,
+    path: &str,
+    (old_api, old): (&Api<'_>, &Type),
+    (new_api, new): (&Api<'_>, &Type),
+) {
+    let (old, new) = (old_api.ty(old), new_api.ty(new));
+    if old != new {
+        report.breaking(path, format!("type changed from `{}` to `{}`", old, new));
+    }
+}
+
+fn compare_defaults(report: &mut Report, path: &str, old: bool, new: bool) {
+    match (old, new) {
+        (true, false) => report.breaking(path, "no longer has a default"),
+        (false, true) => report.additive(path, "now has a default"),
+        _ => {}
+    }
+}
+
+fn compare_non_exhaustive(report: &mut Report, path: &str, old: &Item, new: &Item) {
+    match (is_non_exhaustive(old), is_non_exhaustive(new)) {
+        (false, true) => report.breaking(path, "is now `#[non_exhaustive]`"),
+        (true, false) => report.additive(path, "is no longer `#[non_exhaustive]`"),
+        _ => {}
+    }
+}
+
+/// Classifies the addition of `item`.
+fn addition(item: &ApiItem<'_>) -> (Severity, String) {
+    let parent = item.parent.map(|parent| (parent, &parent.inner));
+    match (parent, &item.item.inner) {
+        (Some((_, ItemEnum::Trait(_))), ItemEnum::Method(Method { has_body: false, .. }))
+        | (Some((_, ItemEnum::Trait(_))), ItemEnum::AssocType { default: None, .. })
+        | (Some((_, ItemEnum::Trait(_))), ItemEnum::AssocConst { default: None, .. }) => (
+            Severity::Breaking,
+            format!("required {} added; implementations must now define it", kind_name(item.item)),
+        ),
+        (Some((parent, ItemEnum::Enum(_))), ItemEnum::Variant(_)) if !is_non_exhaustive(parent) => {
+            (
+                Severity::Breaking,
+                "variant added; exhaustive matches on the enum no longer compile".to_string(),
+            )
+        }
+        (Some((parent, ItemEnum::Struct(s))), ItemEnum::StructField(_))
+            if !s.fields_stripped && !is_non_exhaustive(parent) =>
+        {
+            (
+                Severity::Breaking,
+                "field added; the struct can no longer be built with a struct expression"
+                    .to_string(),
+            )
+        }
+        (Some((_, ItemEnum::Variant(_))), ItemEnum::StructField(_)) => (
+            Severity::Breaking,
+            "field added; the variant can no longer be built with a struct expression".to_string(),
+        ),
+        _ => (Severity::Additive, format!("{} added", kind_name(item.item))),
+    }
+}
+
+fn kind_name(item: &Item) -> &'static str {
+    match item.inner {
+        ItemEnum::Module(_) => "module",
+        ItemEnum::ExternCrate { .. } => "extern crate",
+        ItemEnum::Import(_) => "import",
+        ItemEnum::Struct(_) => "struct",
+        ItemEnum::StructField(_) => "field",
+        ItemEnum::Union(_) => "union",
+        ItemEnum::Enum(_) => "enum",
+        ItemEnum::Variant(_) => "variant",
+        ItemEnum::Function(_) => "function",
+        ItemEnum::Trait(_) => "trait",
+        ItemEnum::TraitAlias(_) => "trait alias",
+        ItemEnum::Method(_) => "method",
+        ItemEnum::Impl(_) => "impl",
+        ItemEnum::Typedef(_) => "type alias",
+        ItemEnum::OpaqueTy(_) => "opaque type",
+        ItemEnum::Constant(_) => "constant",
+        ItemEnum::Static(_) => "static",
+        ItemEnum::ForeignType => "foreign type",
+        ItemEnum::Macro(_) => "macro",
+        ItemEnum::ProcMacro(_) => "procedural macro",
+        ItemEnum::Primitive(_) => "primitive",
+        ItemEnum::Keyword(_) => "keyword",
+        ItemEnum::AssocConst { .. } => "associated constant",
+        ItemEnum::AssocType { .. } => "associated type",
+    }
+}
+
+fn is_public(item: &Item) -> bool {
+    match item.visibility {
+        Visibility::Public => true,
+        _ => false,
+    }
+}
+
+fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs.iter().any(|attr| attr == "#[non_exhaustive]")
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rfind("::").map_or("", |i| &path[..i])
+}
+
+/// Returns `true` if `path` is the path of an item declared inside one of the items at `paths`.
+fn is_inside_any(path: &str, paths: &[&String]) -> bool {
+    paths.iter().any(|p| {
+        path.len() > p.len() && path.starts_with(p.as_str()) && path[p.len()..].starts_with("::")
+    })
+}
@@ -0,0 +1,300 @@
+//! Rustdoc's JSON backend
+//!
+//! This module contains the logic for rendering a crate as JSON rather than the normal static HTML
+//! output. See the [`types`] module docs for a description of the format.
+
+mod conversions;
+crate mod diff;
+pub mod types;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::html::render::{Cache, Error, ExternalLocation, Impl, RenderInfo};
+
+use self::conversions::from_def_id;
+
+/// Renders `krate` as a single JSON file, `<crate name>.json`, in the output directory.
+pub fn run(
+    krate: clean::Crate,
+    options: RenderOptions,
+    renderinfo: RenderInfo,
+) -> Result<(), Error> {
+    let mut path = options.output.clone();
+    let file_name = format!("{}.json", krate.name);
+    let output_crate = convert_crate(krate, &options, renderinfo);
+
+    fs::create_dir_all(&path).map_err(|e| Error::new(e, &path))?;
+    path.push(file_name);
+    let file = File::create(&path).map_err(|e| Error::new(e, &path))?;
+    serde_json::to_writer(BufWriter::new(file), &output_crate)
+        .map_err(|e| Error::new(io::Error::from(e), &path))
+}
+
+/// Converts `krate` into the JSON description of its API, without writing it anywhere.
+crate fn convert_crate(
+    krate: clean::Crate,
+    options: &RenderOptions,
+    renderinfo: RenderInfo,
+) -> types::Crate {
+    let RenderOptions { output, extern_html_root_urls, document_private, .. } = options;
+
+    // The cache strips all impls out of the crate, so they are collected from it below.
+    let (krate, _, _, cache) =
+        Cache::from_krate(renderinfo, extern_html_root_urls, output, krate, false);
+
+    let mut renderer = JsonRenderer { cache: &cache, index: FxHashMap::default() };
+    let root = krate.module.as_ref().expect("no root module in the crate");
+    renderer.item(root);
+    for impl_ in cache.impls.values().chain(cache.implementors.values()).flatten() {
+        renderer.item(&impl_.impl_item);
+    }
+    renderer.external_traits();
+    let index = renderer.index;
+
+    let paths = cache
+        .paths
+        .iter()
+        .chain(cache.external_paths.iter())
+        .map(|(&did, &(ref path, kind))| {
+            let summary = types::ItemSummary {
+                crate_id: did.krate.as_u32(),
+                path: path.clone(),
+                kind: kind.into(),
+            };
+            (from_def_id(did), summary)
+        })
+        .collect();
+
+    let external_crates = cache
+        .extern_locations
+        .iter()
+        .map(|(&krate, &(ref name, _, ref location))| {
+            let html_root_url = match location {
+                ExternalLocation::Remote(url) => Some(url.clone()),
+                _ => None,
+            };
+            (krate.as_u32(), types::ExternalCrate { name: name.clone(), html_root_url })
+        })
+        .collect();
+
+    types::Crate {
+        root: from_def_id(root.def_id),
+        crate_version: cache.crate_version.clone(),
+        includes_private: *document_private,
+        index,
+        paths,
+        external_crates,
+        format_version: types::FORMAT_VERSION,
+    }
+}
+
+struct JsonRenderer<'a> {
+    cache: &'a Cache,
+    /// All the items that have been converted so far, by their ID.
+    index: FxHashMap<types::Id, types::Item>,
+}
+
+impl JsonRenderer<'_> {
+    /// Adds `item` and all of its children to the index. Stripped items are left out, along with
+    /// everything below them.
+    fn item(&mut self, item: &clean::Item) {
+        let id = from_def_id(item.def_id);
+        if item.is_stripped() || self.index.contains_key(&id) {
+            return;
+        }
+
+        let inner = match self.inner(item) {
+            Some(inner) => inner,
+            None => return,
+        };
+        let json_item = types::Item {
+            id: id.clone(),
+            crate_id: item.def_id.krate.as_u32(),
+            name: item.name.clone(),
+            source: conversions::span(item.source.clone()),
+            visibility: item.visibility.clone().into(),
+            docs: item.attrs.collapsed_doc_value().unwrap_or_default(),
+            links: item
+                .attrs
+                .links
+                .iter()
+                .filter_map(|&(ref text, did, _)| did.map(|did| (text.clone(), from_def_id(did))))
+                .collect(),
+            attrs: conversions::attrs(&item.attrs),
+            deprecation: item.deprecation.clone().map(Into::into),
+            inner,
+        };
+        self.index.insert(id, json_item);
+
+        for child in children(item) {
+            self.item(child);
+        }
+    }
+
+    /// Converts the kind-specific part of `item`. Returns `None` for the kinds of items that the
+    /// JSON output does not describe.
+    fn inner(&self, item: &clean::Item) -> Option<types::ItemEnum> {
+        use crate::clean::ItemEnum::*;
+        use types::ItemEnum as Json;
+
+        let inner = match item.inner.clone() {
+            ModuleItem(m) => {
+                Json::Module(types::Module { is_crate: m.is_crate, items: ids(&m.items) })
+            }
+            ExternCrateItem(name, rename) => Json::ExternCrate { name, rename },
+            ImportItem(import) => Json::Import(import.into()),
+            StructItem(s) => Json::Struct(types::Struct {
+                struct_type: s.struct_type.into(),
+                generics: s.generics.into(),
+                fields_stripped: s.fields_stripped,
+                fields: ids(&s.fields),
+                impls: self.impls(item.def_id),
+            }),
+            UnionItem(u) => Json::Union(types::Union {
+                generics: u.generics.into(),
+                fields_stripped: u.fields_stripped,
+                fields: ids(&u.fields),
+                impls: self.impls(item.def_id),
+            }),
+            EnumItem(e) => Json::Enum(types::Enum {
+                generics: e.generics.into(),
+                variants_stripped: e.variants_stripped,
+                variants: ids(&e.variants.raw),
+                impls: self.impls(item.def_id),
+            }),
+            StructFieldItem(ty) => Json::StructField(ty.into()),
+            VariantItem(v) => Json::Variant(match v.kind {
+                clean::VariantKind::CLike => types::Variant::Plain,
+                clean::VariantKind::Tuple(tys) => {
+                    types::Variant::Tuple(tys.into_iter().map(Into::into).collect())
+                }
+                clean::VariantKind::Struct(s) => types::Variant::Struct(ids(&s.fields)),
+            }),
+            FunctionItem(f) | ForeignFunctionItem(f) => Json::Function(f.into()),
+            TraitItem(t) => Json::Trait(self.trait_(item.def_id, t)),
+            TraitAliasItem(a) => Json::TraitAlias(a.into()),
+            MethodItem(m) => Json::Method(m.into()),
+            TyMethodItem(m) => Json::Method(m.into()),
+            ImplItem(i) => Json::Impl(types::Impl {
+                is_unsafe: i.unsafety == rustc_hir::Unsafety::Unsafe,
+                generics: i.generics.into(),
+                provided_trait_methods: {
+                    let mut methods: Vec<_> = i.provided_trait_methods.into_iter().collect();
+                    methods.sort();
+                    methods
+                },
+                trait_: i.trait_.map(Into::into),
+                for_: i.for_.into(),
+                items: ids(&i.items),
+                negative: i.polarity == Some(clean::ImplPolarity::Negative),
+                synthetic: i.synthetic,
+                blanket_impl: i.blanket_impl.map(Into::into),
+            }),
+            TypedefItem(t, false) => Json::Typedef(t.into()),
+            // Associated types in impls, e.g. `type Item = u32;`.
+            TypedefItem(t, true) => {
+                Json::AssocType { bounds: Vec::new(), default: Some(t.type_.into()) }
+            }
+            OpaqueTyItem(o, _) => Json::OpaqueTy(o.into()),
+            ConstantItem(c) => Json::Constant(c.into()),
+            StaticItem(s) | ForeignStaticItem(s) => Json::Static(s.into()),
+            ForeignTypeItem => Json::ForeignType,
+            MacroItem(m) => Json::Macro(m.source),
+            ProcMacroItem(m) => Json::ProcMacro(m.into()),
+            PrimitiveItem(p) => Json::Primitive(p.as_str().to_string()),
+            KeywordItem(k) => Json::Keyword(k),
+            AssocConstItem(type_, default) => Json::AssocConst { type_: type_.into(), default },
+            AssocTypeItem(bounds, default) => Json::AssocType {
+                bounds: bounds.into_iter().map(Into::into).collect(),
+                default: default.map(Into::into),
+            },
+            StrippedItem(_) => return None,
+        };
+        Some(inner)
+    }
+
+    fn trait_(&self, did: DefId, trait_: clean::Trait) -> types::Trait {
+        let clean::Trait { auto, unsafety, items, generics, bounds, is_auto: _ } = trait_;
+        types::Trait {
+            is_auto: auto,
+            is_unsafe: unsafety == rustc_hir::Unsafety::Unsafe,
+            items: ids(&items),
+            generics: generics.into(),
+            bounds: bounds.into_iter().map(Into::into).collect(),
+            implementors: self.implementors(did),
+        }
+    }
+
+    /// Returns the IDs of all impls of the type or primitive `did`.
+    fn impls(&self, did: DefId) -> Vec<types::Id> {
+        impl_ids(self.cache.impls.get(&did))
+    }
+
+    /// Returns the IDs of all impls of the trait `did`.
+    fn implementors(&self, did: DefId) -> Vec<types::Id> {
+        impl_ids(self.cache.implementors.get(&did))
+    }
+
+    /// Adds the traits from other crates that the local crate refers to, so that the trait
+    /// items of their local implementations can be looked up.
+    fn external_traits(&mut self) {
+        let cache = self.cache;
+        for (&did, trait_) in cache.traits.iter().filter(|(did, _)| !did.is_local()) {
+            let id = from_def_id(did);
+            if self.index.contains_key(&id) {
+                continue;
+            }
+
+            let name = cache.external_paths.get(&did).and_then(|(path, _)| path.last().cloned());
+            let json_item = types::Item {
+                id: id.clone(),
+                crate_id: did.krate.as_u32(),
+                name,
+                source: None,
+                visibility: types::Visibility::Public,
+                docs: String::new(),
+                links: FxHashMap::default(),
+                attrs: Vec::new(),
+                deprecation: None,
+                inner: types::ItemEnum::Trait(self.trait_(did, trait_.clone())),
+            };
+            self.index.insert(id, json_item);
+
+            for item in &trait_.items {
+                self.item(item);
+            }
+        }
+    }
+}
+
+/// Returns the items that are documented as part of `item`.
+fn children(item: &clean::Item) -> &[clean::Item] {
+    match item.inner {
+        clean::ModuleItem(ref m) => &m.items,
+        clean::StructItem(ref s) => &s.fields,
+        clean::UnionItem(ref u) => &u.fields,
+        clean::EnumItem(ref e) => &e.variants.raw,
+        clean::VariantItem(clean::Variant { kind: clean::VariantKind::Struct(ref s) }) => &s.fields,
+        clean::TraitItem(ref t) => &t.items,
+        clean::ImplItem(ref i) => &i.items,
+        _ => &[],
+    }
+}
+
+/// Returns the IDs of the items in `items` that were not stripped.
+fn ids(items: &[clean::Item]) -> Vec<types::Id> {
+    items.iter().filter(|i| !i.is_stripped()).map(|i| from_def_id(i.def_id)).collect()
+}
+
+fn impl_ids(impls: Option<&Vec<Impl>>) -> Vec<types::Id> {
+    impls.map_or_else(Vec::new, |impls| {
+        impls.iter().map(|i| from_def_id(i.impl_item.def_id)).collect()
+    })
+}
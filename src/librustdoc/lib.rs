@@ -51,6 +51,8 @@ use std::process;
 use rustc::session::config::{make_crate_type_option, ErrorOutputType, RustcOptGroup};
 use rustc::session::{early_error, early_warn};
 
+use crate::config::OutputFormat;
+
 #[macro_use]
 mod externalfiles;
 
@@ -72,9 +74,11 @@ pub mod html {
     crate mod static_files;
     crate mod toc;
 }
+mod json;
 mod markdown;
 mod passes;
 mod test;
+mod text;
 mod theme;
 mod visit_ast;
 mod visit_lib;
@@ -140,7 +144,9 @@ fn opts() -> Vec<RustcOptGroup> {
         stable("r", |o| {
             o.optopt("r", "input-format", "the input type of the specified file", "[rust]")
         }),
-        stable("w", |o| o.optopt("w", "output-format", "the output type to write", "[html]")),
+        stable("w", |o| {
+            o.optopt("w", "output-format", "the output type to write", "[html|json|markdown|man]")
+        }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
             o.optopt("", "crate-name", "specify the name of this crate", "NAME")
@@ -366,6 +372,35 @@ fn opts() -> Vec<RustcOptGroup> {
                 "disables generating the crate selector on the search box",
             )
         }),
+        unstable("generate-link-to-definition", |o| {
+            o.optflag(
+                "",
+                "generate-link-to-definition",
+                "make the identifiers in source pages link to their definitions",
+            )
+        }),
+        unstable("full-text-search", |o| {
+            o.optflag("", "full-text-search", "index the full text of the documentation for search")
+        }),
+        unstable("scrape-examples", |o| {
+            o.optmulti(
+                "",
+                "scrape-examples",
+                "source file to show calls to the crate's functions and methods from",
+                "PATH",
+            )
+        }),
+        unstable("doc-cfg-auto", |o| {
+            o.optflag("", "doc-cfg-auto", "show the cfg attributes of items as doc(cfg) badges")
+        }),
+        unstable("doc-cfg-hide", |o| {
+            o.optmulti(
+                "",
+                "doc-cfg-hide",
+                "cfg to leave out of the badges inferred by --doc-cfg-auto",
+                "CFG",
+            )
+        }),
         unstable("persist-doctests", |o| {
             o.optopt(
                 "",
@@ -388,6 +423,40 @@ fn opts() -> Vec<RustcOptGroup> {
                 "calculate percentage of public items with documentation",
             )
         }),
+        unstable("coverage-group-by", |o| {
+            o.optopt(
+                "",
+                "coverage-group-by",
+                "group the items of the --show-coverage report by file (default), kind or module",
+                "[file|kind|module]",
+            )
+        }),
+        unstable("coverage-threshold", |o| {
+            o.optopt(
+                "",
+                "coverage-threshold",
+                "fail if less than this percentage of the items are documented",
+                "PERCENT",
+            )
+        }),
+        unstable("coverage-examples-threshold", |o| {
+            o.optopt(
+                "",
+                "coverage-examples-threshold",
+                "fail if less than this percentage of the items that should have a code example \
+                 have one",
+                "PERCENT",
+            )
+        }),
+        unstable("compare-api", |o| {
+            o.optopt(
+                "",
+                "compare-api",
+                "report the changes to the public API since the version of the crate described by \
+                 the given file, as written by `--output-format json`",
+                "PATH",
+            )
+        }),
         unstable("enable-per-target-ignores", |o| {
             o.optflag(
                 "",
@@ -411,6 +480,13 @@ fn opts() -> Vec<RustcOptGroup> {
                 "One (of possibly many) arguments to pass to the runtool",
             )
         }),
+        unstable("merge-doctests", |o| {
+            o.optflag(
+                "",
+                "merge-doctests",
+                "compile compatible doctests together into a single test binary",
+            )
+        }),
         unstable("test-builder", |o| {
             o.optflag(
                 "",
@@ -463,6 +539,7 @@ fn main_options(options: config::Options) -> i32 {
     // but we can't crates the Handler ahead of time because it's not Send
     let diag_opts = (options.error_format, options.edition, options.debugging_options.clone());
     let show_coverage = options.show_coverage;
+    let compare_api = options.compare_api.clone();
     rust_input(options, move |out| {
         if show_coverage {
             // if we ran coverage, bail early, we don't need to also generate docs at this point
@@ -474,7 +551,24 @@ fn main_options(options: config::Options) -> i32 {
         info!("going to format");
         let (error_format, edition, debugging_options) = diag_opts;
         let diag = core::new_handler(error_format, None, &debugging_options);
-        match html::render::run(krate, renderopts, renderinfo, &diag, edition) {
+        if let Some(baseline) = compare_api {
+            return match json::diff::run(krate, &renderopts, renderinfo, &baseline) {
+                Ok(()) => rustc_driver::EXIT_SUCCESS,
+                Err(e) => {
+                    diag.struct_err(&e).emit();
+                    rustc_driver::EXIT_FAILURE
+                }
+            };
+        }
+        let res = match renderinfo.output_format {
+            Some(OutputFormat::Json) => json::run(krate, renderopts, renderinfo),
+            Some(OutputFormat::Markdown) => text::markdown::run(krate, renderopts, renderinfo),
+            Some(OutputFormat::Man) => text::man::run(krate, renderopts, renderinfo),
+            Some(OutputFormat::Html) | None => {
+                html::render::run(krate, renderopts, renderinfo, &diag, edition)
+            }
+        };
+        match res {
             Ok(_) => rustc_driver::EXIT_SUCCESS,
             Err(e) => {
                 diag.struct_err(&format!("couldn't generate documentation: {}", e.error))
@@ -1,8 +1,9 @@
 use crate::clean;
-use crate::config::OutputFormat;
+use crate::config::{CoverageGroup, OutputFormat};
 use crate::core::DocContext;
 use crate::fold::{self, DocFolder};
-use crate::passes::Pass;
+use crate::html::item_type::ItemType;
+use crate::passes::{count_doc_tests, should_have_doc_example, Pass};
 
 use rustc_ast::attr;
 use rustc_span::symbol::sym;
@@ -20,10 +21,11 @@ pub const CALCULATE_DOC_COVERAGE: Pass = Pass {
 };
 
 fn calculate_doc_coverage(krate: clean::Crate, ctx: &DocContext<'_>) -> clean::Crate {
-    let mut calc = CoverageCalculator::new();
+    let mut calc = CoverageCalculator::new(ctx.coverage.group_by);
     let krate = calc.fold_crate(krate);
 
     calc.print_results(ctx.renderinfo.borrow().output_format);
+    calc.check_thresholds(ctx);
 
     krate
 }
@@ -32,15 +34,26 @@ fn calculate_doc_coverage(krate: clean::Crate, ctx: &DocContext<'_>) -> clean::C
 struct ItemCount {
     total: u64,
     with_docs: u64,
+    /// The number of items that should have a code example in their documentation.
+    total_examples: u64,
+    /// The number of items that should have a code example and have one.
+    with_examples: u64,
 }
 
 impl ItemCount {
-    fn count_item(&mut self, has_docs: bool) {
+    fn count_item(&mut self, has_docs: bool, should_have_example: bool, has_example: bool) {
         self.total += 1;
 
         if has_docs {
             self.with_docs += 1;
         }
+        if should_have_example {
+            self.total_examples += 1;
+
+            if has_example {
+                self.with_examples += 1;
+            }
+        }
     }
 
     fn percentage(&self) -> Option<f64> {
@@ -50,13 +63,26 @@ impl ItemCount {
             None
         }
     }
+
+    fn examples_percentage(&self) -> Option<f64> {
+        if self.total_examples > 0 {
+            Some((self.with_examples as f64 * 100.0) / self.total_examples as f64)
+        } else {
+            None
+        }
+    }
 }
 
 impl ops::Sub for ItemCount {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
-        ItemCount { total: self.total - rhs.total, with_docs: self.with_docs - rhs.with_docs }
+        ItemCount {
+            total: self.total - rhs.total,
+            with_docs: self.with_docs - rhs.with_docs,
+            total_examples: self.total_examples - rhs.total_examples,
+            with_examples: self.with_examples - rhs.with_examples,
+        }
     }
 }
 
@@ -64,11 +90,18 @@ impl ops::AddAssign for ItemCount {
     fn add_assign(&mut self, rhs: Self) {
         self.total += rhs.total;
         self.with_docs += rhs.with_docs;
+        self.total_examples += rhs.total_examples;
+        self.with_examples += rhs.with_examples;
     }
 }
 
 struct CoverageCalculator {
+    group_by: CoverageGroup,
     items: BTreeMap<FileName, ItemCount>,
+    kinds: BTreeMap<&'static str, ItemCount>,
+    modules: BTreeMap<String, ItemCount>,
+    /// The names of the modules containing the current item, starting with the crate.
+    module_path: Vec<String>,
 }
 
 fn limit_filename_len(filename: String) -> String {
@@ -82,19 +115,42 @@ fn limit_filename_len(filename: String) -> String {
 }
 
 impl CoverageCalculator {
-    fn new() -> CoverageCalculator {
-        CoverageCalculator { items: Default::default() }
+    fn new(group_by: CoverageGroup) -> CoverageCalculator {
+        CoverageCalculator {
+            group_by,
+            items: Default::default(),
+            kinds: Default::default(),
+            modules: Default::default(),
+            module_path: Vec::new(),
+        }
+    }
+
+    /// Returns the counts of the items in each group of the report, in order.
+    fn groups(&self) -> Vec<(String, ItemCount)> {
+        match self.group_by {
+            CoverageGroup::File => {
+                self.items.iter().map(|(file, &count)| (file.to_string(), count)).collect()
+            }
+            CoverageGroup::Kind => {
+                self.kinds.iter().map(|(kind, &count)| (kind.to_string(), count)).collect()
+            }
+            CoverageGroup::Module => {
+                self.modules.iter().map(|(module, &count)| (module.clone(), count)).collect()
+            }
+        }
+    }
+
+    fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for &count in self.items.values() {
+            total += count;
+        }
+        total
     }
 
     fn to_json(&self) -> String {
-        serde_json::to_string(
-            &self
-                .items
-                .iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect::<BTreeMap<String, &ItemCount>>(),
-        )
-        .expect("failed to convert JSON data to string")
+        serde_json::to_string(&self.groups().into_iter().collect::<BTreeMap<String, ItemCount>>())
+            .expect("failed to convert JSON data to string")
     }
 
     fn print_results(&self, output_format: Option<OutputFormat>) {
@@ -105,26 +161,46 @@ impl CoverageCalculator {
         let mut total = ItemCount::default();
 
         fn print_table_line() {
-            println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
+            println!(
+                "+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+",
+                ""
+            );
         }
 
         fn print_table_record(name: &str, count: ItemCount, percentage: f64) {
+            // There's no percentage to show if none of the items need an example.
+            let examples_percentage = match count.examples_percentage() {
+                Some(percentage) => format!("{:.1}%", percentage),
+                None => "-".to_string(),
+            };
             println!(
-                "| {:<35} | {:>10} | {:>10} | {:>9.1}% |",
-                name, count.with_docs, count.total, percentage
+                "| {:<35} | {:>10} | {:>10} | {:>9.1}% | {:>10} | {:>10} | {:>10} |",
+                name,
+                count.with_docs,
+                count.total,
+                percentage,
+                count.with_examples,
+                count.total_examples,
+                examples_percentage,
             );
         }
 
+        let group_name = match self.group_by {
+            CoverageGroup::File => "File",
+            CoverageGroup::Kind => "Kind",
+            CoverageGroup::Module => "Module",
+        };
+
         print_table_line();
         println!(
-            "| {:<35} | {:>10} | {:>10} | {:>10} |",
-            "File", "Documented", "Total", "Percentage"
+            "| {:<35} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} |",
+            group_name, "Documented", "Total", "Percentage", "Examples", "Total", "Percentage"
         );
         print_table_line();
 
-        for (file, &count) in &self.items {
+        for (name, count) in self.groups() {
             if let Some(percentage) = count.percentage() {
-                print_table_record(&limit_filename_len(file.to_string()), count, percentage);
+                print_table_record(&limit_filename_len(name), count, percentage);
 
                 total += count;
             }
@@ -134,6 +210,27 @@ impl CoverageCalculator {
         print_table_record("Total", total, total.percentage().unwrap_or(0.0));
         print_table_line();
     }
+
+    /// Reports an error if the percentage of documented items, or of items with a code example,
+    /// is lower than the threshold given for it.
+    fn check_thresholds(&self, ctx: &DocContext<'_>) {
+        let total = self.total();
+        let checks = [
+            ("are documented", total.percentage(), ctx.coverage.threshold),
+            ("have a code example", total.examples_percentage(), ctx.coverage.examples_threshold),
+        ];
+        for &(what, percentage, threshold) in &checks {
+            let percentage = percentage.unwrap_or(100.0);
+            if let Some(threshold) = threshold {
+                if percentage < threshold {
+                    ctx.sess().err(&format!(
+                        "only {:.1}% of the items {}, below the threshold of {}%",
+                        percentage, what, threshold
+                    ));
+                }
+            }
+        }
+    }
 }
 
 impl fold::DocFolder for CoverageCalculator {
@@ -186,10 +283,31 @@ impl fold::DocFolder for CoverageCalculator {
             }
             _ => {
                 debug!("counting {:?} {:?} in {}", i.type_(), i.name, i.source.filename);
-                self.items.entry(i.source.filename.clone()).or_default().count_item(has_docs);
+                let should_have_example = should_have_doc_example(&i);
+                let has_example = should_have_example
+                    && count_doc_tests(&i.attrs.collapsed_doc_value().unwrap_or_default()) > 0;
+                let module = if self.module_path.is_empty() {
+                    // the crate root is counted as part of itself
+                    i.name.clone().unwrap_or_default()
+                } else {
+                    self.module_path.join("::")
+                };
+
+                let mut count = ItemCount::default();
+                count.count_item(has_docs, should_have_example, has_example);
+                *self.items.entry(i.source.filename.clone()).or_default() += count;
+                *self.kinds.entry(ItemType::from(&i).as_str()).or_default() += count;
+                *self.modules.entry(module).or_default() += count;
             }
         }
 
-        self.fold_item_recur(i)
+        if i.is_mod() {
+            self.module_path.push(i.name.clone().unwrap_or_default());
+            let i = self.fold_item_recur(i);
+            self.module_path.pop();
+            i
+        } else {
+            self.fold_item_recur(i)
+        }
     }
 }
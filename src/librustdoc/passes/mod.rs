@@ -326,27 +326,15 @@ pub fn look_for_tests<'tcx>(
         }
     };
 
-    struct Tests {
-        found_tests: usize,
-    }
-
-    impl crate::test::Tester for Tests {
-        fn add_test(&mut self, _: String, _: LangString, _: usize) {
-            self.found_tests += 1;
-        }
-    }
-
-    let mut tests = Tests { found_tests: 0 };
-
-    find_testable_code(&dox, &mut tests, ErrorCodes::No, false);
+    let found_tests = count_doc_tests(dox);
 
-    if check_missing_code && tests.found_tests == 0 {
+    if check_missing_code && found_tests == 0 {
         let sp = span_of_attrs(&item.attrs).unwrap_or(item.source.span());
         cx.tcx.struct_span_lint_hir(lint::builtin::MISSING_DOC_CODE_EXAMPLES, hir_id, sp, |lint| {
             lint.build("missing code example in this documentation").emit()
         });
     } else if !check_missing_code
-        && tests.found_tests > 0
+        && found_tests > 0
         && !cx.renderinfo.borrow().access_levels.is_public(item.def_id)
     {
         cx.tcx.struct_span_lint_hir(
@@ -358,6 +346,48 @@ pub fn look_for_tests<'tcx>(
     }
 }
 
+/// Returns the number of code examples in the documentation `dox`.
+crate fn count_doc_tests(dox: &str) -> usize {
+    struct Tests {
+        found_tests: usize,
+    }
+
+    impl crate::test::Tester for Tests {
+        fn add_test(&mut self, _: String, _: LangString, _: usize) {
+            self.found_tests += 1;
+        }
+    }
+
+    let mut tests = Tests { found_tests: 0 };
+
+    find_testable_code(&dox, &mut tests, ErrorCodes::No, false);
+
+    tests.found_tests
+}
+
+/// Whether the item is of a kind that is expected to have a code example in its documentation,
+/// like functions and types, rather than fields, constants or modules.
+crate fn should_have_doc_example(item: &Item) -> bool {
+    match item.inner {
+        clean::StructFieldItem(..)
+        | clean::VariantItem(..)
+        | clean::ConstantItem(..)
+        | clean::StaticItem(..)
+        | clean::ForeignStaticItem(..)
+        | clean::ForeignTypeItem
+        | clean::AssocConstItem(..)
+        | clean::AssocTypeItem(..)
+        | clean::TypedefItem(_, true)
+        | clean::ModuleItem(..)
+        | clean::ImportItem(..)
+        | clean::ExternCrateItem(..)
+        | clean::PrimitiveItem(..)
+        | clean::KeywordItem(..)
+        | clean::TraitAliasItem(..) => false,
+        _ => true,
+    }
+}
+
 /// Returns a span encompassing all the given attributes.
 crate fn span_of_attrs(attrs: &clean::Attributes) -> Option<Span> {
     if attrs.doc_strings.is_empty() {
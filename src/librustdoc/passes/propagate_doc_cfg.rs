@@ -1,5 +1,8 @@
 use std::sync::Arc;
 
+use rustc_data_structures::fx::FxHashSet;
+use rustc_span::symbol::sym;
+
 use crate::clean::cfg::Cfg;
 use crate::clean::{Crate, Item};
 use crate::core::DocContext;
@@ -12,18 +15,45 @@ pub const PROPAGATE_DOC_CFG: Pass = Pass {
     description: "propagates `#[doc(cfg(...))]` to child items",
 };
 
-pub fn propagate_doc_cfg(cr: Crate, _: &DocContext<'_>) -> Crate {
-    CfgPropagator { parent_cfg: None }.fold_crate(cr)
+pub fn propagate_doc_cfg(cr: Crate, cx: &DocContext<'_>) -> Crate {
+    let hidden_cfg = if cx.doc_cfg_auto { Some(&cx.doc_cfg_hide) } else { None };
+    CfgPropagator { parent_cfg: None, hidden_cfg }.fold_crate(cr)
 }
 
-struct CfgPropagator {
+struct CfgPropagator<'a> {
     parent_cfg: Option<Arc<Cfg>>,
+    /// With `--doc-cfg-auto`, the cfgs to leave out of the ones taken from `#[cfg]` attributes.
+    hidden_cfg: Option<&'a FxHashSet<Cfg>>,
 }
 
-impl DocFolder for CfgPropagator {
+/// Returns the configuration given by the `#[cfg]` attributes of the item, without the hidden cfgs.
+fn cfg_attrs(item: &Item, hidden: &FxHashSet<Cfg>) -> Option<Cfg> {
+    let mut cfg = Cfg::True;
+    for attr in item.attrs.other_attrs.iter().filter(|attr| attr.check_name(sym::cfg)) {
+        let list = attr.meta_item_list().unwrap_or_default();
+        // Malformed `#[cfg]` attributes are already reported by the compiler.
+        if let [ref nested] = list[..] {
+            if let Some(Ok(attr_cfg)) = nested.meta_item().map(Cfg::parse) {
+                cfg &= attr_cfg;
+            }
+        }
+    }
+    cfg.without(hidden).filter(|cfg| *cfg != Cfg::True)
+}
+
+impl DocFolder for CfgPropagator<'_> {
     fn fold_item(&mut self, mut item: Item) -> Option<Item> {
         let old_parent_cfg = self.parent_cfg.clone();
 
+        if let Some(hidden) = self.hidden_cfg {
+            if let Some(attr_cfg) = cfg_attrs(&item, hidden) {
+                item.attrs.cfg = Some(Arc::new(match item.attrs.cfg.take() {
+                    Some(cfg) => Cfg::clone(&cfg) & attr_cfg,
+                    None => attr_cfg,
+                }));
+            }
+        }
+
         let new_cfg = match (self.parent_cfg.take(), item.attrs.cfg.take()) {
             (None, None) => None,
             (Some(rc), None) | (None, Some(rc)) => Some(rc),
@@ -12,13 +12,15 @@ use rustc_span::edition::Edition;
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::sym;
 use rustc_span::{BytePos, FileName, Pos, Span, DUMMY_SP};
-use rustc_target::spec::TargetTriple;
 use std::env;
 use std::io::{self, Write};
+use std::ops::Range;
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tempfile::Builder as TempFileBuilder;
 
 use crate::clean::Attributes;
@@ -189,6 +191,47 @@ enum TestFailure {
     UnexpectedRunPass,
 }
 
+impl TestFailure {
+    /// Returns the kind of the failure, as reported in the JSON output of the tests.
+    fn kind(&self) -> &'static str {
+        match *self {
+            TestFailure::CompileError => "compile_error",
+            TestFailure::UnexpectedCompilePass => "unexpected_compile_pass",
+            TestFailure::MissingErrorCodes(_) => "missing_error_codes",
+            TestFailure::ExecutionError(_) => "execution_error",
+            TestFailure::ExecutionFailure(_) => "execution_failure",
+            TestFailure::UnexpectedRunPass => "unexpected_run_pass",
+        }
+    }
+}
+
+/// Whether the results of the tests are written as JSON, with `--test-args --format=json`. The
+/// doctests are then compiled with JSON diagnostics, which are included in their results.
+fn json_test_output(test_args: &[String]) -> bool {
+    test_args.iter().any(|arg| arg == "--format=json")
+        || test_args.windows(2).any(|args| args[0] == "--format" && args[1] == "json")
+}
+
+/// Returns the rendered text of the JSON diagnostics in `stderr`, and adds each of them to
+/// `diagnostics`. The lines that aren't diagnostics, like the ones of an ICE, are kept as they are.
+fn render_json_diagnostics(stderr: &str, diagnostics: &mut Vec<String>) -> String {
+    let mut out = String::new();
+    for line in stderr.lines() {
+        let diagnostic = serde_json::from_str::<serde_json::Value>(line).ok();
+        match diagnostic.as_ref().and_then(|d| d.get("rendered")).and_then(|r| r.as_str()) {
+            Some(rendered) => {
+                out.push_str(rendered);
+                diagnostics.push(line.to_string());
+            }
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
 fn run_test(
     test: &str,
     cratename: &str,
@@ -200,11 +243,11 @@ fn run_test(
     as_test_harness: bool,
     runtool: Option<String>,
     runtool_args: Vec<String>,
-    target: TargetTriple,
     compile_fail: bool,
     mut error_codes: Vec<String>,
     opts: &TestOptions,
     edition: Edition,
+    details: &mut testing::TestDetails,
 ) -> Result<(), TestFailure> {
     let (test, line_offset) = make_test(test, Some(cratename), as_test_harness, opts, edition);
 
@@ -215,87 +258,30 @@ fn run_test(
         _ => PathBuf::from(r"doctest.rs"),
     };
 
-    enum DirState {
-        Temp(tempfile::TempDir),
-        Perm(PathBuf),
-    }
-
-    impl DirState {
-        fn path(&self) -> &std::path::Path {
-            match self {
-                DirState::Temp(t) => t.path(),
-                DirState::Perm(p) => p.as_path(),
-            }
-        }
-    }
-
-    let outdir = if let Some(mut path) = options.persist_doctests {
-        path.push(format!(
-            "{}_{}",
-            filename.to_string().rsplit('/').next().unwrap().replace(".", "_"),
-            line
-        ));
-        std::fs::create_dir_all(&path).expect("Couldn't create directory for doctest executables");
-
-        DirState::Perm(path)
-    } else {
-        DirState::Temp(
-            TempFileBuilder::new()
-                .prefix("rustdoctest")
-                .tempdir()
-                .expect("rustdoc needs a tempdir"),
-        )
-    };
+    let outdir = DirState::new(
+        &options,
+        format!("{}_{}", filename.to_string().rsplit('/').next().unwrap().replace(".", "_"), line),
+    );
     let output_file = outdir.path().join("rust_out");
 
-    let rustc_binary = options
-        .test_builder
-        .as_ref()
-        .map(|v| &**v)
-        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if let Some(sysroot) = options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler = rustc_command(&options, edition, &output_file);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
     if as_test_harness {
         compiler.arg("--test");
     }
-    for lib_str in &options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
-    for debugging_option_str in &options.debugging_options_strs {
-        compiler.arg("-Z").arg(&debugging_option_str);
-    }
     if no_run && !compile_fail {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(target.to_string());
-
-    compiler.arg("-");
-    compiler.stdin(Stdio::piped());
-    compiler.stderr(Stdio::piped());
 
+    let start = Instant::now();
     let mut child = compiler.spawn().expect("Failed to spawn rustc process");
     {
         let stdin = child.stdin.as_mut().expect("Failed to open stdin");
         stdin.write_all(test.as_bytes()).expect("could write out test sources");
     }
     let output = child.wait_with_output().expect("Failed to read stdout");
+    details.compile_time = Some(start.elapsed());
 
     struct Bomb<'a>(&'a str);
     impl Drop for Bomb<'_> {
@@ -303,7 +289,12 @@ fn run_test(
             eprint!("{}", self.0);
         }
     }
-    let out = str::from_utf8(&output.stderr).unwrap();
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    let out = if json_test_output(&options.test_args) {
+        render_json_diagnostics(stderr, &mut details.diagnostics)
+    } else {
+        stderr.to_string()
+    };
     let _bomb = Bomb(&out);
     match (output.status.success(), compile_fail) {
         (true, true) => {
@@ -328,7 +319,93 @@ fn run_test(
         return Ok(());
     }
 
-    // Run the code!
+    run_binary(&output_file, None, runtool, runtool_args, should_panic, details)
+}
+
+/// The directory that a doctest binary is written to.
+enum DirState {
+    Temp(tempfile::TempDir),
+    Perm(PathBuf),
+}
+
+impl DirState {
+    /// Creates a new directory, inside the `--persist-doctests` directory if one was given, or
+    /// as a temporary directory otherwise.
+    fn new(options: &Options, name: String) -> DirState {
+        if let Some(ref path) = options.persist_doctests {
+            let path = path.join(name);
+            std::fs::create_dir_all(&path)
+                .expect("Couldn't create directory for doctest executables");
+
+            DirState::Perm(path)
+        } else {
+            DirState::Temp(
+                TempFileBuilder::new()
+                    .prefix("rustdoctest")
+                    .tempdir()
+                    .expect("rustdoc needs a tempdir"),
+            )
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            DirState::Temp(t) => t.path(),
+            DirState::Perm(p) => p.as_path(),
+        }
+    }
+}
+
+/// Returns a command that compiles the doctest binary read from its stdin into `output_file`.
+fn rustc_command(options: &Options, edition: Edition, output_file: &Path) -> Command {
+    let rustc_binary = options
+        .test_builder
+        .as_ref()
+        .map(|v| &**v)
+        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if let Some(ref sysroot) = options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    compiler.arg("-o").arg(output_file);
+    for lib_str in &options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    for codegen_options_str in &options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    for debugging_option_str in &options.debugging_options_strs {
+        compiler.arg("-Z").arg(&debugging_option_str);
+    }
+    compiler.arg("--target").arg(options.target.to_string());
+    if json_test_output(&options.test_args) {
+        compiler.arg("--error-format=json");
+    }
+
+    compiler.arg("-");
+    compiler.stdin(Stdio::piped());
+    compiler.stderr(Stdio::piped());
+    compiler
+}
+
+/// Runs a doctest binary, passing it `arg` if there is one, and checks its exit status.
+fn run_binary(
+    output_file: &Path,
+    arg: Option<String>,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+    details: &mut testing::TestDetails,
+) -> Result<(), TestFailure> {
     let mut cmd;
 
     if let Some(tool) = runtool {
@@ -338,8 +415,12 @@ fn run_test(
     } else {
         cmd = Command::new(output_file);
     }
+    cmd.args(arg);
 
-    match cmd.output() {
+    let start = Instant::now();
+    let output = cmd.output();
+    details.run_time = Some(start.elapsed());
+    match output {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
         Ok(out) => {
             if should_panic && out.status.success() {
@@ -387,6 +468,59 @@ pub fn make_test(
     prog.push_str(&crate_attrs);
     prog.push_str(&crates);
 
+    let (already_has_main, already_has_extern_crate) =
+        match scan_test(s, &crates, everything_else, cratename, edition) {
+            Ok(found) => found,
+            Err(ErrorReported) => {
+                // If the parser panicked due to a fatal error, pass the test code through
+                // unchanged. The error will be reported during compilation.
+                return (s.to_owned(), 0);
+            }
+        };
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    // FIXME: This code cannot yet handle no_std test cases yet
+    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+        prog.push_str(everything_else);
+    } else {
+        let returns_result = everything_else.trim_end().ends_with("(())");
+        let (main_pre, main_post) = if returns_result {
+            (
+                "fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {",
+                "}\n_inner().unwrap() }",
+            )
+        } else {
+            ("fn main() {\n", "\n}")
+        };
+        prog.extend([main_pre, everything_else, main_post].iter().cloned());
+        line_offset += 1;
+    }
+
+    debug!("final doctest:\n{}", prog);
+
+    (prog, line_offset)
+}
+
+/// Parses the doctest `s`, split into its `extern crate` lines and everything else, and returns
+/// whether it has its own `fn main` and whether it already declares `extern crate <cratename>`.
+fn scan_test(
+    s: &str,
+    crates: &str,
+    everything_else: &str,
+    cratename: Option<&str>,
+    edition: Edition,
+) -> Result<(bool, bool), ErrorReported> {
     // Uses librustc_ast to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
     let result = rustc_driver::catch_fatal_errors(|| {
@@ -398,7 +532,7 @@ pub fn make_test(
             use rustc_span::source_map::FilePathMapping;
 
             let filename = FileName::anon_source_code(s);
-            let source = crates + everything_else;
+            let source = format!("{}{}", crates, everything_else);
 
             // Any errors in parsing should also appear when the doctest is compiled for real, so just
             // send all the errors that librustc_ast emits directly into a `Sink` instead of stderr.
@@ -469,14 +603,7 @@ pub fn make_test(
             (found_main, found_extern_crate, found_macro)
         })
     });
-    let (already_has_main, already_has_extern_crate, found_macro) = match result {
-        Ok(result) => result,
-        Err(ErrorReported) => {
-            // If the parser panicked due to a fatal error, pass the test code through unchanged.
-            // The error will be reported during compilation.
-            return (s.to_owned(), 0);
-        }
-    };
+    let (already_has_main, already_has_extern_crate, found_macro) = result?;
 
     // If a doctest's `fn main` is being masked by a wrapper macro, the parsing loop above won't
     // see it. In that case, run the old text-based scan to see if they at least have a main
@@ -493,38 +620,122 @@ pub fn make_test(
         already_has_main
     };
 
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
+    Ok((already_has_main, already_has_extern_crate))
+}
+
+/// Returns the body of the function that runs the doctest `s` in a merged doctest binary, whether
+/// the binary needs to declare `extern crate <cratename>` for it, and the number of lines between
+/// the opening fence of the code block and the first line of the body.
+///
+/// Returns `None` for tests that have to be compiled on their own: tests with crate attributes,
+/// `extern crate` declarations or their own `fn main`, which only work at the root of a crate.
+fn make_merged_test(
+    s: &str,
+    cratename: Option<&str>,
+    opts: &TestOptions,
+    edition: Edition,
+) -> Option<(String, bool, usize)> {
+    let (crate_attrs, everything_else, crates) = partition_source(s);
+    if crate_attrs.lines().any(|line| line.trim().starts_with("#![")) || !crates.trim().is_empty() {
+        return None;
     }
 
-    // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
-        prog.push_str(everything_else);
+    let everything_else = everything_else.trim();
+    let (has_main, has_extern_crate) =
+        scan_test(s, &crates, everything_else, cratename, edition).ok()?;
+    if has_main || (cratename.is_some() && has_extern_crate) {
+        return None;
+    }
+
+    let inject_crate = match cratename {
+        Some(cratename) => !opts.no_crate_inject && cratename != "std" && s.contains(cratename),
+        None => false,
+    };
+    // Only blank lines and comments can come before the code, since crate attributes aren't
+    // allowed here.
+    let line_offset = 1 + crate_attrs.lines().count();
+    if everything_else.trim_end().ends_with("(())") {
+        let body = format!(
+            "fn _inner() -> Result<(), impl core::fmt::Debug> {{\n{}\n}}\n_inner().unwrap()",
+            everything_else
+        );
+        Some((body, inject_crate, line_offset - 1))
     } else {
-        let returns_result = everything_else.trim_end().ends_with("(())");
-        let (main_pre, main_post) = if returns_result {
-            (
-                "fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {",
-                "}\n_inner().unwrap() }",
-            )
-        } else {
-            ("fn main() {\n", "\n}")
-        };
-        prog.extend([main_pre, everything_else, main_post].iter().cloned());
-        line_offset += 1;
+        Some((everything_else.to_string(), inject_crate, line_offset))
     }
+}
 
-    debug!("final doctest:\n{}", prog);
+/// Builds the source of a merged doctest binary, with one function per test in `tests`. The
+/// binary runs the test whose index it is given as its only argument.
+///
+/// Also returns the lines of the source that the body of each test spans.
+fn make_merged_source(
+    tests: &[String],
+    inject_crate: Option<&str>,
+    opts: &TestOptions,
+) -> (String, Vec<Range<usize>>) {
+    let mut prog = String::new();
 
-    (prog, line_offset)
+    // The same crate attributes as `make_test` adds to each test.
+    if opts.attrs.is_empty() && !opts.display_warnings {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    if let Some(cratename) = inject_crate {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+
+    let mut lines = Vec::with_capacity(tests.len());
+    for (i, test) in tests.iter().enumerate() {
+        // The body starts on the line after the function signature.
+        let start = prog.matches('\n').count() + 2;
+        lines.push(start..start + test.lines().count());
+        prog.push_str(&format!("fn __doctest_{}() {{\n{}\n}}\n", i, test));
+    }
+
+    prog.push_str("fn main() {\n");
+    prog.push_str("    match ::std::env::args().nth(1).as_ref().map(|s| &**s) {\n");
+    for i in 0..tests.len() {
+        prog.push_str(&format!("        Some(\"{}\") => __doctest_{}(),\n", i, i));
+    }
+    prog.push_str("        _ => panic!(\"unknown doctest\"),\n");
+    prog.push_str("    }\n");
+    prog.push_str("}\n");
+
+    debug!("merged doctests:\n{}", prog);
+
+    (prog, lines)
+}
+
+/// Rewrites the locations in `output` that point into the lines `lines` of the merged doctest
+/// binary source `source_name`, like those of panics, to point into `filename` instead, where the
+/// first of these lines is `first_line`.
+fn fix_merged_locations(
+    output: &str,
+    source_name: &str,
+    lines: &Range<usize>,
+    filename: &str,
+    first_line: usize,
+) -> String {
+    let pattern = format!("{}:", source_name);
+    let mut fixed = String::with_capacity(output.len());
+    let mut rest = output;
+    while let Some(pos) = rest.find(&pattern) {
+        fixed.push_str(&rest[..pos]);
+        rest = &rest[pos + pattern.len()..];
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        match rest[..digits].parse::<usize>() {
+            Ok(line) if lines.contains(&line) => {
+                fixed.push_str(&format!("{}:{}", filename, first_line + line - lines.start));
+                rest = &rest[digits..];
+            }
+            _ => fixed.push_str(&pattern),
+        }
+    }
+    fixed.push_str(rest);
+    fixed
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -597,6 +808,125 @@ fn partition_source(s: &str) -> (String, String, String) {
     (before, after, crates)
 }
 
+/// Doctests of one edition that are compiled together into a single binary.
+///
+/// The binary is built by the first of the tests to run, once all of them have been collected.
+/// Each test then runs the binary with its index as argument, so that the tests still run in
+/// separate processes. If the binary fails to build, for example because two of the tests export
+/// the same `#[no_mangle]` symbol, every test falls back to being compiled on its own.
+///
+/// Since the binary is built from generated source, the locations that failing tests report are
+/// rewritten to point into the documentation, as if the tests had been compiled on their own.
+struct MergedDoctests {
+    edition: Edition,
+    cratename: String,
+    options: Options,
+    opts: TestOptions,
+    state: Mutex<MergedState>,
+}
+
+enum MergedState {
+    Collecting { tests: Vec<String>, inject_crate: bool },
+    /// The directory containing the binary and the lines of its source that each test spans, or
+    /// `None` if it failed to build.
+    Built(Option<(DirState, Vec<Range<usize>>)>),
+}
+
+impl MergedDoctests {
+    fn new(edition: Edition, cratename: String, options: Options, opts: TestOptions) -> Self {
+        MergedDoctests {
+            edition,
+            cratename,
+            options,
+            opts,
+            state: Mutex::new(MergedState::Collecting { tests: Vec::new(), inject_crate: false }),
+        }
+    }
+
+    /// Adds the body of a test to the binary, and returns the index to run it with.
+    fn add(&self, body: String, needs_crate: bool) -> usize {
+        match *self.state.lock().unwrap() {
+            MergedState::Collecting { ref mut tests, ref mut inject_crate } => {
+                tests.push(body);
+                *inject_crate |= needs_crate;
+                tests.len() - 1
+            }
+            MergedState::Built(_) => panic!("doctest added to an already built binary"),
+        }
+    }
+
+    /// Returns the path of the binary, building it first if needed, or `None` if it couldn't be
+    /// built.
+    fn binary(&self) -> Option<PathBuf> {
+        let mut state = self.state.lock().unwrap();
+        if let MergedState::Collecting { ref tests, inject_crate } = *state {
+            let cratename = if inject_crate { Some(&self.cratename[..]) } else { None };
+            let (source, lines) = make_merged_source(tests, cratename, &self.opts);
+            *state = MergedState::Built(self.build(&source).map(|outdir| (outdir, lines)));
+        }
+        match *state {
+            MergedState::Built(ref built) => built.as_ref().map(|(d, _)| d.path().join("rust_out")),
+            MergedState::Collecting { .. } => unreachable!(),
+        }
+    }
+
+    /// The file name that the source of the binary is compiled as.
+    fn source_name(&self) -> String {
+        format!("merged_doctests_{}.rs", self.edition)
+    }
+
+    fn build(&self, source: &str) -> Option<DirState> {
+        let outdir = DirState::new(&self.options, format!("merged_doctests_{}", self.edition));
+        let output_file = outdir.path().join("rust_out");
+        let mut compiler = rustc_command(&self.options, self.edition, &output_file);
+        compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", self.source_name());
+        compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", "0");
+        let mut child = compiler.spawn().expect("Failed to spawn rustc process");
+        {
+            let stdin = child.stdin.as_mut().expect("Failed to open stdin");
+            stdin.write_all(source.as_bytes()).expect("could write out test sources");
+        }
+        let output = child.wait_with_output().expect("Failed to read stdout");
+        if output.status.success() {
+            Some(outdir)
+        } else {
+            debug!(
+                "merged doctests failed to build, compiling them separately:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            None
+        }
+    }
+
+    /// Rewrites the locations in the output of the failed test `index` to point into `filename`,
+    /// where the body of the test starts at `first_line`.
+    fn fix_locations(
+        &self,
+        err: TestFailure,
+        index: usize,
+        filename: &FileName,
+        first_line: usize,
+    ) -> TestFailure {
+        let mut out = match err {
+            TestFailure::ExecutionFailure(out) => out,
+            err => return err,
+        };
+        let state = self.state.lock().unwrap();
+        if let MergedState::Built(Some((_, ref lines))) = *state {
+            let source_name = self.source_name();
+            let filename = filename.to_string();
+            let fix = |output: &[u8]| {
+                let output = String::from_utf8_lossy(output);
+                fix_merged_locations(&output, &source_name, &lines[index], &filename, first_line)
+                    .into_bytes()
+            };
+            out.stdout = fix(&out.stdout);
+            out.stderr = fix(&out.stderr);
+        }
+        TestFailure::ExecutionFailure(out)
+    }
+}
+
 pub trait Tester {
     fn add_test(&mut self, test: String, config: LangString, line: usize);
     fn get_line(&self) -> usize {
@@ -638,6 +968,8 @@ pub struct Collector {
     position: Span,
     source_map: Option<Lrc<SourceMap>>,
     filename: Option<PathBuf>,
+    /// The binaries that doctests are merged into with `--merge-doctests`, one per edition.
+    merged: Vec<Arc<MergedDoctests>>,
 }
 
 impl Collector {
@@ -661,9 +993,46 @@ impl Collector {
             position: DUMMY_SP,
             source_map,
             filename,
+            merged: Vec::new(),
         }
     }
 
+    /// Adds `test` to the merged binary for its edition if it can be merged into one, and returns
+    /// that binary along with the index of the test in it and the number of lines between the
+    /// opening fence of the code block and the body of the test.
+    fn merge_test(
+        &mut self,
+        test: &str,
+        config: &LangString,
+        edition: Edition,
+    ) -> Option<(Arc<MergedDoctests>, usize, usize)> {
+        if !self.options.merge_doctests
+            || config.compile_fail
+            || config.test_harness
+            || config.allow_fail
+        {
+            return None;
+        }
+        let (body, needs_crate, line_offset) =
+            make_merged_test(test, Some(&self.cratename), &self.opts, edition)?;
+
+        let merged = match self.merged.iter().find(|m| m.edition == edition) {
+            Some(merged) => merged.clone(),
+            None => {
+                let merged = Arc::new(MergedDoctests::new(
+                    edition,
+                    self.cratename.clone(),
+                    self.options.clone(),
+                    self.opts.clone(),
+                ));
+                self.merged.push(merged.clone());
+                merged
+            }
+        };
+        let index = merged.add(body, needs_crate);
+        Some((merged, index, line_offset))
+    }
+
     fn generate_name(&self, line: usize, filename: &FileName) -> String {
         format!("{} - {} (line {})", filename, self.names.join("::"), line)
     }
@@ -701,41 +1070,66 @@ impl Tester for Collector {
         let options = self.options.clone();
         let runtool = self.options.runtool.clone();
         let runtool_args = self.options.runtool_args.clone();
-        let target = self.options.target.clone();
-        let target_str = target.to_string();
+        let target_str = self.options.target.to_string();
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+        // Ignored tests are often not meant to compile, so they are never merged.
+        let merged = if ignore { None } else { self.merge_test(&test, &config, edition) };
+        // The test starts after the opening fence of its code block, and ends with its closing one.
+        let end_line = line + test.lines().count() + 1;
 
         debug!("creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
+                ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
                 test_type: testing::TestType::DocTest,
             },
             testfn: testing::DynTestFn(box move || {
-                let res = run_test(
-                    &test,
-                    &cratename,
-                    &filename,
-                    line,
-                    options,
-                    config.should_panic,
-                    config.no_run,
-                    config.test_harness,
-                    runtool,
-                    runtool_args,
-                    target,
-                    config.compile_fail,
-                    config.error_codes,
-                    &opts,
-                    edition,
-                );
+                let mut details = testing::TestDetails {
+                    source_file: filename.to_string(),
+                    start_line: line,
+                    end_line,
+                    ..testing::TestDetails::default()
+                };
+                let binary = merged.and_then(|merged| Some((merged.0.binary()?, merged)));
+                let res = match binary {
+                    Some(_) if config.no_run => Ok(()),
+                    Some((binary, (merged, index, line_offset))) => run_binary(
+                        &binary,
+                        Some(index.to_string()),
+                        runtool,
+                        runtool_args,
+                        config.should_panic,
+                        &mut details,
+                    )
+                    .map_err(|err| merged.fix_locations(err, index, &filename, line + line_offset)),
+                    None => run_test(
+                        &test,
+                        &cratename,
+                        &filename,
+                        line,
+                        options,
+                        config.should_panic,
+                        config.no_run,
+                        config.test_harness,
+                        runtool,
+                        runtool_args,
+                        config.compile_fail,
+                        config.error_codes,
+                        &opts,
+                        edition,
+                        &mut details,
+                    ),
+                };
+                details.failure_kind = res.as_ref().err().map(|err| err.kind().to_string());
+                testing::set_test_details(details);
 
                 if let Err(err) = res {
                     match err {
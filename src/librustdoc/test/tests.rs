@@ -1,4 +1,4 @@
-use super::{make_test, TestOptions};
+use super::{fix_merged_locations, make_merged_source, make_merged_test, make_test, TestOptions};
 use rustc_span::edition::DEFAULT_EDITION;
 
 #[test]
@@ -277,3 +277,93 @@ test_wrapper! {
     let output = make_test(input, Some("my_crate"), false, &opts, DEFAULT_EDITION);
     assert_eq!(output, (expected, 1));
 }
+
+#[test]
+fn make_merged_test_basic() {
+    // Mergeable tests are used as the body of a function as is, and report whether they need the
+    // crate to be injected.
+    let opts = TestOptions::default();
+    let input = "use asdf::qwop;
+assert_eq!(2+2, 4);";
+    let output = make_merged_test(input, Some("asdf"), &opts, DEFAULT_EDITION);
+    assert_eq!(output, Some((input.to_string(), true, 1)));
+
+    let output = make_merged_test("assert_eq!(2+2, 4);", Some("asdf"), &opts, DEFAULT_EDITION);
+    assert_eq!(output, Some(("assert_eq!(2+2, 4);".to_string(), false, 1)));
+}
+
+#[test]
+fn make_merged_test_line_offset() {
+    // Leading comments aren't part of the body, and the `_inner` wrapper takes up one more line.
+    let opts = TestOptions::default();
+    let input = "// a comment
+
+assert_eq!(2+2, 4);";
+    let output = make_merged_test(input, None, &opts, DEFAULT_EDITION);
+    assert_eq!(output, Some(("assert_eq!(2+2, 4);".to_string(), false, 3)));
+
+    let input = "let x: u32 = \"4\".parse()?;
+Ok::<(), std::num::ParseIntError>(())";
+    let expected = "fn _inner() -> Result<(), impl core::fmt::Debug> {
+let x: u32 = \"4\".parse()?;
+Ok::<(), std::num::ParseIntError>(())
+}
+_inner().unwrap()";
+    let output = make_merged_test(input, None, &opts, DEFAULT_EDITION);
+    assert_eq!(output, Some((expected.to_string(), false, 0)));
+}
+
+#[test]
+fn make_merged_test_unmergeable() {
+    // Crate attributes, `extern crate` declarations and custom `fn main`s only work at the root of
+    // a crate, so such tests are compiled separately.
+    let opts = TestOptions::default();
+    for input in &[
+        "#![feature(asdf)]
+assert_eq!(2+2, 4);",
+        "extern crate asdf;
+assert_eq!(asdf::foo, 4);",
+        "fn main() {
+    assert_eq!(2+2, 4);
+}",
+    ] {
+        assert_eq!(make_merged_test(input, Some("asdf"), &opts, DEFAULT_EDITION), None);
+    }
+}
+
+#[test]
+fn make_merged_source_basic() {
+    let opts = TestOptions::default();
+    let tests = vec!["assert_eq!(2+2, 4);".to_string(), "asdf::foo();".to_string()];
+    let expected = "#![allow(unused)]
+extern crate asdf;
+fn __doctest_0() {
+assert_eq!(2+2, 4);
+}
+fn __doctest_1() {
+asdf::foo();
+}
+fn main() {
+    match ::std::env::args().nth(1).as_ref().map(|s| &**s) {
+        Some(\"0\") => __doctest_0(),
+        Some(\"1\") => __doctest_1(),
+        _ => panic!(\"unknown doctest\"),
+    }
+}
+"
+    .to_string();
+
+    let output = make_merged_source(&tests, Some("asdf"), &opts);
+    assert_eq!(output, (expected, vec![4..5, 7..8]));
+}
+
+#[test]
+fn fix_merged_locations_basic() {
+    // Only locations in the lines of the test are rewritten.
+    let output = "thread 'main' panicked at 'oops', merged_doctests_2018.rs:5:1
+note: merged_doctests_2018.rs:12:5 and merged_doctests_2018.rs:";
+    let expected = "thread 'main' panicked at 'oops', src/lib.rs:13:1
+note: merged_doctests_2018.rs:12:5 and merged_doctests_2018.rs:";
+    let output = fix_merged_locations(output, "merged_doctests_2018.rs", &(4..6), "src/lib.rs", 12);
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,313 @@
+//! Rustdoc's man page backend
+//!
+//! Each page is written to `man3/<name>.3`, where `<name>` is the path of the item's module
+//! joined with dots, followed by the item's type and name, like `krate.module.struct.Name` (the
+//! page of the crate itself is just `krate`). The pages can be read with
+//! `man -M <output directory> <name>`, and link to each other in their "SEE ALSO" sections.
+
+use std::path::PathBuf;
+
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::html::item_type::ItemType;
+use crate::html::markdown::{is_rust_code_block, opts, plain_summary_line, strip_hidden_lines};
+use crate::html::render::{Error, RenderInfo};
+use crate::text::{self, Context, Format, LinkTarget, Page};
+
+/// Renders `krate` as man pages in the `man3` directory of the output directory.
+crate fn run(
+    krate: clean::Crate,
+    options: RenderOptions,
+    renderinfo: RenderInfo,
+) -> Result<(), Error> {
+    text::run::<ManPage>(krate, options, renderinfo)
+}
+
+struct ManPage {
+    out: String,
+    /// Whether the page has any section yet, after its name.
+    has_section: bool,
+    /// The pages referenced by the intra-doc links of the page.
+    see_also: Vec<String>,
+}
+
+impl ManPage {
+    /// Returns the roff for the documentation of `item`, recording the pages it links to.
+    fn roff(&mut self, cx: &Context<'_>, item: &clean::Item) -> String {
+        let links: Vec<_> = cx
+            .links(item)
+            .into_iter()
+            .map(|(s, target)| match target {
+                LinkTarget::Page(path, ty) => {
+                    let page = page_name(&path, ty);
+                    if !self.see_also.contains(&page) {
+                        self.see_also.push(page.clone());
+                    }
+                    (s, format!("{}(3)", page))
+                }
+                LinkTarget::Url(url) => (s, url),
+            })
+            .collect();
+        item.doc_value().map_or(String::new(), |doc| markdown_to_roff(doc, &links))
+    }
+}
+
+impl Format for ManPage {
+    fn new(cx: &Context<'_>, page: &Page<'_>) -> Self {
+        let name = page.path.join("::");
+        let title = page_name(&page.path, page.ty);
+        let source = match cx.cache.crate_version {
+            Some(ref version) => format!("{} {}", cx.krate.name, version),
+            None => cx.krate.name.clone(),
+        };
+        let summary = plain_summary_line(page.item.doc_value().unwrap_or(""));
+        let mut out = format!(
+            ".TH \"{}\" 3 \"\" \"{}\" \"Rust Documentation\"\n.SH NAME\n{}",
+            title,
+            source,
+            escape(&name)
+        );
+        if !summary.is_empty() {
+            out.push_str(&format!(" \\- {}", escape(&summary)));
+        }
+        out.push('\n');
+        ManPage { out, has_section: false, see_also: Vec::new() }
+    }
+
+    fn file_path(path: &[String], ty: ItemType) -> PathBuf {
+        ["man3".to_string(), format!("{}.3", page_name(path, ty))].iter().collect()
+    }
+
+    fn section(&mut self, title: &str) {
+        self.has_section = true;
+        self.out.push_str(&format!(".SH \"{}\"\n", title.to_uppercase()));
+    }
+
+    fn code(&mut self, code: &str) {
+        if !self.has_section {
+            self.section("Synopsis");
+        }
+        self.out.push_str(&format!(".PP\n.nf\n{}\n.fi\n", escape(code)));
+    }
+
+    fn docs(&mut self, cx: &Context<'_>, item: &clean::Item) {
+        let roff = self.roff(cx, item);
+        if !roff.is_empty() {
+            self.section("Description");
+            self.out.push_str(&roff);
+        }
+    }
+
+    fn entry(&mut self, cx: &Context<'_>, declaration: &str, item: &clean::Item) {
+        let declaration = match declaration {
+            "" => item.name.as_ref().map_or("", |name| name.as_str()),
+            declaration => declaration,
+        };
+        self.out.push_str(&format!(".PP\n.nf\n\\fB{}\\fR\n.fi\n", escape(declaration)));
+        let roff = self.roff(cx, item);
+        if !roff.is_empty() {
+            self.out.push_str(&format!(".RS 4\n{}.RE\n", roff));
+        }
+    }
+
+    fn listing(&mut self, path: &[String], ty: ItemType, summary: &str) {
+        self.out.push_str(&format!(".TP\n.BR {} (3)\n", page_name(path, ty)));
+        if !summary.is_empty() {
+            self.out.push_str(&format!("{}\n", escape(summary)));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.see_also.is_empty() {
+            self.section("See Also");
+            let pages: Vec<_> =
+                self.see_also.iter().map(|page| format!(".BR {} (3)", page)).collect();
+            self.out.push_str(&pages.join(",\n"));
+            self.out.push('\n');
+        }
+        self.out
+    }
+}
+
+/// Returns the name of the page of the item with the full path `path` and type `ty`, like
+/// `krate.module.struct.Name`. The type keeps items of different namespaces apart, like a module
+/// and a macro with the same name, and dots are allowed in file names on every platform.
+crate fn page_name(path: &[String], ty: ItemType) -> String {
+    match path.split_last() {
+        Some((name, [])) => name.clone(),
+        Some((name, parents)) => format!("{}.{}.{}", parents.join("."), ty.as_str(), name),
+        None => String::new(),
+    }
+}
+
+/// Escapes `text` so it is printed as is by roff, including at the start of a line.
+fn escape(text: &str) -> String {
+    let lines: Vec<_> = text
+        .replace('\\', "\\e")
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Converts the Markdown documentation `md` of an item to roff. The links to the keys of `links`
+/// are written with their text, followed by the target in parentheses.
+crate fn markdown_to_roff(md: &str, links: &[(String, String)]) -> String {
+    let replacer = |_: &str, s: &str| {
+        links.iter().find(|link| &*link.0 == s).map(|link| (link.1.clone(), s.to_owned()))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, opts(), Some(&replacer));
+    let mut w = RoffWriter { out: String::new(), lists: Vec::new(), item_start: false };
+    let mut code: Option<(bool, String)> = None;
+    let mut link_targets: Vec<String> = Vec::new();
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                if w.item_start {
+                    w.item_start = false;
+                } else if !w.lists.is_empty() {
+                    w.request(".sp");
+                } else {
+                    w.request(".PP");
+                }
+            }
+            Event::Start(Tag::Heading(_)) => {
+                w.line_start();
+                w.out.push_str(".SS ");
+            }
+            Event::Start(Tag::BlockQuote) => w.request(".RS 4"),
+            Event::End(Tag::BlockQuote) => w.request(".RE"),
+            Event::Start(Tag::CodeBlock(kind)) => {
+                code = Some((is_rust_code_block(&kind), String::new()));
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some((rust, contents)) = code.take() {
+                    let contents = if rust { strip_hidden_lines(&contents) } else { contents };
+                    w.request(if w.lists.is_empty() { ".PP" } else { ".sp" });
+                    w.request(".RS 4");
+                    w.request(".nf");
+                    w.out.push_str(&escape(contents.trim_end_matches('\n')));
+                    w.request(".fi");
+                    w.request(".RE");
+                }
+            }
+            Event::Start(Tag::List(first)) => {
+                if !w.lists.is_empty() {
+                    w.request(".RS 2");
+                }
+                w.lists.push(first);
+            }
+            Event::End(Tag::List(_)) => {
+                w.lists.pop();
+                if !w.lists.is_empty() {
+                    w.request(".RE");
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let bullet = match w.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!(".IP {}. 4", *n - 1)
+                    }
+                    _ => ".IP \\(bu 2".to_string(),
+                };
+                w.request(&bullet);
+                w.item_start = true;
+            }
+            Event::End(Tag::Item) => w.item_start = false,
+            Event::Start(Tag::Emphasis) => w.out.push_str("\\fI"),
+            Event::Start(Tag::Strong) => w.out.push_str("\\fB"),
+            Event::End(Tag::Emphasis) | Event::End(Tag::Strong) => w.out.push_str("\\fR"),
+            Event::Start(Tag::Link(_, dest, _)) => {
+                let target = links
+                    .iter()
+                    .find(|link| link.0 == *dest || link.1 == *dest)
+                    .map_or_else(|| dest.to_string(), |link| link.1.clone());
+                link_targets.push(target);
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some(target) = link_targets.pop() {
+                    w.text(&format!(" ({})", target));
+                }
+            }
+            Event::Start(Tag::Image(_, dest, _)) => {
+                link_targets.push(dest.to_string());
+            }
+            Event::End(Tag::Image(..)) => {
+                if let Some(target) = link_targets.pop() {
+                    w.text(&format!(" ({})", target));
+                }
+            }
+            Event::Start(Tag::TableRow) | Event::Start(Tag::TableHead) => w.request(".br"),
+            Event::End(Tag::TableCell) => w.text(" | "),
+            Event::Start(Tag::Table(_)) => w.request(".PP"),
+            Event::Text(text) => match code {
+                Some((_, ref mut contents)) => contents.push_str(&text),
+                None => w.text(&text),
+            },
+            Event::Code(text) => {
+                w.out.push_str("\\fB");
+                w.text(&text);
+                w.out.push_str("\\fR");
+            }
+            Event::SoftBreak => w.out.push('\n'),
+            Event::HardBreak => w.request(".br"),
+            Event::Rule => w.request(".PP"),
+            Event::FootnoteReference(name) => w.text(&format!("[{}]", name)),
+            Event::Start(Tag::FootnoteDefinition(name)) => {
+                w.request(".PP");
+                w.text(&format!("[{}]: ", name));
+                w.item_start = true;
+            }
+            Event::TaskListMarker(done) => w.text(if done { "[x] " } else { "[ ] " }),
+            _ => {}
+        }
+    }
+
+    w.line_start();
+    w.out
+}
+
+struct RoffWriter {
+    out: String,
+    /// The lists the writer is in, with the number of their next item if they are ordered.
+    lists: Vec<Option<u64>>,
+    /// Whether the writer is at the start of a list item, which doesn't need a new paragraph.
+    item_start: bool,
+}
+
+impl RoffWriter {
+    /// Ends the current line, if any.
+    fn line_start(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    /// Writes a request, like `.PP`, on its own line.
+    fn request(&mut self, request: &str) {
+        self.line_start();
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    /// Writes text, escaping it so it isn't read as a request.
+    fn text(&mut self, text: &str) {
+        let text = text.replace('\\', "\\e");
+        if (self.out.is_empty() || self.out.ends_with('\n'))
+            && (text.starts_with('.') || text.starts_with('\''))
+        {
+            self.out.push_str("\\&");
+        }
+        self.out.push_str(&text);
+    }
+}
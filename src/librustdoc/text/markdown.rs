@@ -0,0 +1,220 @@
+//! Rustdoc's Markdown backend
+//!
+//! Each page is written to a `.md` file at the same place as the HTML page would be, like
+//! `krate/module/struct.Name.md`, so the links between pages are relative paths.
+
+use std::ops::Range;
+use std::path::PathBuf;
+
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::html::item_type::ItemType;
+use crate::html::markdown::{is_rust_code_block, opts, strip_hidden_lines};
+use crate::html::render::{page_kind, Error, RenderInfo};
+use crate::text::{self, Context, Format, LinkTarget, Page};
+
+/// Renders `krate` as a tree of Markdown files in the output directory.
+crate fn run(
+    krate: clean::Crate,
+    options: RenderOptions,
+    renderinfo: RenderInfo,
+) -> Result<(), Error> {
+    text::run::<MarkdownPage>(krate, options, renderinfo)
+}
+
+struct MarkdownPage {
+    out: String,
+    /// The number of directories between the output directory and the file of the page.
+    depth: usize,
+}
+
+impl MarkdownPage {
+    fn href(&self, path: &[String], ty: ItemType) -> String {
+        let file = Self::file_path(path, ty);
+        let components: Vec<_> = file.iter().map(|c| c.to_string_lossy()).collect();
+        format!("{}{}", "../".repeat(self.depth), components.join("/"))
+    }
+
+    fn links(&self, cx: &Context<'_>, item: &clean::Item) -> Vec<(String, String)> {
+        cx.links(item)
+            .into_iter()
+            .map(|(s, target)| match target {
+                LinkTarget::Page(path, ty) => (s, self.href(&path, ty)),
+                LinkTarget::Url(url) => (s, url),
+            })
+            .collect()
+    }
+
+    fn end_block(&mut self) {
+        while !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+}
+
+impl Format for MarkdownPage {
+    fn new(_: &Context<'_>, page: &Page<'_>) -> Self {
+        let depth = match page.ty {
+            ItemType::Module => page.path.len(),
+            _ => page.path.len() - 1,
+        };
+        let out = format!("# {}{}\n\n", page_kind(page.item), page.path.join("::"));
+        MarkdownPage { out, depth }
+    }
+
+    fn file_path(path: &[String], ty: ItemType) -> PathBuf {
+        let mut file: PathBuf = path[..path.len() - 1].iter().collect();
+        match ty {
+            ItemType::Module => {
+                file.push(path.last().unwrap());
+                file.push("index.md");
+            }
+            _ => file.push(format!("{}.{}.md", ty.as_str(), path.last().unwrap())),
+        }
+        file
+    }
+
+    fn section(&mut self, title: &str) {
+        self.end_block();
+        self.out.push_str(&format!("## {}\n\n", title));
+    }
+
+    fn code(&mut self, code: &str) {
+        self.end_block();
+        self.out.push_str(&format!("```rust\n{}\n```\n\n", code));
+    }
+
+    fn docs(&mut self, cx: &Context<'_>, item: &clean::Item) {
+        if let Some(doc) = item.doc_value() {
+            self.end_block();
+            self.out.push_str(&rewrite_docs(doc, &self.links(cx, item), 1));
+        }
+    }
+
+    fn entry(&mut self, cx: &Context<'_>, declaration: &str, item: &clean::Item) {
+        self.end_block();
+        self.out.push_str(&format!("### {}\n\n", item.name.as_ref().map_or("", |n| n.as_str())));
+        if !declaration.is_empty() {
+            self.code(declaration);
+        }
+        if let Some(doc) = item.doc_value() {
+            self.end_block();
+            self.out.push_str(&rewrite_docs(doc, &self.links(cx, item), 3));
+        }
+    }
+
+    fn listing(&mut self, path: &[String], ty: ItemType, summary: &str) {
+        let name = path.last().unwrap();
+        let href = self.href(path, ty);
+        if summary.is_empty() {
+            self.out.push_str(&format!("- [{}]({})\n", name, href));
+        } else {
+            self.out.push_str(&format!("- [{}]({}): {}\n", name, href, summary));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.out.truncate(self.out.trim_end().len());
+        self.out.push('\n');
+        self.out
+    }
+}
+
+/// Rewrites the documentation `md` of an item so it can be included in a Markdown page:
+///
+/// * the headings are moved down by `heading_offset` levels, up to level 6,
+/// * the hidden lines of Rust code blocks are removed, and their language is set to `rust`,
+/// * the intra-doc links are replaced with the targets given in `links`.
+///
+/// The rest of the documentation is kept as it was written.
+crate fn rewrite_docs(md: &str, links: &[(String, String)], heading_offset: u32) -> String {
+    let replacer = |_: &str, s: &str| {
+        links.iter().find(|link| &*link.0 == s).map(|link| (link.1.clone(), s.to_owned()))
+    };
+    let parser = Parser::new_with_broken_link_callback(md, opts(), Some(&replacer));
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    // The link being rewritten, with the range of its text.
+    let mut link: Option<(Range<usize>, String, Option<Range<usize>>)> = None;
+    // The code block being rewritten, with its contents.
+    let mut code: Option<(Range<usize>, String)> = None;
+
+    for (event, range) in parser.into_offset_iter() {
+        if let Some((_, _, ref mut text)) = link {
+            match event {
+                Event::End(Tag::Link(..)) => {}
+                _ => {
+                    let start = text.as_ref().map_or(range.start, |text| text.start);
+                    *text = Some(start..range.end);
+                }
+            }
+        }
+        match event {
+            Event::Start(Tag::Heading(level)) => {
+                let extra = (level + heading_offset).min(6) - level;
+                if md[range.start..].starts_with('#') {
+                    edits.push((range.start..range.start, "#".repeat(extra as usize)));
+                } else {
+                    // Setext headings are replaced with ATX headings.
+                    let source = md[range.clone()].trim_end();
+                    let text = source.rsplitn(2, '\n').nth(1).unwrap_or(source).trim();
+                    let hashes = "#".repeat((level + extra) as usize);
+                    edits.push((range.start..range.start + source.len(), hashes + " " + text));
+                }
+            }
+            Event::Start(Tag::CodeBlock(ref kind)) if is_rust_code_block(kind) => {
+                code = Some((range, String::new()));
+            }
+            Event::Text(ref text) => {
+                if let Some((_, ref mut contents)) = code {
+                    contents.push_str(text);
+                }
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some((range, contents)) = code.take() {
+                    let source = md[range.clone()].trim_end();
+                    let line_start = md[..range.start].rfind('\n').map_or(0, |i| i + 1);
+                    let indent: String = md[line_start..range.start]
+                        .chars()
+                        .map(|c| if c == '>' { '>' } else { ' ' })
+                        .collect();
+                    let block = format!("```rust\n{}\n```", strip_hidden_lines(&contents));
+                    let block = block.replace('\n', &format!("\n{}", indent));
+                    edits.push((range.start..range.start + source.len(), block));
+                }
+            }
+            Event::Start(Tag::Link(_, ref dest, _)) => {
+                let target = links
+                    .iter()
+                    .find(|link| link.0 == **dest || link.1 == **dest)
+                    .map(|link| link.1.clone());
+                if let Some(target) = target {
+                    link = Some((range, target, None));
+                }
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some((range, target, text)) = link.take() {
+                    let text = text.map_or("", |text| &md[text]);
+                    edits.push((range, format!("[{}]({})", text, target)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    edits.sort_by_key(|edit| edit.0.start);
+    let mut out = String::with_capacity(md.len());
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        if range.start < pos {
+            continue;
+        }
+        out.push_str(&md[pos..range.start]);
+        out.push_str(&replacement);
+        pos = range.end;
+    }
+    out.push_str(&md[pos..]);
+    out
+}
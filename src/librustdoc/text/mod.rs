@@ -0,0 +1,572 @@
+//! Rustdoc's plain text backends
+//!
+//! This module contains the logic shared by the Markdown and man page outputs. Both write a page
+//! for each module and item that has its own page in the HTML output, from the same cleaned crate.
+//! The fields, variants and associated items of an item, as well as its implementations, are
+//! documented on the page of the item, like in the HTML output.
+
+crate mod man;
+crate mod markdown;
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::path::PathBuf;
+
+use rustc_hir::def_id::DefId;
+use rustc_span::hygiene::MacroKind;
+
+use crate::clean;
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::doctree::StructType;
+use crate::html::format::{print_abi_with_space, print_generic_bounds, PrintWithSpace};
+use crate::html::format::{Function, WhereClause};
+use crate::html::item_type::ItemType;
+use crate::html::markdown::plain_summary_line;
+use crate::html::render::{item_ty_to_strs, Cache, Error, ExternalLocation, Impl, RenderInfo};
+
+/// A module or item with its own page.
+crate struct Page<'a> {
+    crate item: &'a clean::Item,
+    /// The full path of the item, starting with the name of the crate.
+    crate path: Vec<String>,
+    crate ty: ItemType,
+}
+
+/// The target of an intra-doc link.
+crate enum LinkTarget {
+    /// The page of a module or item in the same output directory, by its path and kind.
+    Page(Vec<String>, ItemType),
+    /// A page of HTML documentation.
+    Url(String),
+}
+
+/// An output format of the plain text backends. Each page is written by its own value.
+crate trait Format {
+    /// Starts the page of an item.
+    fn new(cx: &Context<'_>, page: &Page<'_>) -> Self;
+
+    /// Returns the path of the file of the page of an item, relative to the output directory.
+    fn file_path(path: &[String], ty: ItemType) -> PathBuf;
+
+    /// Starts a section of the page, like "Fields" or "Implementations".
+    fn section(&mut self, title: &str);
+
+    /// Adds a block of Rust code to the page.
+    fn code(&mut self, code: &str);
+
+    /// Adds the documentation of the item of the page.
+    fn docs(&mut self, cx: &Context<'_>, item: &clean::Item);
+
+    /// Adds an item that is documented on the page, like a field or a method, with its
+    /// declaration and documentation.
+    fn entry(&mut self, cx: &Context<'_>, declaration: &str, item: &clean::Item);
+
+    /// Adds a link to the page of an item in a module, along with the first line of its
+    /// documentation.
+    fn listing(&mut self, path: &[String], ty: ItemType, summary: &str);
+
+    /// Returns the contents of the page.
+    fn finish(self) -> String;
+}
+
+/// Renders `krate` with the format `F`, writing one file for each page.
+crate fn run<F: Format>(
+    krate: clean::Crate,
+    options: RenderOptions,
+    renderinfo: RenderInfo,
+) -> Result<(), Error> {
+    let RenderOptions { output, extern_html_root_urls, .. } = &options;
+    let (krate, _, _, cache) =
+        Cache::from_krate(renderinfo, extern_html_root_urls, output, krate, false);
+    let cx = Context { cache: &cache, krate: &krate };
+
+    let root = krate.module.as_ref().expect("no root module in the crate");
+    let mut pages = Vec::new();
+    collect_pages(root, &mut Vec::new(), &mut pages);
+
+    for page in &pages {
+        let mut f = F::new(&cx, page);
+        render_page(&cx, page, &mut f);
+
+        let path = output.join(F::file_path(&page.path, page.ty));
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| Error::new(e, dir))?;
+        }
+        fs::write(&path, f.finish()).map_err(|e| Error::new(e, &path))?;
+    }
+    Ok(())
+}
+
+crate struct Context<'a> {
+    crate cache: &'a Cache,
+    crate krate: &'a clean::Crate,
+}
+
+impl Context<'_> {
+    /// Returns the targets of the intra-doc links in the documentation of `item`, by the text of
+    /// the links. The links to items whose documentation can't be found are left out.
+    crate fn links(&self, item: &clean::Item) -> Vec<(String, LinkTarget)> {
+        item.attrs
+            .links
+            .iter()
+            .filter_map(|&(ref s, did, ref fragment)| {
+                let target = match did {
+                    Some(did) => self.link_target(did, fragment.as_ref())?,
+                    // Links to primitives without a local page have no `DefId`.
+                    None => LinkTarget::Url(self.primitive_url(item, fragment.as_ref()?)),
+                };
+                Some((s.clone(), target))
+            })
+            .collect()
+    }
+
+    fn link_target(&self, did: DefId, fragment: Option<&String>) -> Option<LinkTarget> {
+        if !did.is_local() && !self.cache.access_levels.is_public(did) {
+            return None;
+        }
+        if let Some(&(ref path, ty)) = self.cache.paths.get(&did) {
+            return Some(LinkTarget::Page(path.clone(), ty));
+        }
+
+        let &(ref path, ty) = self.cache.external_paths.get(&did)?;
+        match self.cache.extern_locations[&did.krate] {
+            (.., ExternalLocation::Local) => Some(LinkTarget::Page(path.clone(), ty)),
+            (.., ExternalLocation::Remote(ref root)) => {
+                let mut url = root.clone();
+                if !url.ends_with('/') {
+                    url.push('/');
+                }
+                url.push_str(&html_path(path, ty));
+                if let Some(fragment) = fragment {
+                    url.push('#');
+                    url.push_str(fragment);
+                }
+                Some(LinkTarget::Url(url))
+            }
+            (.., ExternalLocation::Unknown) => None,
+        }
+    }
+
+    /// Returns the URL of the HTML page of a primitive type, from a fragment like `u8#method.max`.
+    fn primitive_url(&self, item: &clean::Item, fragment: &str) -> String {
+        let root = match self.cache.extern_locations.get(&item.def_id.krate) {
+            Some(&(.., ExternalLocation::Remote(ref root))) => root.as_str(),
+            _ => "https://doc.rust-lang.org/nightly",
+        };
+        let tail = fragment.find('#').unwrap_or_else(|| fragment.len());
+        format!(
+            "{}{}std/primitive.{}.html{}",
+            root,
+            if !root.ends_with('/') { "/" } else { "" },
+            &fragment[..tail],
+            &fragment[tail..]
+        )
+    }
+
+    /// Returns the implementations of the type `did`.
+    fn impls(&self, did: DefId) -> &[Impl] {
+        self.cache.impls.get(&did).map_or(&[], |impls| &impls[..])
+    }
+}
+
+/// Returns the path of the HTML page of an item, relative to the root of the documentation.
+fn html_path(path: &[String], ty: ItemType) -> String {
+    let mut url = String::new();
+    for component in &path[..path.len() - 1] {
+        url.push_str(component);
+        url.push('/');
+    }
+    match ty {
+        ItemType::Module => {
+            url.push_str(path.last().unwrap());
+            url.push_str("/index.html");
+        }
+        _ => url.push_str(&format!("{}.{}.html", ty.as_str(), path.last().unwrap())),
+    }
+    url
+}
+
+/// Whether `item` has its own page.
+fn has_page(item: &clean::Item) -> bool {
+    match item.inner {
+        clean::ModuleItem(..)
+        | clean::FunctionItem(..)
+        | clean::ForeignFunctionItem(..)
+        | clean::TraitItem(..)
+        | clean::StructItem(..)
+        | clean::UnionItem(..)
+        | clean::EnumItem(..)
+        | clean::TypedefItem(_, false)
+        | clean::MacroItem(..)
+        | clean::ProcMacroItem(..)
+        | clean::PrimitiveItem(..)
+        | clean::StaticItem(..)
+        | clean::ForeignStaticItem(..)
+        | clean::ConstantItem(..)
+        | clean::ForeignTypeItem
+        | clean::KeywordItem(..)
+        | clean::OpaqueTyItem(..)
+        | clean::TraitAliasItem(..) => item.name.is_some(),
+        _ => false,
+    }
+}
+
+/// Adds the pages of the module `item` and of the items in it to `pages`.
+fn collect_pages<'a>(item: &'a clean::Item, path: &mut Vec<String>, pages: &mut Vec<Page<'a>>) {
+    let module = match item.inner {
+        clean::ModuleItem(ref module) => module,
+        _ => return,
+    };
+    path.push(item.name.clone().unwrap_or_default());
+    pages.push(Page { item, path: path.clone(), ty: ItemType::Module });
+
+    for child in module.items.iter().filter(|child| !child.is_stripped() && has_page(child)) {
+        if child.is_mod() {
+            collect_pages(child, path, pages);
+        } else {
+            let mut child_path = path.clone();
+            child_path.push(child.name.clone().unwrap());
+            pages.push(Page { item: child, path: child_path, ty: child.type_() });
+        }
+    }
+    path.pop();
+}
+
+/// Adds the contents of `page` to `f`.
+fn render_page<F: Format>(cx: &Context<'_>, page: &Page<'_>, f: &mut F) {
+    let item = page.item;
+    let declaration = declaration(item);
+    if !declaration.is_empty() {
+        f.code(&declaration);
+    }
+    f.docs(cx, item);
+
+    match item.inner {
+        clean::ModuleItem(ref module) => render_module(page, module, f),
+        clean::StructItem(clean::Struct { ref fields, .. })
+        | clean::UnionItem(clean::Union { ref fields, .. }) => {
+            render_entries(cx, f, "Fields", fields.iter());
+        }
+        clean::EnumItem(ref e) => {
+            render_entries(cx, f, "Variants", e.variants.iter());
+        }
+        clean::TraitItem(ref t) => render_trait(cx, item, t, f),
+        _ => {}
+    }
+    render_impls(cx, item, f);
+}
+
+/// Adds the listing of the items of a module, grouped by kind.
+fn render_module<F: Format>(page: &Page<'_>, module: &clean::Module, f: &mut F) {
+    let reexports: Vec<_> = module
+        .items
+        .iter()
+        .filter(|item| !item.is_stripped())
+        .filter_map(|item| match item.inner {
+            clean::ImportItem(ref import) => {
+                Some(format!("{:#}{:#}", item.visibility.print_with_space(), import.print()))
+            }
+            clean::ExternCrateItem(ref name, ref rename) => Some(match *rename {
+                Some(ref rename) => format!("extern crate {} as {};", rename, name),
+                None => format!("extern crate {};", name),
+            }),
+            _ => None,
+        })
+        .collect();
+    if !reexports.is_empty() {
+        f.section(item_ty_to_strs(&ItemType::Import).1);
+        f.code(&reexports.join("\n"));
+    }
+
+    let mut items: Vec<_> =
+        module.items.iter().filter(|item| !item.is_stripped() && has_page(item)).collect();
+    items.sort_by_key(|item| (item.type_(), item.name.clone()));
+    let mut current_ty = None;
+    for item in items {
+        let ty = item.type_();
+        if current_ty != Some(ty) {
+            f.section(item_ty_to_strs(&ty).1);
+            current_ty = Some(ty);
+        }
+        let mut path = page.path.clone();
+        path.push(item.name.clone().unwrap());
+        let summary = plain_summary_line(item.doc_value().unwrap_or(""));
+        f.listing(&path, ty, &summary);
+    }
+}
+
+/// Adds a section with the given items, unless there are none.
+fn render_entries<'a, F: Format>(
+    cx: &Context<'_>,
+    f: &mut F,
+    title: &str,
+    items: impl Iterator<Item = &'a clean::Item>,
+) {
+    let mut items = items.filter(|item| !item.is_stripped()).peekable();
+    if items.peek().is_none() {
+        return;
+    }
+    f.section(title);
+    for item in items {
+        f.entry(cx, &declaration(item), item);
+    }
+}
+
+/// Adds the associated items and the implementors of a trait.
+fn render_trait<F: Format>(cx: &Context<'_>, item: &clean::Item, t: &clean::Trait, f: &mut F) {
+    let of_type = |ty: ItemType| t.items.iter().filter(move |item| item.type_() == ty);
+    render_entries(cx, f, "Associated Types", of_type(ItemType::AssocType));
+    render_entries(cx, f, "Associated Constants", of_type(ItemType::AssocConst));
+    render_entries(cx, f, "Required Methods", of_type(ItemType::TyMethod));
+    render_entries(cx, f, "Provided Methods", of_type(ItemType::Method));
+
+    if let Some(implementors) = cx.cache.implementors.get(&item.def_id) {
+        let implementors: Vec<_> =
+            implementors.iter().map(|i| format!("{:#}", i.inner_impl().print())).collect();
+        f.section("Implementors");
+        f.code(&implementors.join("\n"));
+    }
+}
+
+/// Adds the inherent implementations of a type and the list of the traits it implements.
+fn render_impls<F: Format>(cx: &Context<'_>, item: &clean::Item, f: &mut F) {
+    let impls = cx.impls(item.def_id);
+
+    for i in impls.iter().filter(|i| i.inner_impl().trait_.is_none()) {
+        render_entries(cx, f, "Implementations", i.inner_impl().items.iter());
+    }
+
+    let trait_impls = |title: &str, filter: &dyn Fn(&clean::Impl) -> bool, f: &mut F| {
+        let impls: Vec<_> = impls
+            .iter()
+            .map(Impl::inner_impl)
+            .filter(|i| i.trait_.is_some() && filter(i))
+            .map(|i| format!("{:#}", i.print()))
+            .collect();
+        if !impls.is_empty() {
+            f.section(title);
+            f.code(&impls.join("\n"));
+        }
+    };
+    trait_impls("Trait Implementations", &|i| !i.synthetic && i.blanket_impl.is_none(), f);
+    trait_impls("Auto Trait Implementations", &|i| i.synthetic, f);
+    trait_impls("Blanket Implementations", &|i| i.blanket_impl.is_some(), f);
+}
+
+/// Returns the declaration of `item` as Rust code, or an empty string for the items that don't
+/// have one, like modules.
+crate fn declaration(item: &clean::Item) -> String {
+    let name = item.name.as_ref().map_or("", |name| name.as_str());
+    let vis = item.visibility.print_with_space();
+    let decl = match item.inner {
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            fn_declaration(item, &f.header, &f.generics, &f.decl)
+        }
+        clean::MethodItem(ref m) => fn_declaration(item, &m.header, &m.generics, &m.decl),
+        clean::TyMethodItem(ref m) => {
+            format!("{};", fn_declaration(item, &m.header, &m.generics, &m.decl))
+        }
+        clean::StructItem(ref s) => format!(
+            "{:#}struct {}{:#}{}",
+            vis,
+            name,
+            s.generics.print(),
+            struct_body(s.struct_type, &s.generics, &s.fields, s.fields_stripped),
+        ),
+        clean::UnionItem(ref u) => format!(
+            "{:#}union {}{:#}{}",
+            vis,
+            name,
+            u.generics.print(),
+            struct_body(u.struct_type, &u.generics, &u.fields, u.fields_stripped),
+        ),
+        clean::EnumItem(ref e) => {
+            let mut decl = format!(
+                "{:#}enum {}{:#}{:#} {{\n",
+                vis,
+                name,
+                e.generics.print(),
+                where_clause(&e.generics)
+            );
+            for variant in e.variants.iter().filter(|v| !v.is_stripped()) {
+                decl.push_str(&format!("    {},\n", declaration(variant)));
+            }
+            if e.variants_stripped {
+                decl.push_str("    // some variants omitted\n");
+            }
+            decl.push('}');
+            decl
+        }
+        clean::VariantItem(ref v) => match v.kind {
+            clean::VariantKind::CLike => name.to_string(),
+            clean::VariantKind::Tuple(ref tys) => {
+                let tys: Vec<_> = tys.iter().map(|ty| format!("{:#}", ty.print())).collect();
+                format!("{}({})", name, tys.join(", "))
+            }
+            clean::VariantKind::Struct(ref s) => {
+                let fields: Vec<_> =
+                    s.fields.iter().filter(|field| !field.is_stripped()).map(declaration).collect();
+                format!("{} {{ {} }}", name, fields.join(", "))
+            }
+        },
+        clean::StructFieldItem(ref ty) => format!("{:#}{}: {:#}", vis, name, ty.print()),
+        clean::TraitItem(ref t) => {
+            let bounds = if t.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {:#}", print_generic_bounds(&t.bounds))
+            };
+            let mut decl = format!(
+                "{:#}{}{}trait {}{:#}{}{:#} {{\n",
+                vis,
+                t.unsafety.print_with_space(),
+                if t.is_auto { "auto " } else { "" },
+                name,
+                t.generics.print(),
+                bounds,
+                where_clause(&t.generics)
+            );
+            for item in &t.items {
+                let item_decl = declaration(item);
+                let item_decl = match item.inner {
+                    clean::MethodItem(..) => format!("{} {{ ... }}", item_decl),
+                    _ => item_decl,
+                };
+                decl.push_str(&format!("    {}\n", item_decl.replace('\n', "\n    ")));
+            }
+            decl.push('}');
+            decl
+        }
+        clean::TraitAliasItem(ref a) => format!(
+            "trait {}{:#} = {:#}{:#};",
+            name,
+            a.generics.print(),
+            print_generic_bounds(&a.bounds),
+            where_clause(&a.generics)
+        ),
+        clean::TypedefItem(ref t, false) => format!(
+            "{:#}type {}{:#}{:#} = {:#};",
+            vis,
+            name,
+            t.generics.print(),
+            where_clause(&t.generics),
+            t.type_.print()
+        ),
+        clean::TypedefItem(ref t, true) => format!("type {} = {:#};", name, t.type_.print()),
+        clean::OpaqueTyItem(ref t, _) => format!(
+            "{:#}type {}{:#}{:#} = impl {:#};",
+            vis,
+            name,
+            t.generics.print(),
+            where_clause(&t.generics),
+            print_generic_bounds(&t.bounds)
+        ),
+        clean::ConstantItem(ref c) => {
+            format!("{:#}const {}: {:#} = {};", vis, name, c.type_.print(), c.expr)
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => format!(
+            "{:#}static {}{}: {:#};",
+            vis,
+            s.mutability.print_with_space(),
+            name,
+            s.type_.print()
+        ),
+        clean::AssocConstItem(ref ty, ref default) => match *default {
+            Some(ref default) => format!("const {}: {:#} = {};", name, ty.print(), default),
+            None => format!("const {}: {:#};", name, ty.print()),
+        },
+        clean::AssocTypeItem(ref bounds, ref default) => {
+            let mut decl = format!("type {}", name);
+            if !bounds.is_empty() {
+                decl.push_str(&format!(": {:#}", print_generic_bounds(bounds)));
+            }
+            if let Some(ref default) = *default {
+                decl.push_str(&format!(" = {:#}", default.print()));
+            }
+            decl.push(';');
+            decl
+        }
+        clean::ForeignTypeItem => format!("{:#}type {};", vis, name),
+        clean::MacroItem(ref m) => m.source.clone(),
+        clean::ProcMacroItem(ref m) => match m.kind {
+            MacroKind::Bang => format!("{}!() {{ /* proc-macro */ }}", name),
+            MacroKind::Attr => format!("#[{}]", name),
+            MacroKind::Derive => {
+                let mut decl = format!("#[derive({})]", name);
+                if !m.helpers.is_empty() {
+                    decl.push_str("\n\n// Attributes available to this derive:\n");
+                    for helper in &m.helpers {
+                        decl.push_str(&format!("#[{}]\n", helper));
+                    }
+                }
+                decl
+            }
+        },
+        clean::ImplItem(ref i) => format!("{:#}", i.print()),
+        _ => String::new(),
+    };
+    // Long function declarations are wrapped with HTML spaces, even in plain text.
+    decl.replace("&nbsp;", " ")
+}
+
+fn fn_declaration(
+    item: &clean::Item,
+    header: &rustc_hir::FnHeader,
+    generics: &clean::Generics,
+    decl: &clean::FnDecl,
+) -> String {
+    let head = format!(
+        "{:#}{}{}{}{:#}fn {}{:#}",
+        item.visibility.print_with_space(),
+        header.constness.print_with_space(),
+        header.asyncness.print_with_space(),
+        header.unsafety.print_with_space(),
+        print_abi_with_space(header.abi),
+        item.name.as_ref().map_or("", |name| name.as_str()),
+        generics.print()
+    );
+    let decl = Function { decl, header_len: head.len(), indent: 0, asyncness: header.asyncness };
+    format!("{}{:#}{:#}", head, decl.print(), where_clause(generics))
+}
+
+fn struct_body(
+    struct_type: StructType,
+    generics: &clean::Generics,
+    fields: &[clean::Item],
+    fields_stripped: bool,
+) -> String {
+    let visible = fields.iter().filter(|field| !field.is_stripped());
+    match struct_type {
+        StructType::Plain => {
+            let mut body = format!("{:#} {{\n", where_clause(generics));
+            for field in visible {
+                body.push_str(&format!("    {},\n", declaration(field)));
+            }
+            if fields_stripped {
+                body.push_str("    // some fields omitted\n");
+            }
+            body.push('}');
+            body
+        }
+        StructType::Tuple => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|field| match field.inner {
+                    clean::StructFieldItem(ref ty) if !field.is_stripped() => {
+                        format!("{:#}{:#}", field.visibility.print_with_space(), ty.print())
+                    }
+                    _ => "_".to_string(),
+                })
+                .collect();
+            format!("({}){:#};", fields.join(", "), where_clause(generics))
+        }
+        StructType::Unit => format!("{:#};", where_clause(generics)),
+    }
+}
+
+fn where_clause(generics: &clean::Generics) -> WhereClause<'_> {
+    WhereClause { gens: generics, indent: 0, end_newline: false }
+}
@@ -0,0 +1,85 @@
+use super::man::{markdown_to_roff, page_name};
+use super::markdown::rewrite_docs;
+
+use crate::html::item_type::ItemType;
+
+#[test]
+fn test_rewrite_docs_headings() {
+    fn t(input: &str, offset: u32, expect: &str) {
+        assert_eq!(rewrite_docs(input, &[], offset), expect);
+    }
+
+    t("# Examples\n\nSome text.", 1, "## Examples\n\nSome text.");
+    t("## Panics", 3, "##### Panics");
+    t("##### Deep", 3, "###### Deep");
+    t("Errors\n======\n\ntext", 1, "## Errors\n\ntext");
+    t("Not a heading # at all", 1, "Not a heading # at all");
+}
+
+#[test]
+fn test_rewrite_docs_code_blocks() {
+    fn t(input: &str, expect: &str) {
+        assert_eq!(rewrite_docs(input, &[], 1), expect);
+    }
+
+    t("```\n# let x = 1;\nlet y = x;\n```", "```rust\nlet y = x;\n```");
+    t(
+        "```should_panic\n## [derive(Debug)]\npanic!();\n```",
+        "```rust\n# [derive(Debug)]\npanic!();\n```",
+    );
+    t("```text\n# not hidden\n```", "```text\n# not hidden\n```");
+    t("- item\n\n  ```\n  # hidden\n  shown();\n  ```", "- item\n\n  ```rust\n  shown();\n  ```");
+}
+
+#[test]
+fn test_rewrite_docs_links() {
+    fn t(input: &str, expect: &str) {
+        let links = [
+            ("Foo".to_string(), "struct.Foo.md".to_string()),
+            ("`Bar`".to_string(), "https://example.com/bar/enum.Bar.html".to_string()),
+        ];
+        assert_eq!(rewrite_docs(input, &links, 1), expect);
+    }
+
+    t("See [Foo].", "See [Foo](struct.Foo.md).");
+    t("See [the foo](Foo).", "See [the foo](struct.Foo.md).");
+    t("See [`Bar`].", "See [`Bar`](https://example.com/bar/enum.Bar.html).");
+    t("See [Baz] and [it](https://rust-lang.org).", "See [Baz] and [it](https://rust-lang.org).");
+}
+
+#[test]
+fn test_markdown_to_roff() {
+    fn t(input: &str, expect: &str) {
+        let links = [("Foo".to_string(), "krate::Foo(3)".to_string())];
+        assert_eq!(markdown_to_roff(input, &links), expect);
+    }
+
+    t(
+        "Some *emphasized* and **strong** `code`.",
+        ".PP\nSome \\fIemphasized\\fR and \\fBstrong\\fR \\fBcode\\fR.\n",
+    );
+    t(
+        "# Examples\n\n```\n# let x = 1;\n.x\n```",
+        ".SS Examples\n.PP\n.RS 4\n.nf\n\\&.x\n.fi\n.RE\n",
+    );
+    t("- one\n- two", ".IP \\(bu 2\none\n.IP \\(bu 2\ntwo\n");
+    t("1. one\n2. two", ".IP 1. 4\none\n.IP 2. 4\ntwo\n");
+    t("See [Foo].", ".PP\nSee Foo (krate::Foo(3)).\n");
+    t("a \\ b\n.dot", ".PP\na \\e b\n\\&.dot\n");
+    t("> quoted", ".RS 4\n.PP\nquoted\n.RE\n");
+}
+
+#[test]
+fn test_man_page_names() {
+    fn t(path: &[&str], ty: ItemType, expect: &str) {
+        let path: Vec<_> = path.iter().map(|s| s.to_string()).collect();
+        assert_eq!(page_name(&path, ty), expect);
+    }
+
+    t(&["krate"], ItemType::Module, "krate");
+    t(&["krate", "Foo"], ItemType::Struct, "krate.struct.Foo");
+    t(&["krate", "foo", "bar"], ItemType::Function, "krate.foo.fn.bar");
+    // A module and a macro with the same name get different pages.
+    t(&["krate", "foo"], ItemType::Module, "krate.mod.foo");
+    t(&["krate", "foo"], ItemType::Macro, "krate.macro.foo");
+}
@@ -63,6 +63,16 @@
 //! variables is cached once the first backtrace is created, so altering
 //! `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` at runtime may not actually change
 //! how backtraces are captured.
+//!
+//! ## Inspecting frames
+//!
+//! Besides being rendered with `Display` or `Debug`, a captured backtrace can
+//! be inspected frame by frame through `Backtrace::frames`. Each
+//! `BacktraceFrame` exposes its instruction pointer and, once resolved, the
+//! `BacktraceSymbol`s it maps to along with their names and source locations.
+//! Resolution is deferred until it's asked for, either for the whole
+//! backtrace with `Backtrace::resolve` or for a single frame with
+//! `BacktraceFrame::resolve`.
 
 #![unstable(feature = "backtrace", issue = "53487")]
 
@@ -92,11 +102,13 @@
 // a backtrace or actually symbolizing it.
 
 use crate::env;
+use crate::ffi::c_void;
 use crate::fmt;
+use crate::path::PathBuf;
 use crate::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use crate::sync::Mutex;
-use crate::sys_common::backtrace::{lock, output_filename};
-use crate::vec::Vec;
+use crate::sys_common::backtrace::{filename_to_path, lock, output_filename};
+use crate::vec::{self, Vec};
 use backtrace::BytesOrWideString;
 use backtrace_rs as backtrace;
 
@@ -143,17 +155,42 @@ fn _assert_send_sync() {
     _assert::<Backtrace>();
 }
 
-struct BacktraceFrame {
+/// A single stack frame of a captured backtrace.
+///
+/// Frames are returned by `Backtrace::frames`. A frame always knows its
+/// instruction pointer, but the symbols it corresponds to are only available
+/// after it has been resolved, see `BacktraceFrame::resolve`.
+#[unstable(feature = "backtrace_frames", issue = "none")]
+#[derive(Clone)]
+pub struct BacktraceFrame {
     frame: backtrace::Frame,
     symbols: Vec<BacktraceSymbol>,
+    resolved: bool,
 }
 
-struct BacktraceSymbol {
+/// A symbol that a `BacktraceFrame` resolved to.
+///
+/// A single frame may resolve to several symbols when functions have been
+/// inlined into each other, in which case the innermost function comes first.
+#[unstable(feature = "backtrace_frames", issue = "none")]
+#[derive(Clone)]
+pub struct BacktraceSymbol {
     name: Option<Vec<u8>>,
     filename: Option<BytesOrWide>,
     lineno: Option<u32>,
+    colno: Option<u32>,
 }
 
+/// An iterator over the frames of a `Backtrace`.
+///
+/// This struct is created by the `Backtrace::frames` method.
+#[unstable(feature = "backtrace_frames", issue = "none")]
+#[derive(Debug)]
+pub struct Frames {
+    inner: vec::IntoIter<BacktraceFrame>,
+}
+
+#[derive(Clone)]
 enum BytesOrWide {
     Bytes(Vec<u8>),
     Wide(Vec<u16>),
@@ -186,6 +223,19 @@ impl fmt::Debug for Backtrace {
     }
 }
 
+#[unstable(feature = "backtrace_frames", issue = "none")]
+impl fmt::Debug for BacktraceFrame {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dbg = fmt.debug_struct("BacktraceFrame");
+        dbg.field("ip", &self.ip());
+        if self.resolved {
+            dbg.field("symbols", &self.symbols);
+        }
+        dbg.finish()
+    }
+}
+
+#[unstable(feature = "backtrace_frames", issue = "none")]
 impl fmt::Debug for BacktraceSymbol {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "{{ ")?;
@@ -204,6 +254,10 @@ impl fmt::Debug for BacktraceSymbol {
             write!(fmt, ", line: {:?}", line)?;
         }
 
+        if let Some(col) = self.colno.as_ref() {
+            write!(fmt, ", column: {:?}", col)?;
+        }
+
         write!(fmt, " }}")
     }
 }
@@ -212,16 +266,22 @@ impl fmt::Debug for BytesOrWide {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         output_filename(
             fmt,
-            match self {
-                BytesOrWide::Bytes(w) => BytesOrWideString::Bytes(w),
-                BytesOrWide::Wide(w) => BytesOrWideString::Wide(w),
-            },
+            self.as_bows(),
             backtrace::PrintFmt::Short,
             crate::env::current_dir().as_ref().ok(),
         )
     }
 }
 
+impl BytesOrWide {
+    fn as_bows(&self) -> BytesOrWideString<'_> {
+        match self {
+            BytesOrWide::Bytes(w) => BytesOrWideString::Bytes(w),
+            BytesOrWide::Wide(w) => BytesOrWideString::Wide(w),
+        }
+    }
+}
+
 impl Backtrace {
     /// Returns whether backtrace captures are enabled through environment
     /// variables.
@@ -293,7 +353,11 @@ impl Backtrace {
         let mut actual_start = None;
         unsafe {
             backtrace::trace_unsynchronized(|frame| {
-                frames.push(BacktraceFrame { frame: frame.clone(), symbols: Vec::new() });
+                frames.push(BacktraceFrame {
+                    frame: frame.clone(),
+                    symbols: Vec::new(),
+                    resolved: false,
+                });
                 if frame.symbol_address() as usize == ip && actual_start.is_none() {
                     actual_start = Some(frames.len());
                 }
@@ -327,8 +391,166 @@ impl Backtrace {
             Inner::Captured(_) => BacktraceStatus::Captured,
         }
     }
+
+    /// Resolves the symbols of every frame in this backtrace.
+    ///
+    /// Symbol resolution is by far the most expensive part of working with a
+    /// backtrace, so it is normally deferred until the backtrace is printed.
+    /// This method forces it to happen now, after which `Backtrace::frames`
+    /// returns frames that are already resolved. Calling it more than once,
+    /// or on a backtrace that wasn't captured, does nothing.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn resolve(&self) {
+        if let Inner::Captured(c) = &self.inner {
+            c.lock().unwrap().resolve();
+        }
+    }
+
+    /// Returns an iterator over the frames of this backtrace, starting with
+    /// the frame that called `Backtrace::capture` or
+    /// `Backtrace::force_capture`.
+    ///
+    /// The frames are copied out of the backtrace. They're only resolved if
+    /// the backtrace itself has been resolved, either explicitly through
+    /// `Backtrace::resolve` or by having been printed; otherwise they can be
+    /// resolved individually with `BacktraceFrame::resolve`.
+    ///
+    /// The iterator is empty if the backtrace is disabled or unsupported.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn frames(&self) -> Frames {
+        let frames = match &self.inner {
+            Inner::Unsupported | Inner::Disabled => Vec::new(),
+            Inner::Captured(c) => {
+                let capture = c.lock().unwrap();
+                capture.frames[capture.actual_start..].to_vec()
+            }
+        };
+        Frames { inner: frames.into_iter() }
+    }
 }
 
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    ///
+    /// This is usually the address of the next instruction to execute in the
+    /// frame, i.e. the return address, rather than the call instruction itself.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn ip(&self) -> *mut c_void {
+        self.frame.ip()
+    }
+
+    /// Returns the starting address of the function this frame belongs to,
+    /// if the platform's unwinder reports it, or the instruction pointer
+    /// otherwise.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn symbol_address(&self) -> *mut c_void {
+        self.frame.symbol_address()
+    }
+
+    /// Returns whether the symbols of this frame have been resolved.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn is_resolved(&self) -> bool {
+        self.resolved
+    }
+
+    /// Resolves the symbols of this frame if that hasn't happened yet.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn resolve(&mut self) {
+        if self.resolved {
+            return;
+        }
+        let _lock = lock();
+        self.resolve_unsynchronized();
+    }
+
+    /// Returns the symbols this frame resolved to.
+    ///
+    /// This is empty if the frame hasn't been resolved yet, and may also be
+    /// empty for a resolved frame if no symbol information was available.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        &self.symbols
+    }
+
+    // The caller must hold the global backtrace lock.
+    fn resolve_unsynchronized(&mut self) {
+        self.resolved = true;
+        let symbols = &mut self.symbols;
+        unsafe {
+            backtrace::resolve_frame_unsynchronized(&self.frame, |symbol| {
+                symbols.push(BacktraceSymbol {
+                    name: symbol.name().map(|m| m.as_bytes().to_vec()),
+                    filename: symbol.filename_raw().map(|b| match b {
+                        BytesOrWideString::Bytes(b) => BytesOrWide::Bytes(b.to_owned()),
+                        BytesOrWideString::Wide(b) => BytesOrWide::Wide(b.to_owned()),
+                    }),
+                    lineno: symbol.lineno(),
+                    // FIXME: fill this in from `symbol.colno()`. The first
+                    // `backtrace` releases that have it no longer support the
+                    // `rustc-dep-of-std` build, and their libbacktrace
+                    // symbolizer doesn't report columns either.
+                    colno: None,
+                });
+            });
+        }
+    }
+}
+
+impl BacktraceSymbol {
+    /// Returns the demangled name of this symbol, without the trailing hash
+    /// that Rust symbol names carry.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn name(&self) -> Option<String> {
+        self.name.as_ref().map(|b| format!("{:#}", backtrace::SymbolName::new(b)))
+    }
+
+    /// Returns the raw, possibly mangled, bytes of this symbol's name.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn name_raw(&self) -> Option<&[u8]> {
+        self.name.as_deref()
+    }
+
+    /// Returns the path of the source file containing this symbol, if debug
+    /// information is available.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn filename(&self) -> Option<PathBuf> {
+        self.filename.as_ref().map(|f| filename_to_path(f.as_bows()).into_owned())
+    }
+
+    /// Returns the line number within `filename` for this symbol, if debug
+    /// information is available.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn lineno(&self) -> Option<u32> {
+        self.lineno
+    }
+
+    /// Returns the column number within `filename` for this symbol, if debug
+    /// information is available.
+    ///
+    /// Column information is not reported on any platform yet, so this
+    /// currently always returns `None`.
+    #[unstable(feature = "backtrace_frames", issue = "none")]
+    pub fn colno(&self) -> Option<u32> {
+        self.colno
+    }
+}
+
+#[unstable(feature = "backtrace_frames", issue = "none")]
+impl Iterator for Frames {
+    type Item = BacktraceFrame;
+
+    fn next(&mut self) -> Option<BacktraceFrame> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[unstable(feature = "backtrace_frames", issue = "none")]
+impl ExactSizeIterator for Frames {}
+
 impl fmt::Display for Backtrace {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut capture = match &self.inner {
@@ -365,10 +587,7 @@ impl fmt::Display for Backtrace {
                     f.print_raw(
                         frame.frame.ip(),
                         symbol.name.as_ref().map(|b| backtrace::SymbolName::new(b)),
-                        symbol.filename.as_ref().map(|b| match b {
-                            BytesOrWide::Bytes(w) => BytesOrWideString::Bytes(w),
-                            BytesOrWide::Wide(w) => BytesOrWideString::Wide(w),
-                        }),
+                        symbol.filename.as_ref().map(|b| b.as_bows()),
                         symbol.lineno,
                     )?;
                 }
@@ -392,18 +611,8 @@ impl Capture {
         // everything.
         let _lock = lock();
         for frame in self.frames.iter_mut() {
-            let symbols = &mut frame.symbols;
-            unsafe {
-                backtrace::resolve_frame_unsynchronized(&frame.frame, |symbol| {
-                    symbols.push(BacktraceSymbol {
-                        name: symbol.name().map(|m| m.as_bytes().to_vec()),
-                        filename: symbol.filename_raw().map(|b| match b {
-                            BytesOrWideString::Bytes(b) => BytesOrWide::Bytes(b.to_owned()),
-                            BytesOrWideString::Wide(b) => BytesOrWide::Wide(b.to_owned()),
-                        }),
-                        lineno: symbol.lineno(),
-                    });
-                });
+            if !frame.resolved {
+                frame.resolve_unsynchronized();
             }
         }
     }
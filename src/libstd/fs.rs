@@ -221,6 +221,46 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// A handle to an open directory on the filesystem.
+///
+/// Paths passed to the methods of a `Dir` are resolved relative to the
+/// directory the handle refers to, even if that directory is later moved or
+/// renamed. This makes it possible to operate on the contents of a directory
+/// without racing against other processes that replace one of its ancestors
+/// with a symbolic link. Absolute paths are resolved as usual.
+///
+/// The handle is closed when the value is dropped.
+///
+/// # Platform-specific behavior
+///
+/// On Unix the handle wraps a directory file descriptor and its methods
+/// correspond to `openat`, `mkdirat`, `unlinkat`, `fstatat` and `renameat`.
+/// Other platforms currently remember the path the handle was opened with and
+/// resolve relative paths against it, so they offer no protection against the
+/// directory being replaced.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_dir)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::prelude::*;
+///
+/// fn main() -> std::io::Result<()> {
+///     let dir = Dir::open("/some/dir")?;
+///     let mut file = dir.open_file("foo.txt", OpenOptions::new().write(true).create(true))?;
+///     file.write_all(b"Hello, world!")?;
+///     dir.rename("foo.txt", &dir, "bar.txt")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_dir", issue = "none")]
+#[derive(Debug)]
+pub struct Dir(fs_imp::DirHandle);
+
 /// Indicates how large a buffer to pre-allocate before reading the entire file.
 fn initial_buffer_size(file: &File) -> usize {
     // Allocate one extra byte so the buffer doesn't need to grow before the
@@ -1989,9 +2029,11 @@ pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
 ///
 /// # Platform-specific behavior
 ///
-/// This function currently corresponds to `opendir`, `lstat`, `rm` and `rmdir` functions on Unix
-/// and the `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`, and `RemoveDirectory` functions
-/// on Windows.
+/// This function currently corresponds to `lstat`, `openat`, `fdopendir` and `unlinkat` functions
+/// on Unix and the `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`, and `RemoveDirectory`
+/// functions on Windows. On Unix every directory in the tree is opened relative to its parent
+/// without following symbolic links, so a directory that is concurrently replaced with a symbolic
+/// link causes an error instead of the link's target being removed.
 /// Note that, this [may change in the future][changes].
 ///
 /// [changes]: ../io/index.html#platform-specific-behavior
@@ -2223,6 +2265,132 @@ impl AsInnerMut<fs_imp::DirBuilder> for DirBuilder {
     }
 }
 
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not exist or does
+    /// not refer to a directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_dir)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/some/dir")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::DirHandle::open(path.as_ref()).map(Dir)
+    }
+
+    /// Opens the file at `path` relative to this directory with the options
+    /// specified by `opts`.
+    ///
+    /// This is the counterpart of [`OpenOptions::open`].
+    ///
+    /// [`OpenOptions::open`]: struct.OpenOptions.html#method.open
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_dir)]
+    /// use std::fs::{Dir, OpenOptions};
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/some/dir")?;
+    ///     let file = dir.open_file("foo.txt", OpenOptions::new().read(true))?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn open_file<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        self.0.open_file(path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Creates a new, empty directory at `path` relative to this directory.
+    ///
+    /// This is the counterpart of [`fs::create_dir`].
+    ///
+    /// [`fs::create_dir`]: fn.create_dir.html
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.0.mkdir(path.as_ref())
+    }
+
+    /// Removes the file at `path` relative to this directory.
+    ///
+    /// This is the counterpart of [`fs::remove_file`].
+    ///
+    /// [`fs::remove_file`]: fn.remove_file.html
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.0.unlink(path.as_ref())
+    }
+
+    /// Removes the empty directory at `path` relative to this directory.
+    ///
+    /// This is the counterpart of [`fs::remove_dir`].
+    ///
+    /// [`fs::remove_dir`]: fn.remove_dir.html
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.0.rmdir(path.as_ref())
+    }
+
+    /// Queries the metadata of the file at `path` relative to this directory
+    /// without following a symbolic link in the final component.
+    ///
+    /// This is the counterpart of [`fs::symlink_metadata`].
+    ///
+    /// [`fs::symlink_metadata`]: fn.symlink_metadata.html
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.0.lstat(path.as_ref()).map(Metadata)
+    }
+
+    /// Returns an iterator over the entries of the directory at `path`
+    /// relative to this directory. Pass `"."` to list this directory itself.
+    ///
+    /// This is the counterpart of [`fs::read_dir`]. The [`DirEntry::path`] of
+    /// each entry is built from the path this handle was opened with, which
+    /// may no longer be accurate if the directory has been moved.
+    ///
+    /// [`fs::read_dir`]: fn.read_dir.html
+    /// [`DirEntry::path`]: struct.DirEntry.html#method.path
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        self.0.readdir(path.as_ref()).map(ReadDir)
+    }
+
+    /// Renames the file or directory at `from` relative to this directory to
+    /// `to` relative to `to_dir`, replacing the destination if it exists.
+    ///
+    /// This is the counterpart of [`fs::rename`].
+    ///
+    /// [`fs::rename`]: fn.rename.html
+    #[unstable(feature = "fs_dir", issue = "none")]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Dir,
+        to: Q,
+    ) -> io::Result<()> {
+        self.0.rename(from.as_ref(), &to_dir.0, to.as_ref())
+    }
+}
+
+impl AsInner<fs_imp::DirHandle> for Dir {
+    fn as_inner(&self) -> &fs_imp::DirHandle {
+        &self.0
+    }
+}
+
 #[cfg(all(test, not(any(target_os = "cloudabi", target_os = "emscripten", target_env = "sgx"))))]
 mod tests {
     use crate::io::prelude::*;
@@ -2800,6 +2968,48 @@ mod tests {
         assert!(canary.exists());
     }
 
+    #[test]
+    fn dir_handle_ops() {
+        let tmpdir = tmpdir();
+        let dir = check!(fs::Dir::open(tmpdir.path()));
+        check!(dir.create_dir("sub"));
+        assert!(check!(dir.metadata("sub")).is_dir());
+
+        let mut opts = OpenOptions::new();
+        opts.write(true).create_new(true);
+        check!(check!(dir.open_file("sub/a.txt", &opts)).write(b"foo"));
+        let mut contents = String::new();
+        check!(check!(File::open(tmpdir.join("sub/a.txt"))).read_to_string(&mut contents));
+        assert_eq!(contents, "foo");
+
+        let sub = check!(fs::Dir::open(tmpdir.join("sub")));
+        check!(sub.rename("a.txt", &dir, "b.txt"));
+        assert!(!tmpdir.join("sub/a.txt").exists());
+        assert_eq!(check!(dir.metadata("b.txt")).len(), 3);
+
+        let names: Vec<_> = check!(dir.read_dir("."))
+            .map(|e| check!(e).file_name().into_string().unwrap())
+            .filter(|n| n == "sub" || n == "b.txt")
+            .collect();
+        assert_eq!(names.len(), 2);
+
+        assert!(dir.remove_dir("b.txt").is_err());
+        check!(dir.remove_file("b.txt"));
+        check!(dir.remove_dir("sub"));
+        assert!(check!(dir.read_dir(".")).next().is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dir_handle_survives_rename() {
+        let tmpdir = tmpdir();
+        check!(fs::create_dir(tmpdir.join("before")));
+        let dir = check!(fs::Dir::open(tmpdir.join("before")));
+        check!(fs::rename(tmpdir.join("before"), tmpdir.join("after")));
+        check!(check!(dir.open_file("f", OpenOptions::new().write(true).create(true))).write(b"x"));
+        assert!(tmpdir.join("after/f").exists());
+    }
+
     #[test]
     fn recursive_rmdir_of_symlink() {
         // test we do not recursively delete a symlink but only dirs.
@@ -287,6 +287,7 @@ pub use self::stdio::{_eprint, _print};
 pub use self::stdio::{set_panic, set_print};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::util::{copy, empty, repeat, sink, Empty, Repeat, Sink};
+pub(crate) use self::util::generic_copy;
 
 mod buffered;
 mod cursor;
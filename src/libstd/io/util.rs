@@ -18,6 +18,19 @@ use crate::mem::MaybeUninit;
 ///
 /// [`fs::copy`]: ../fs/fn.copy.html
 ///
+/// # Platform-specific behavior
+///
+/// On Linux (including Android), this function uses `copy_file_range(2)`,
+/// `sendfile(2)` or `splice(2)` syscalls to move data directly between file
+/// descriptors if possible. This applies to `File`, `TcpStream`, `UnixStream`
+/// and the pipes of child processes, also when they are wrapped in a
+/// `BufReader`, `BufWriter` or `Take`. If the kernel rejects these syscalls,
+/// the data is copied through a buffer as on other platforms.
+///
+/// Note that platform-specific behavior [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
 /// # Errors
 ///
 /// This function will return an error immediately if any call to `read` or
@@ -41,6 +54,21 @@ use crate::mem::MaybeUninit;
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+where
+    R: Read,
+    W: Write,
+{
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            crate::sys::kernel_copy::copy_spec(reader, writer)
+        } else {
+            generic_copy(reader, writer)
+        }
+    }
+}
+
+/// The general read-write loop of `copy`, through a stack buffer.
+pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
 where
     R: Read,
     W: Write,
@@ -279,6 +279,7 @@
 #![feature(needs_panic_runtime)]
 #![feature(never_type)]
 #![feature(nll)]
+#![feature(once_cell)]
 #![feature(optin_builtin_traits)]
 #![feature(panic_info_message)]
 #![feature(panic_internals)]
@@ -449,6 +450,8 @@ pub mod error;
 pub mod ffi;
 pub mod fs;
 pub mod io;
+#[unstable(feature = "once_cell", issue = "none")]
+pub mod lazy;
 pub mod net;
 pub mod num;
 pub mod os;
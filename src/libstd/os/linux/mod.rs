@@ -4,3 +4,5 @@
 
 pub mod fs;
 pub mod raw;
+#[cfg(any(doc, target_os = "linux"))]
+pub mod process;
@@ -0,0 +1,217 @@
+//! Linux-specific extensions to primitives in the `std::process` module.
+
+#![unstable(feature = "linux_pidfd", issue = "none")]
+
+use crate::io::Result;
+use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::process;
+use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
+use crate::time::Duration;
+
+#[cfg(not(doc))]
+use crate::sys::fd::FileDesc;
+
+#[cfg(doc)]
+struct FileDesc;
+
+/// This type represents a file descriptor that refers to a process.
+///
+/// A `PidFd` can be obtained by setting the corresponding option on [`Command`]
+/// with [`create_pidfd`]. Subsequently, the created pidfd can be retrieved
+/// from the [`Child`] by calling [`pidfd`] or [`take_pidfd`].
+///
+/// Example:
+/// ```no_run
+/// #![feature(linux_pidfd)]
+/// use std::os::linux::process::{CommandExt, ChildExt};
+/// use std::process::Command;
+///
+/// let mut child = Command::new("echo")
+///     .create_pidfd(true)
+///     .spawn()
+///     .expect("Failed to spawn child");
+///
+/// let pidfd = child
+///     .take_pidfd()
+///     .expect("Failed to retrieve pidfd");
+///
+/// // The file descriptor will be closed when `pidfd` is dropped.
+/// ```
+/// Refer to the man page of [`pidfd_open(2)`] for further details.
+///
+/// [`Command`]: ../../../../std/process/struct.Command.html
+/// [`create_pidfd`]: trait.CommandExt.html#tymethod.create_pidfd
+/// [`Child`]: ../../../../std/process/struct.Child.html
+/// [`pidfd`]: trait.ChildExt.html#tymethod.pidfd
+/// [`take_pidfd`]: trait.ChildExt.html#tymethod.take_pidfd
+/// [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
+#[derive(Debug)]
+pub struct PidFd {
+    inner: FileDesc,
+}
+
+impl AsInner<FileDesc> for PidFd {
+    fn as_inner(&self) -> &FileDesc {
+        &self.inner
+    }
+}
+
+impl FromInner<FileDesc> for PidFd {
+    fn from_inner(inner: FileDesc) -> PidFd {
+        PidFd { inner }
+    }
+}
+
+impl IntoInner<FileDesc> for PidFd {
+    fn into_inner(self) -> FileDesc {
+        self.inner
+    }
+}
+
+impl AsRawFd for PidFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().raw()
+    }
+}
+
+impl FromRawFd for PidFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::from_inner(FileDesc::new(fd))
+    }
+}
+
+impl IntoRawFd for PidFd {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_inner().into_raw()
+    }
+}
+
+mod private {
+    /// This trait being unreachable from outside the crate prevents other implementations of
+    /// the extension traits below, which allows adding more methods to them later.
+    pub trait Sealed {}
+}
+
+impl private::Sealed for process::Command {}
+impl private::Sealed for process::Child {}
+
+/// Os-specific extensions for [`Child`]
+///
+/// This trait is sealed: it cannot be implemented outside the standard library.
+///
+/// [`Child`]: ../../../../std/process/struct.Child.html
+pub trait ChildExt: private::Sealed {
+    /// Obtains a reference to the [`PidFd`] created for this [`Child`], if available.
+    ///
+    /// A pidfd will only be available if its creation was requested with
+    /// [`create_pidfd`] when the corresponding [`Command`] was created.
+    ///
+    /// Even if requested, a pidfd may not be available due to an older
+    /// version of Linux being in use, or if some other error occurred.
+    ///
+    /// [`Command`]: ../../../../std/process/struct.Command.html
+    /// [`create_pidfd`]: trait.CommandExt.html#tymethod.create_pidfd
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    /// [`PidFd`]: struct.PidFd.html
+    fn pidfd(&self) -> Result<&PidFd>;
+
+    /// Takes ownership of the [`PidFd`] created for this [`Child`], if available.
+    ///
+    /// A pidfd will only be available if its creation was requested with
+    /// [`create_pidfd`] when the corresponding [`Command`] was created.
+    ///
+    /// Even if requested, a pidfd may not be available due to an older
+    /// version of Linux being in use, or if some other error occurred.
+    ///
+    /// Once the pidfd has been taken, [`kill`], [`try_wait`] and
+    /// [`wait_timeout`] go back to operating on the raw process ID.
+    ///
+    /// [`Command`]: ../../../../std/process/struct.Command.html
+    /// [`create_pidfd`]: trait.CommandExt.html#tymethod.create_pidfd
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    /// [`PidFd`]: struct.PidFd.html
+    /// [`kill`]: ../../../../std/process/struct.Child.html#method.kill
+    /// [`try_wait`]: ../../../../std/process/struct.Child.html#method.try_wait
+    /// [`wait_timeout`]: #tymethod.wait_timeout
+    fn take_pidfd(&mut self) -> Result<PidFd>;
+
+    /// Waits for the child to exit for at most `timeout`.
+    ///
+    /// Returns `Ok(None)` if the child is still running once the timeout has
+    /// elapsed, and its exit status otherwise. Like [`wait`], this closes the
+    /// child's stdin first, so that it doesn't wait for input forever.
+    ///
+    /// If the child has a pidfd, the current thread sleeps until either the
+    /// child exits or the timeout elapses. Otherwise, the child's status is
+    /// polled at increasing intervals.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd)]
+    /// use std::os::linux::process::{ChildExt, CommandExt};
+    /// use std::process::Command;
+    /// use std::time::Duration;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").create_pidfd(true).spawn().unwrap();
+    /// match child.wait_timeout(Duration::from_secs(1)).unwrap() {
+    ///     Some(status) => println!("exited with: {}", status),
+    ///     None => child.kill().unwrap(),
+    /// }
+    /// ```
+    ///
+    /// [`wait`]: ../../../../std/process/struct.Child.html#method.wait
+    fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<process::ExitStatus>>;
+}
+
+/// Os-specific extensions for [`Command`]
+///
+/// This trait is sealed: it cannot be implemented outside the standard library.
+///
+/// [`Command`]: ../../../../std/process/struct.Command.html
+pub trait CommandExt: private::Sealed {
+    /// Sets whether a [`PidFd`] should be created for the [`Child`]
+    /// spawned by this [`Command`].
+    /// By default, no pidfd will be created.
+    ///
+    /// The pidfd can be retrieved from the child with [`pidfd`] or [`take_pidfd`].
+    ///
+    /// A pidfd will only be created if it is possible to do so
+    /// in a guaranteed race-free manner, which requires the `clone3` system
+    /// call (Linux 5.3 or later, and not blocked by a seccomp filter).
+    /// Otherwise, the child is spawned without one, and [`pidfd`] will return
+    /// an error.
+    ///
+    /// [`Command`]: ../../../../std/process/struct.Command.html
+    /// [`Child`]: ../../../../std/process/struct.Child.html
+    /// [`PidFd`]: struct.PidFd.html
+    /// [`pidfd`]: trait.ChildExt.html#tymethod.pidfd
+    /// [`take_pidfd`]: trait.ChildExt.html#tymethod.take_pidfd
+    fn create_pidfd(&mut self, val: bool) -> &mut process::Command;
+}
+
+impl CommandExt for process::Command {
+    fn create_pidfd(&mut self, val: bool) -> &mut process::Command {
+        self.as_inner_mut().create_pidfd(val);
+        self
+    }
+}
+
+impl ChildExt for process::Child {
+    fn pidfd(&self) -> Result<&PidFd> {
+        self.as_inner().pidfd().ok_or_else(no_pidfd)
+    }
+
+    fn take_pidfd(&mut self) -> Result<PidFd> {
+        self.as_inner_mut().take_pidfd().ok_or_else(no_pidfd)
+    }
+
+    fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<process::ExitStatus>> {
+        drop(self.stdin.take());
+        self.as_inner_mut().wait_timeout(timeout).map(|status| status.map(FromInner::from_inner))
+    }
+}
+
+fn no_pidfd() -> crate::io::Error {
+    crate::io::Error::new(crate::io::ErrorKind::Other, "No pidfd was created.")
+}
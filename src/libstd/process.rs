@@ -191,6 +191,12 @@ impl AsInner<imp::Process> for Child {
     }
 }
 
+impl AsInnerMut<imp::Process> for Child {
+    fn as_inner_mut(&mut self) -> &mut imp::Process {
+        &mut self.handle
+    }
+}
+
 impl FromInner<(imp::Process, imp::StdioPipes)> for Child {
     fn from_inner((handle, io): (imp::Process, imp::StdioPipes)) -> Child {
         Child {
@@ -2090,6 +2096,25 @@ mod tests {
         assert!(events > 0);
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_wait_timeout() {
+        use crate::os::linux::process::{ChildExt, CommandExt};
+        use crate::time::Duration;
+
+        for &create_pidfd in &[false, true] {
+            let mut p =
+                Command::new("sleep").arg("1000").create_pidfd(create_pidfd).spawn().unwrap();
+            if !create_pidfd {
+                assert!(p.pidfd().is_err());
+            }
+            assert!(p.wait_timeout(Duration::from_millis(10)).unwrap().is_none());
+            p.kill().unwrap();
+            let status = p.wait_timeout(Duration::from_secs(60)).unwrap().unwrap();
+            assert!(!status.success());
+        }
+    }
+
     #[test]
     fn test_command_implements_send() {
         fn take_send_type<T: Send>(_: T) {}
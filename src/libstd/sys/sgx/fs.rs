@@ -6,6 +6,8 @@ use crate::path::{Path, PathBuf};
 use crate::sys::time::SystemTime;
 use crate::sys::{unsupported, Void};
 
+pub use crate::sys_common::fs::DirHandle;
+
 pub struct File(Void);
 
 pub struct FileAttr(Void);
@@ -0,0 +1,154 @@
+use crate::sync::atomic::{AtomicU32, Ordering::Relaxed};
+use crate::sys::futex::{futex_wait, futex_wake, futex_wake_all};
+use crate::sys::mutex::Mutex;
+use crate::time::Duration;
+
+pub type MovableCondvar = Condvar;
+
+// The futex of a condition variable is incremented on every notification. A
+// waiting thread reads it before unlocking the mutex, and only goes to sleep
+// if it didn't change since, so it can't miss a notification sent in between.
+pub struct Condvar {
+    futex: AtomicU32,
+}
+
+impl Condvar {
+    pub const fn new() -> Condvar {
+        Condvar { futex: AtomicU32::new(0) }
+    }
+
+    #[inline]
+    pub unsafe fn init(&mut self) {}
+
+    #[inline]
+    pub unsafe fn destroy(&self) {}
+
+    // All the memory orderings here are `Relaxed`, because synchronization is
+    // done by unlocking and locking the mutex.
+
+    pub unsafe fn notify_one(&self) {
+        self.futex.fetch_add(1, Relaxed);
+        futex_wake(&self.futex);
+    }
+
+    pub unsafe fn notify_all(&self) {
+        self.futex.fetch_add(1, Relaxed);
+        futex_wake_all(&self.futex);
+    }
+
+    pub unsafe fn wait(&self, mutex: &Mutex) {
+        self.wait_optional_timeout(mutex, None);
+    }
+
+    pub unsafe fn wait_timeout(&self, mutex: &Mutex, timeout: Duration) -> bool {
+        self.wait_optional_timeout(mutex, Some(timeout))
+    }
+
+    unsafe fn wait_optional_timeout(&self, mutex: &Mutex, timeout: Option<Duration>) -> bool {
+        // Examine the notification counter _before_ we unlock the mutex.
+        let futex_value = self.futex.load(Relaxed);
+
+        // Unlock the mutex before going to sleep.
+        mutex.unlock();
+
+        // Wait, but only if there hasn't been any notification since we
+        // unlocked the mutex.
+        let r = futex_wait(&self.futex, futex_value, timeout);
+
+        // Lock the mutex again.
+        mutex.lock();
+
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Condvar;
+    use crate::cell::UnsafeCell;
+    use crate::sync::Arc;
+    use crate::sys::mutex::Mutex;
+    use crate::thread;
+
+    /// A counter protected by a mutex, with a condition variable to wait for it to change.
+    struct Shared {
+        mutex: Mutex,
+        condvar: Condvar,
+        count: UnsafeCell<usize>,
+    }
+
+    unsafe impl Sync for Shared {}
+
+    impl Shared {
+        fn new() -> Shared {
+            Shared { mutex: Mutex::new(), condvar: Condvar::new(), count: UnsafeCell::new(0) }
+        }
+    }
+
+    #[test]
+    fn notify_all_contended() {
+        const THREADS: usize = 8;
+
+        let shared = Arc::new(Shared::new());
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let shared = shared.clone();
+                thread::spawn(move || unsafe {
+                    shared.mutex.lock();
+                    *shared.count.get() += 1;
+                    shared.condvar.notify_all();
+                    // Wait for the main thread to reset the count once all threads got here.
+                    while *shared.count.get() != 0 {
+                        shared.condvar.wait(&shared.mutex);
+                    }
+                    shared.mutex.unlock();
+                })
+            })
+            .collect();
+
+        unsafe {
+            shared.mutex.lock();
+            while *shared.count.get() != THREADS {
+                shared.condvar.wait(&shared.mutex);
+            }
+            *shared.count.get() = 0;
+            shared.condvar.notify_all();
+            shared.mutex.unlock();
+        }
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn notify_one_ping_pong() {
+        const ROUNDS: usize = 10_000;
+
+        // Two threads take turns incrementing the count, the first one the even values and the
+        // second one the odd values. A lost notification makes both of them wait forever.
+        let shared = Arc::new(Shared::new());
+        let threads: Vec<_> = (0..2)
+            .map(|parity| {
+                let shared = shared.clone();
+                thread::spawn(move || unsafe {
+                    shared.mutex.lock();
+                    loop {
+                        while *shared.count.get() % 2 != parity && *shared.count.get() < ROUNDS {
+                            shared.condvar.wait(&shared.mutex);
+                        }
+                        if *shared.count.get() >= ROUNDS {
+                            break;
+                        }
+                        *shared.count.get() += 1;
+                        shared.condvar.notify_one();
+                    }
+                    shared.mutex.unlock();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(unsafe { *shared.count.get() }, ROUNDS);
+    }
+}
@@ -81,6 +81,13 @@ impl IntoRawFd for fs::File {
     }
 }
 
+#[unstable(feature = "fs_dir", issue = "none")]
+impl AsRawFd for fs::Dir {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().fd().raw()
+    }
+}
+
 #[stable(feature = "asraw_stdio", since = "1.21.0")]
 impl AsRawFd for io::Stdin {
     fn as_raw_fd(&self) -> RawFd {
@@ -16,8 +16,19 @@ use libc::{c_int, mode_t};
 
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "android"))]
 use libc::dirfd;
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "emscripten",
+    target_os = "android",
+    target_os = "macos"
+)))]
+use libc::fstatat as fstatat64;
 #[cfg(any(target_os = "linux", target_os = "emscripten"))]
 use libc::fstatat64;
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+use libc::openat as openat64;
+#[cfg(target_os = "linux")]
+use libc::openat64;
 #[cfg(not(any(
     target_os = "linux",
     target_os = "emscripten",
@@ -46,8 +57,10 @@ use libc::{
 use libc::{
     dirent64, fstat64, ftruncate64, lseek64, lstat64, off64_t, open64, readdir64_r, stat64,
 };
-
-pub use crate::sys_common::fs::remove_dir_all;
+#[cfg(not(target_os = "macos"))]
+use libc::{fdopendir, mkdirat, renameat, unlinkat};
+#[cfg(target_os = "macos")]
+use macos_at::{fdopendir, fstatat64, mkdirat, openat64, renameat, unlinkat};
 
 pub struct File(FileDesc);
 
@@ -193,6 +206,11 @@ struct Dir(*mut libc::DIR);
 unsafe impl Send for Dir {}
 unsafe impl Sync for Dir {}
 
+pub struct DirHandle {
+    fd: FileDesc,
+    root: PathBuf,
+}
+
 pub struct DirEntry {
     entry: dirent64,
     dir: ReadDir,
@@ -683,53 +701,17 @@ impl File {
             | (opts.custom_flags as c_int & !libc::O_ACCMODE);
         let fd = cvt_r(|| unsafe { open64(path.as_ptr(), flags, opts.mode as c_int) })?;
         let fd = FileDesc::new(fd);
+        ensure_cloexec(&fd)?;
+        Ok(File(fd))
+    }
 
-        // Currently the standard library supports Linux 2.6.18 which did not
-        // have the O_CLOEXEC flag (passed above). If we're running on an older
-        // Linux kernel then the flag is just ignored by the OS. After we open
-        // the first file, we check whether it has CLOEXEC set. If it doesn't,
-        // we will explicitly ask for a CLOEXEC fd for every further file we
-        // open, if it does, we will skip that step.
-        //
-        // The CLOEXEC flag, however, is supported on versions of macOS/BSD/etc
-        // that we support, so we only do this on Linux currently.
-        #[cfg(target_os = "linux")]
-        fn ensure_cloexec(fd: &FileDesc) -> io::Result<()> {
-            use crate::sync::atomic::{AtomicUsize, Ordering};
-
-            const OPEN_CLOEXEC_UNKNOWN: usize = 0;
-            const OPEN_CLOEXEC_SUPPORTED: usize = 1;
-            const OPEN_CLOEXEC_NOTSUPPORTED: usize = 2;
-            static OPEN_CLOEXEC: AtomicUsize = AtomicUsize::new(OPEN_CLOEXEC_UNKNOWN);
-
-            let need_to_set;
-            match OPEN_CLOEXEC.load(Ordering::Relaxed) {
-                OPEN_CLOEXEC_UNKNOWN => {
-                    need_to_set = !fd.get_cloexec()?;
-                    OPEN_CLOEXEC.store(
-                        if need_to_set {
-                            OPEN_CLOEXEC_NOTSUPPORTED
-                        } else {
-                            OPEN_CLOEXEC_SUPPORTED
-                        },
-                        Ordering::Relaxed,
-                    );
-                }
-                OPEN_CLOEXEC_SUPPORTED => need_to_set = false,
-                OPEN_CLOEXEC_NOTSUPPORTED => need_to_set = true,
-                _ => unreachable!(),
-            }
-            if need_to_set {
-                fd.set_cloexec()?;
-            }
-            Ok(())
-        }
-
-        #[cfg(not(target_os = "linux"))]
-        fn ensure_cloexec(_: &FileDesc) -> io::Result<()> {
-            Ok(())
-        }
-
+    pub fn open_at_c(dirfd: c_int, path: &CStr, opts: &OpenOptions) -> io::Result<File> {
+        let flags = libc::O_CLOEXEC
+            | opts.get_access_mode()?
+            | opts.get_creation_mode()?
+            | (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        let fd = cvt_r(|| unsafe { openat64(dirfd, path.as_ptr(), flags, opts.mode as c_int) })?;
+        let fd = FileDesc::new(fd);
         ensure_cloexec(&fd)?;
         Ok(File(fd))
     }
@@ -876,6 +858,48 @@ fn cstr(path: &Path) -> io::Result<CString> {
     Ok(CString::new(path.as_os_str().as_bytes())?)
 }
 
+// Currently the standard library supports Linux 2.6.18 which did not
+// have the O_CLOEXEC flag (passed when opening). If we're running on an older
+// Linux kernel then the flag is just ignored by the OS. After we open
+// the first file, we check whether it has CLOEXEC set. If it doesn't,
+// we will explicitly ask for a CLOEXEC fd for every further file we
+// open, if it does, we will skip that step.
+//
+// The CLOEXEC flag, however, is supported on versions of macOS/BSD/etc
+// that we support, so we only do this on Linux currently.
+#[cfg(target_os = "linux")]
+fn ensure_cloexec(fd: &FileDesc) -> io::Result<()> {
+    use crate::sync::atomic::{AtomicUsize, Ordering};
+
+    const OPEN_CLOEXEC_UNKNOWN: usize = 0;
+    const OPEN_CLOEXEC_SUPPORTED: usize = 1;
+    const OPEN_CLOEXEC_NOTSUPPORTED: usize = 2;
+    static OPEN_CLOEXEC: AtomicUsize = AtomicUsize::new(OPEN_CLOEXEC_UNKNOWN);
+
+    let need_to_set;
+    match OPEN_CLOEXEC.load(Ordering::Relaxed) {
+        OPEN_CLOEXEC_UNKNOWN => {
+            need_to_set = !fd.get_cloexec()?;
+            OPEN_CLOEXEC.store(
+                if need_to_set { OPEN_CLOEXEC_NOTSUPPORTED } else { OPEN_CLOEXEC_SUPPORTED },
+                Ordering::Relaxed,
+            );
+        }
+        OPEN_CLOEXEC_SUPPORTED => need_to_set = false,
+        OPEN_CLOEXEC_NOTSUPPORTED => need_to_set = true,
+        _ => unreachable!(),
+    }
+    if need_to_set {
+        fd.set_cloexec()?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn ensure_cloexec(_: &FileDesc) -> io::Result<()> {
+    Ok(())
+}
+
 impl FromInner<c_int> for File {
     fn from_inner(fd: c_int) -> File {
         File(FileDesc::new(fd))
@@ -948,6 +972,105 @@ impl fmt::Debug for File {
     }
 }
 
+impl DirHandle {
+    pub fn open(p: &Path) -> io::Result<DirHandle> {
+        let root = p.to_path_buf();
+        let p = cstr(p)?;
+        let fd = open_dir_at(libc::AT_FDCWD, &p, 0)?;
+        Ok(DirHandle { fd, root })
+    }
+
+    pub fn open_file(&self, p: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let p = cstr(p)?;
+        File::open_at_c(self.fd.raw(), &p, opts)
+    }
+
+    pub fn mkdir(&self, p: &Path) -> io::Result<()> {
+        let p = cstr(p)?;
+        cvt(unsafe { mkdirat(self.fd.raw(), p.as_ptr(), 0o777) })?;
+        Ok(())
+    }
+
+    pub fn unlink(&self, p: &Path) -> io::Result<()> {
+        let p = cstr(p)?;
+        cvt(unsafe { unlinkat(self.fd.raw(), p.as_ptr(), 0) })?;
+        Ok(())
+    }
+
+    pub fn rmdir(&self, p: &Path) -> io::Result<()> {
+        let p = cstr(p)?;
+        cvt(unsafe { unlinkat(self.fd.raw(), p.as_ptr(), libc::AT_REMOVEDIR) })?;
+        Ok(())
+    }
+
+    pub fn lstat(&self, p: &Path) -> io::Result<FileAttr> {
+        let p = cstr(p)?;
+        lstat_at(self.fd.raw(), &p)
+    }
+
+    pub fn readdir(&self, p: &Path) -> io::Result<ReadDir> {
+        let root = self.root.join(p);
+        let p = cstr(p)?;
+        let fd = open_dir_at(self.fd.raw(), &p, 0)?;
+        fdreaddir(fd, root).map(|(dir, _)| dir)
+    }
+
+    pub fn rename(&self, old: &Path, new_dir: &DirHandle, new: &Path) -> io::Result<()> {
+        let old = cstr(old)?;
+        let new = cstr(new)?;
+        cvt(unsafe { renameat(self.fd.raw(), old.as_ptr(), new_dir.fd.raw(), new.as_ptr()) })?;
+        Ok(())
+    }
+
+    pub fn fd(&self) -> &FileDesc {
+        &self.fd
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dir").field("fd", &self.fd.raw()).field("path", &self.root).finish()
+    }
+}
+
+fn open_dir_at(dirfd: c_int, p: &CStr, flags: c_int) -> io::Result<FileDesc> {
+    let flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC | flags;
+    let fd = cvt_r(|| unsafe { openat64(dirfd, p.as_ptr(), flags, 0) })?;
+    let fd = FileDesc::new(fd);
+    ensure_cloexec(&fd)?;
+    Ok(fd)
+}
+
+// Returns the directory stream along with its descriptor, which the stream now
+// owns and closes on drop. This is the descriptor `dirfd()` would return, which
+// isn't available as a function on old macOS versions.
+fn fdreaddir(fd: FileDesc, root: PathBuf) -> io::Result<(ReadDir, c_int)> {
+    let ptr = unsafe { fdopendir(fd.raw()) };
+    if ptr.is_null() {
+        return Err(Error::last_os_error());
+    }
+    let fd = fd.into_raw();
+    let inner = InnerReadDir { dirp: Dir(ptr), root };
+    Ok((ReadDir { inner: Arc::new(inner), end_of_stream: false }, fd))
+}
+
+fn lstat_at(dirfd: c_int, p: &CStr) -> io::Result<FileAttr> {
+    cfg_has_statx! {
+        if let Some(ret) = unsafe { try_statx(
+            dirfd,
+            p.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW | libc::AT_STATX_SYNC_AS_STAT,
+            libc::STATX_ALL,
+        ) } {
+            return ret;
+        }
+    }
+
+    let mut stat: stat64 = unsafe { mem::zeroed() };
+    cvt(unsafe { fstatat64(dirfd, p.as_ptr(), &mut stat, libc::AT_SYMLINK_NOFOLLOW) })?;
+    Ok(FileAttr::from_stat64(stat))
+}
+
 pub fn readdir(p: &Path) -> io::Result<ReadDir> {
     let root = p.to_path_buf();
     let p = cstr(p)?;
@@ -987,6 +1110,145 @@ pub fn rmdir(p: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn remove_dir_all(path: &Path) -> io::Result<()> {
+    // Without the `*at` functions, fall back to removing everything by path.
+    #[cfg(target_os = "macos")]
+    {
+        if !macos_at::available() {
+            return crate::sys_common::fs::remove_dir_all(path);
+        }
+    }
+
+    let filetype = lstat(path)?.file_type();
+    if filetype.is_symlink() {
+        unlink(path)
+    } else {
+        remove_dir_all_recursive(libc::AT_FDCWD, &cstr(path)?, path)
+    }
+}
+
+// Every directory is opened relative to its parent's descriptor with
+// `O_NOFOLLOW`, and everything in it is examined and removed relative to that
+// same descriptor, so replacing a directory in the tree with a symlink while
+// the removal is in progress makes the removal fail rather than escape the
+// tree.
+fn remove_dir_all_recursive(parent_fd: c_int, name: &CStr, path: &Path) -> io::Result<()> {
+    let fd = open_dir_at(parent_fd, name, libc::O_NOFOLLOW)?;
+    let (dir, fd) = fdreaddir(fd, path.to_path_buf())?;
+    for child in dir {
+        let child = child?;
+        let child_name = CString::new(child.name_bytes())?;
+        if is_dir_at(fd, &child, &child_name)? {
+            remove_dir_all_recursive(fd, &child_name, &child.path())?;
+        } else {
+            cvt(unsafe { unlinkat(fd, child_name.as_ptr(), 0) })?;
+        }
+    }
+    cvt(unsafe { unlinkat(parent_fd, name.as_ptr(), libc::AT_REMOVEDIR) })?;
+    Ok(())
+}
+
+// Like `child.file_type()?.is_dir()`, but an entry of unknown type is examined
+// relative to the descriptor of its directory instead of by path.
+#[cfg(not(any(target_os = "solaris", target_os = "haiku")))]
+fn is_dir_at(dirfd: c_int, child: &DirEntry, name: &CStr) -> io::Result<bool> {
+    match child.entry.d_type {
+        libc::DT_DIR => Ok(true),
+        libc::DT_UNKNOWN => Ok(lstat_at(dirfd, name)?.file_type().is_dir()),
+        _ => Ok(false),
+    }
+}
+
+#[cfg(any(target_os = "solaris", target_os = "haiku"))]
+fn is_dir_at(dirfd: c_int, _child: &DirEntry, name: &CStr) -> io::Result<bool> {
+    Ok(lstat_at(dirfd, name)?.file_type().is_dir())
+}
+
+// `openat`, `fdopendir`, `fstatat`, `mkdirat`, `renameat` and `unlinkat` are
+// only available from macOS 10.10 on, while we still support 10.7, so they are
+// looked up at runtime. When missing, they fail with `ENOSYS`.
+#[cfg(target_os = "macos")]
+mod macos_at {
+    use crate::ptr;
+    use crate::sys::os;
+    use libc::{c_char, c_int, mode_t, stat, DIR};
+
+    /// Returns whether the functions `remove_dir_all` needs are available.
+    pub fn available() -> bool {
+        weak!(fn openat(c_int, *const c_char, c_int, c_int) -> c_int);
+        openat.get().is_some()
+    }
+
+    pub unsafe fn openat64(dirfd: c_int, path: *const c_char, flags: c_int, mode: c_int) -> c_int {
+        weak!(fn openat(c_int, *const c_char, c_int, c_int) -> c_int);
+        match openat.get() {
+            Some(f) => f(dirfd, path, flags, mode),
+            None => enosys(),
+        }
+    }
+
+    pub unsafe fn fdopendir(fd: c_int) -> *mut DIR {
+        #[cfg(target_arch = "x86")]
+        weak!(fn fdopendir(c_int) -> *mut DIR, "fdopendir$INODE64$UNIX2003");
+        #[cfg(not(target_arch = "x86"))]
+        weak!(fn fdopendir(c_int) -> *mut DIR, "fdopendir$INODE64");
+        match fdopendir.get() {
+            Some(f) => f(fd),
+            None => {
+                os::set_errno(libc::ENOSYS);
+                ptr::null_mut()
+            }
+        }
+    }
+
+    pub unsafe fn fstatat64(
+        dirfd: c_int,
+        path: *const c_char,
+        buf: *mut stat,
+        flags: c_int,
+    ) -> c_int {
+        weak!(fn fstatat(c_int, *const c_char, *mut stat, c_int) -> c_int, "fstatat$INODE64");
+        match fstatat.get() {
+            Some(f) => f(dirfd, path, buf, flags),
+            None => enosys(),
+        }
+    }
+
+    pub unsafe fn mkdirat(dirfd: c_int, path: *const c_char, mode: mode_t) -> c_int {
+        weak!(fn mkdirat(c_int, *const c_char, mode_t) -> c_int);
+        match mkdirat.get() {
+            Some(f) => f(dirfd, path, mode),
+            None => enosys(),
+        }
+    }
+
+    pub unsafe fn renameat(
+        olddirfd: c_int,
+        oldpath: *const c_char,
+        newdirfd: c_int,
+        newpath: *const c_char,
+    ) -> c_int {
+        weak!(fn renameat(c_int, *const c_char, c_int, *const c_char) -> c_int);
+        match renameat.get() {
+            Some(f) => f(olddirfd, oldpath, newdirfd, newpath),
+            None => enosys(),
+        }
+    }
+
+    pub unsafe fn unlinkat(dirfd: c_int, path: *const c_char, flags: c_int) -> c_int {
+        weak!(fn unlinkat(c_int, *const c_char, c_int) -> c_int);
+        match unlinkat.get() {
+            Some(f) => f(dirfd, path, flags),
+            None => enosys(),
+        }
+    }
+
+    fn enosys() -> c_int {
+        os::set_errno(libc::ENOSYS);
+        -1
+    }
+}
+
 pub fn readlink(p: &Path) -> io::Result<PathBuf> {
     let c_path = cstr(p)?;
     let p = c_path.as_ptr();
@@ -1134,81 +1396,18 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
-    use crate::cmp;
-    use crate::sync::atomic::{AtomicBool, Ordering};
-
-    // Kernel prior to 4.5 don't have copy_file_range
-    // We store the availability in a global to avoid unnecessary syscalls
-    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
-
-    unsafe fn copy_file_range(
-        fd_in: libc::c_int,
-        off_in: *mut libc::loff_t,
-        fd_out: libc::c_int,
-        off_out: *mut libc::loff_t,
-        len: libc::size_t,
-        flags: libc::c_uint,
-    ) -> libc::c_long {
-        libc::syscall(libc::SYS_copy_file_range, fd_in, off_in, fd_out, off_out, len, flags)
-    }
+    use super::kernel_copy::{copy_regular_files, CopyResult};
 
     let (mut reader, reader_metadata) = open_from(from)?;
-    let len = reader_metadata.len();
     let (mut writer, _) = open_to_and_set_permissions(to, reader_metadata)?;
 
-    let has_copy_file_range = HAS_COPY_FILE_RANGE.load(Ordering::Relaxed);
-    let mut written = 0u64;
-    while written < len {
-        let copy_result = if has_copy_file_range {
-            let bytes_to_copy = cmp::min(len - written, usize::max_value() as u64) as usize;
-            let copy_result = unsafe {
-                // We actually don't have to adjust the offsets,
-                // because copy_file_range adjusts the file offset automatically
-                cvt(copy_file_range(
-                    reader.as_raw_fd(),
-                    ptr::null_mut(),
-                    writer.as_raw_fd(),
-                    ptr::null_mut(),
-                    bytes_to_copy,
-                    0,
-                ))
-            };
-            if let Err(ref copy_err) = copy_result {
-                match copy_err.raw_os_error() {
-                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
-                        HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
-                    }
-                    _ => {}
-                }
-            }
-            copy_result
-        } else {
-            Err(io::Error::from_raw_os_error(libc::ENOSYS))
-        };
-        match copy_result {
-            Ok(ret) => written += ret as u64,
-            Err(err) => {
-                match err.raw_os_error() {
-                    Some(os_err)
-                        if os_err == libc::ENOSYS
-                            || os_err == libc::EXDEV
-                            || os_err == libc::EINVAL
-                            || os_err == libc::EPERM =>
-                    {
-                        // Try fallback io::copy if either:
-                        // - Kernel version is < 4.5 (ENOSYS)
-                        // - Files are mounted on different fs (EXDEV)
-                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
-                        // - copy_file_range cannot be used with pipes or device nodes (EINVAL)
-                        assert_eq!(written, 0);
-                        return io::copy(&mut reader, &mut writer);
-                    }
-                    _ => return Err(err),
-                }
-            }
+    match copy_regular_files(reader.as_raw_fd(), writer.as_raw_fd(), u64::max_value()) {
+        CopyResult::Ended(bytes) => Ok(bytes),
+        CopyResult::Error(e, _) => Err(e),
+        CopyResult::Fallback(written) => {
+            io::generic_copy(&mut reader, &mut writer).map(|bytes| bytes + written)
         }
     }
-    Ok(written)
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
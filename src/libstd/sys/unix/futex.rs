@@ -0,0 +1,58 @@
+//! Wrappers around the Linux `futex(2)` syscall, used by the lock implementations in
+//! `mutex_futex.rs`, `condvar_futex.rs` and `rwlock_futex.rs`.
+
+use crate::convert::TryInto;
+use crate::ptr;
+use crate::sync::atomic::AtomicU32;
+use crate::time::Duration;
+
+/// Waits for a `futex_wake` on `futex` while it holds `expected`, for at most `timeout`.
+///
+/// Returns directly if `futex` doesn't hold `expected`. Returns false only if the timeout
+/// elapsed; as with any condition variable, other wakeups may be spurious.
+pub fn futex_wait(futex: &AtomicU32, expected: u32, timeout: Option<Duration>) -> bool {
+    // A timeout that doesn't fit in a `timespec` is treated as no timeout at all.
+    let timespec = timeout.and_then(|d| {
+        Some(libc::timespec {
+            tv_sec: d.as_secs().try_into().ok()?,
+            tv_nsec: d.subsec_nanos() as _,
+        })
+    });
+    let r = unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            timespec.as_ref().map_or(ptr::null(), |t| t as *const libc::timespec),
+        )
+    };
+    !(r < 0 && super::os::errno() == libc::ETIMEDOUT)
+}
+
+/// Wakes up one thread blocked in `futex_wait` on `futex`.
+///
+/// Returns true if a thread was woken up. If false, a thread may still be about to call
+/// `futex_wait`, which returns directly if the value of `futex` changed before.
+pub fn futex_wake(futex: &AtomicU32) -> bool {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            1,
+        ) > 0
+    }
+}
+
+/// Wakes up all threads blocked in `futex_wait` on `futex`.
+pub fn futex_wake_all(futex: &AtomicU32) {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            i32::max_value(),
+        );
+    }
+}
@@ -0,0 +1,668 @@
+//! Specializations of `io::copy` that offload copies between file descriptor backed std types
+//! (`File`, `TcpStream`, `UnixStream` and child process pipes) to the kernel.
+//!
+//! Specialization is only applied to types wholly owned by std, so user code can't observe that
+//! the `Read` and `Write` implementations are bypassed.
+//!
+//! Readers and writers are handled separately by the `CopyRead` and `CopyWrite` traits, which
+//! extract the file descriptors from the std types and the wrappers around them (`BufReader`,
+//! `BufWriter`, `Take`). Before operating on the file descriptors, the buffers of the wrappers
+//! are flushed so the data order is preserved.
+//!
+//! Whether `copy_file_range(2)`, `sendfile(2)` or `splice(2)` accepts a pair of file
+//! descriptors can't be fully checked in advance, so they are simply tried one after another,
+//! guided by hints about the file types, before falling back to the generic read-write loop.
+//! Once a syscall accepts the file descriptors, it is called in a loop until the copy is done.
+
+use crate::cmp::min;
+use crate::fs::{File, Metadata};
+use crate::io::{self, BufRead, BufReader, BufWriter, Read, Take, Write};
+use crate::mem::ManuallyDrop;
+use crate::net::TcpStream;
+use crate::os::unix::fs::FileTypeExt;
+use crate::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use crate::os::unix::net::UnixStream;
+use crate::process::{ChildStderr, ChildStdin, ChildStdout};
+use crate::ptr;
+use crate::sync::atomic::{AtomicBool, Ordering};
+use crate::sys::cvt;
+
+pub fn copy_spec<R: Read + ?Sized, W: Write + ?Sized>(
+    read: &mut R,
+    write: &mut W,
+) -> io::Result<u64> {
+    let copier = Copier { read, write };
+    SpecCopy::copy(copier)
+}
+
+/// The file type of a file descriptor, either inferred from the type it was extracted from or
+/// queried from its metadata.
+///
+/// This is only a hint: because of `FromRawFd`, a type can hold any kind of file descriptor.
+enum FdMeta {
+    Metadata(Metadata),
+    Socket,
+    Pipe,
+    /// The file descriptor comes from a type that can hold any file type, like `File`, and its
+    /// metadata wasn't queried.
+    NoneObtained,
+}
+
+impl FdMeta {
+    fn maybe_fifo(&self) -> bool {
+        match self {
+            FdMeta::Metadata(meta) => meta.file_type().is_fifo(),
+            FdMeta::Socket => false,
+            FdMeta::Pipe => true,
+            FdMeta::NoneObtained => true,
+        }
+    }
+
+    fn potential_sendfile_source(&self) -> bool {
+        match self {
+            // procfs erroneously reports a length of 0 for files that aren't empty, and `read` can
+            // tell cheaply whether a file is actually empty, so only non-empty files are tried.
+            FdMeta::Metadata(meta)
+                if meta.file_type().is_file() && meta.len() > 0
+                    || meta.file_type().is_block_device() =>
+            {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn copy_file_range_candidate(&self) -> bool {
+        match self {
+            // `copy_file_range` fails on procfs files, which report a length of 0.
+            FdMeta::Metadata(meta) if meta.is_file() && meta.len() > 0 => true,
+            FdMeta::NoneObtained => true,
+            _ => false,
+        }
+    }
+}
+
+struct CopyParams(FdMeta, Option<RawFd>);
+
+struct Copier<'a, 'b, R: Read + ?Sized, W: Write + ?Sized> {
+    read: &'a mut R,
+    write: &'b mut W,
+}
+
+trait SpecCopy {
+    fn copy(self) -> io::Result<u64>;
+}
+
+impl<R: Read + ?Sized, W: Write + ?Sized> SpecCopy for Copier<'_, '_, R, W> {
+    default fn copy(self) -> io::Result<u64> {
+        io::generic_copy(self.read, self.write)
+    }
+}
+
+impl<R: CopyRead, W: CopyWrite> SpecCopy for Copier<'_, '_, R, W> {
+    fn copy(self) -> io::Result<u64> {
+        let (reader, writer) = (self.read, self.write);
+        let r_cfg = reader.properties();
+        let w_cfg = writer.properties();
+
+        let mut written = 0u64;
+
+        if let (CopyParams(input_meta, Some(readfd)), CopyParams(output_meta, Some(writefd))) =
+            (r_cfg, w_cfg)
+        {
+            // Empty the buffers of the reader and the writer before bypassing them. The bytes in
+            // the buffer of a `BufWriter` were already counted by earlier calls to `copy`.
+            written += reader.drain_to(writer, u64::max_value())?;
+            writer.flush()?;
+
+            let max_write = reader.min_limit();
+
+            if input_meta.copy_file_range_candidate() && output_meta.copy_file_range_candidate() {
+                let result = copy_regular_files(readfd, writefd, max_write);
+                result.update_take(reader);
+
+                match result {
+                    CopyResult::Ended(bytes_copied) => return Ok(bytes_copied + written),
+                    CopyResult::Error(e, _) => return Err(e),
+                    CopyResult::Fallback(bytes) => written += bytes,
+                }
+            }
+
+            // On recent kernels, `sendfile` can copy from any mmapable file to any file
+            // descriptor, while older kernels only accept a socket as the writer. Either way, it
+            // is simply tried before falling back.
+            if input_meta.potential_sendfile_source() {
+                let result = sendfile_splice(SpliceMode::Sendfile, readfd, writefd, max_write);
+                result.update_take(reader);
+
+                match result {
+                    CopyResult::Ended(bytes_copied) => return Ok(bytes_copied + written),
+                    CopyResult::Error(e, _) => return Err(e),
+                    CopyResult::Fallback(bytes) => written += bytes,
+                }
+            }
+
+            if input_meta.maybe_fifo() || output_meta.maybe_fifo() {
+                let result = sendfile_splice(SpliceMode::Splice, readfd, writefd, max_write);
+                result.update_take(reader);
+
+                match result {
+                    CopyResult::Ended(bytes_copied) => return Ok(bytes_copied + written),
+                    CopyResult::Error(e, _) => return Err(e),
+                    CopyResult::Fallback(0) => {}
+                    CopyResult::Fallback(_) => {
+                        unreachable!("splice should not return > 0 bytes on the fallback path")
+                    }
+                }
+            }
+        }
+
+        // None of the syscalls accepted the file descriptors.
+        match io::generic_copy(reader, writer) {
+            Ok(bytes) => Ok(bytes + written),
+            err => err,
+        }
+    }
+}
+
+trait CopyRead: Read {
+    /// Writes the data buffered by the reader, if any, to `writer`, up to `limit` bytes. Nested
+    /// buffers are drained from the outermost to the innermost one.
+    fn drain_to<W: Write>(&mut self, _writer: &mut W, _limit: u64) -> io::Result<u64> {
+        Ok(0)
+    }
+
+    /// Updates the limits of the `Take` wrappers after `bytes` were copied behind their backs.
+    fn taken(&mut self, _bytes: u64) {}
+
+    /// Returns the smallest limit of the `Take` wrappers, or `u64::max_value()` if there are
+    /// none. This doesn't account for buffered data, so it is only valid after `drain_to`.
+    fn min_limit(&self) -> u64 {
+        u64::max_value()
+    }
+
+    /// Returns the file descriptor of the reader and hints about its type.
+    fn properties(&self) -> CopyParams;
+}
+
+trait CopyWrite: Write {
+    /// Returns the file descriptor of the writer and hints about its type.
+    fn properties(&self) -> CopyParams;
+}
+
+impl<T> CopyRead for &mut T
+where
+    T: CopyRead,
+{
+    fn drain_to<W: Write>(&mut self, writer: &mut W, limit: u64) -> io::Result<u64> {
+        (**self).drain_to(writer, limit)
+    }
+
+    fn taken(&mut self, bytes: u64) {
+        (**self).taken(bytes);
+    }
+
+    fn min_limit(&self) -> u64 {
+        (**self).min_limit()
+    }
+
+    fn properties(&self) -> CopyParams {
+        (**self).properties()
+    }
+}
+
+impl<T> CopyWrite for &mut T
+where
+    T: CopyWrite,
+{
+    fn properties(&self) -> CopyParams {
+        (**self).properties()
+    }
+}
+
+impl CopyRead for File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(*self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::NoneObtained, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::NoneObtained, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for TcpStream {
+    fn properties(&self) -> CopyParams {
+        // avoid the stat syscall since we can be fairly sure it's a socket
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &TcpStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for TcpStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &TcpStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for ChildStdin {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for ChildStdout {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for ChildStderr {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+impl<T: CopyRead> CopyRead for Take<T> {
+    fn drain_to<W: Write>(&mut self, writer: &mut W, outer_limit: u64) -> io::Result<u64> {
+        let local_limit = self.limit();
+        let combined_limit = min(outer_limit, local_limit);
+        let bytes_drained = self.get_mut().drain_to(writer, combined_limit)?;
+        // update limit since read() was bypassed
+        self.set_limit(local_limit - bytes_drained);
+        Ok(bytes_drained)
+    }
+
+    fn taken(&mut self, bytes: u64) {
+        self.set_limit(self.limit() - bytes);
+        self.get_mut().taken(bytes);
+    }
+
+    fn min_limit(&self) -> u64 {
+        min(Take::limit(self), self.get_ref().min_limit())
+    }
+
+    fn properties(&self) -> CopyParams {
+        self.get_ref().properties()
+    }
+}
+
+impl<T: CopyRead> CopyRead for BufReader<T> {
+    fn drain_to<W: Write>(&mut self, writer: &mut W, outer_limit: u64) -> io::Result<u64> {
+        let buf = self.buffer();
+        let buf = &buf[0..min(buf.len() as u64, outer_limit) as usize];
+        let bytes = buf.len();
+        writer.write_all(buf)?;
+        self.consume(bytes);
+
+        let remaining = outer_limit - bytes as u64;
+
+        // in case of nested bufreaders we also need to drain the ones closer to the source
+        let inner_bytes = self.get_mut().drain_to(writer, remaining)?;
+
+        Ok(bytes as u64 + inner_bytes)
+    }
+
+    fn taken(&mut self, bytes: u64) {
+        self.get_mut().taken(bytes);
+    }
+
+    fn min_limit(&self) -> u64 {
+        self.get_ref().min_limit()
+    }
+
+    fn properties(&self) -> CopyParams {
+        self.get_ref().properties()
+    }
+}
+
+impl<T: CopyWrite> CopyWrite for BufWriter<T> {
+    fn properties(&self) -> CopyParams {
+        self.get_ref().properties()
+    }
+}
+
+fn fd_to_meta<T: AsRawFd>(fd: &T) -> FdMeta {
+    let fd = fd.as_raw_fd();
+    // The file descriptor is borrowed, so the `File` must not close it.
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    match file.metadata() {
+        Ok(meta) => FdMeta::Metadata(meta),
+        Err(_) => FdMeta::NoneObtained,
+    }
+}
+
+pub(super) enum CopyResult {
+    /// The copy is done, because the end of the input or the limit was reached.
+    Ended(u64),
+    /// The copy failed after the given number of bytes.
+    Error(io::Error, u64),
+    /// The syscall can't be used for these file descriptors; the given number of bytes was copied
+    /// before that was noticed, and the rest must be copied another way.
+    Fallback(u64),
+}
+
+impl CopyResult {
+    fn update_take(&self, reader: &mut impl CopyRead) {
+        match *self {
+            CopyResult::Fallback(bytes)
+            | CopyResult::Ended(bytes)
+            | CopyResult::Error(_, bytes) => reader.taken(bytes),
+        }
+    }
+}
+
+/// Copies up to `max_len` bytes between two regular files with `copy_file_range`, which also
+/// lets copy-on-write filesystems share the data instead of duplicating it.
+///
+/// Callers must fall back to another method on `Fallback`, which may come after some bytes were
+/// copied if the offset of one of the files would exceed `i64::max_value()` (`EOVERFLOW`).
+pub(super) fn copy_regular_files(reader: RawFd, writer: RawFd, max_len: u64) -> CopyResult {
+    // Kernel prior to 4.5 don't have copy_file_range
+    // We store the availability in a global to avoid unnecessary syscalls
+    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+
+    unsafe fn copy_file_range(
+        fd_in: libc::c_int,
+        off_in: *mut libc::loff_t,
+        fd_out: libc::c_int,
+        off_out: *mut libc::loff_t,
+        len: libc::size_t,
+        flags: libc::c_uint,
+    ) -> libc::c_long {
+        libc::syscall(libc::SYS_copy_file_range, fd_in, off_in, fd_out, off_out, len, flags)
+    }
+
+    let has_copy_file_range = HAS_COPY_FILE_RANGE.load(Ordering::Relaxed);
+    let mut written = 0u64;
+    while written < max_len {
+        let copy_result = if has_copy_file_range {
+            // Copy at most 1 GiB at a time, so a `max_len` of `u64::max_value()` doesn't make the
+            // syscall fail with `EOVERFLOW` when the files are not at offset 0.
+            let bytes_to_copy = min(max_len - written, 0x4000_0000) as usize;
+            let copy_result = unsafe {
+                // We actually don't have to adjust the offsets,
+                // because copy_file_range adjusts the file offset automatically
+                cvt(copy_file_range(
+                    reader,
+                    ptr::null_mut(),
+                    writer,
+                    ptr::null_mut(),
+                    bytes_to_copy,
+                    0,
+                ))
+            };
+            if let Err(ref copy_err) = copy_result {
+                // EOPNOTSUPP only means that the filesystems of these files don't support it, so
+                // it doesn't disable copy_file_range for other files.
+                match copy_err.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+                    }
+                    _ => {}
+                }
+            }
+            copy_result
+        } else {
+            Err(io::Error::from_raw_os_error(libc::ENOSYS))
+        };
+        match copy_result {
+            Ok(0) if written == 0 => {
+                // Some kernels report success without copying anything instead of an error, e.g.
+                // for procfs files, so try another method in case the input isn't actually empty.
+                return CopyResult::Fallback(0);
+            }
+            Ok(0) => return CopyResult::Ended(written), // reached EOF
+            Ok(ret) => written += ret as u64,
+            Err(err) => {
+                return match err.raw_os_error() {
+                    // when file offset + max_length > u64::MAX
+                    Some(libc::EOVERFLOW) => CopyResult::Fallback(written),
+                    Some(libc::ENOSYS)
+                    | Some(libc::EXDEV)
+                    | Some(libc::EINVAL)
+                    | Some(libc::EPERM)
+                    | Some(libc::EOPNOTSUPP)
+                    | Some(libc::EBADF) => {
+                        // Try fallback io::copy if either:
+                        // - Kernel version is < 4.5 (ENOSYS)
+                        // - Files are mounted on different fs (EXDEV)
+                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
+                        // - copy_file_range cannot be used with pipes or device nodes (EINVAL)
+                        // - copy_file_range is not supported by the filesystem (EOPNOTSUPP)
+                        // - the writer was opened with O_APPEND (EBADF)
+                        assert_eq!(written, 0);
+                        CopyResult::Fallback(0)
+                    }
+                    _ => CopyResult::Error(err, written),
+                };
+            }
+        }
+    }
+    CopyResult::Ended(written)
+}
+
+#[derive(PartialEq)]
+enum SpliceMode {
+    Sendfile,
+    Splice,
+}
+
+/// Copies up to `len` bytes with `sendfile` or `splice`. Callers must fall back to another method
+/// on `Fallback`.
+fn sendfile_splice(mode: SpliceMode, reader: RawFd, writer: RawFd, len: u64) -> CopyResult {
+    static HAS_SENDFILE: AtomicBool = AtomicBool::new(true);
+    static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
+
+    unsafe fn splice(
+        fd_in: libc::c_int,
+        off_in: *mut libc::loff_t,
+        fd_out: libc::c_int,
+        off_out: *mut libc::loff_t,
+        len: libc::size_t,
+        flags: libc::c_uint,
+    ) -> libc::c_long {
+        libc::syscall(libc::SYS_splice, fd_in, off_in, fd_out, off_out, len, flags)
+    }
+
+    match mode {
+        SpliceMode::Sendfile if !HAS_SENDFILE.load(Ordering::Relaxed) => {
+            return CopyResult::Fallback(0);
+        }
+        SpliceMode::Splice if !HAS_SPLICE.load(Ordering::Relaxed) => {
+            return CopyResult::Fallback(0);
+        }
+        _ => (),
+    }
+
+    let mut written = 0u64;
+    while written < len {
+        // according to its manpage that's the maximum size sendfile() will copy per invocation
+        let chunk_size = min(len - written, 0x7fff_f000) as usize;
+
+        let result = match mode {
+            SpliceMode::Sendfile => {
+                cvt(unsafe { libc::sendfile(writer, reader, ptr::null_mut(), chunk_size) })
+                    .map(|n| n as u64)
+            }
+            SpliceMode::Splice => cvt(unsafe {
+                splice(reader, ptr::null_mut(), writer, ptr::null_mut(), chunk_size, 0)
+            })
+            .map(|n| n as u64),
+        };
+
+        match result {
+            Ok(0) => break, // EOF
+            Ok(ret) => written += ret,
+            Err(err) => {
+                return match err.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        // syscall not supported (ENOSYS)
+                        // syscall is disallowed, e.g. by seccomp (EPERM)
+                        match mode {
+                            SpliceMode::Sendfile => HAS_SENDFILE.store(false, Ordering::Relaxed),
+                            SpliceMode::Splice => HAS_SPLICE.store(false, Ordering::Relaxed),
+                        }
+                        assert_eq!(written, 0);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(libc::EINVAL) => {
+                        // splice/sendfile do not support this particular file descriptor (EINVAL)
+                        assert_eq!(written, 0);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(os_err) if mode == SpliceMode::Sendfile && os_err == libc::EOVERFLOW => {
+                        CopyResult::Fallback(written)
+                    }
+                    _ => CopyResult::Error(err, written),
+                };
+            }
+        }
+    }
+    CopyResult::Ended(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fs::{File, OpenOptions};
+    use crate::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+    use crate::os::unix::net::UnixStream;
+    use crate::sys_common::io::test::tmpdir;
+    use crate::thread;
+
+    fn create_file(path: &crate::path::Path, len: usize) -> Vec<u8> {
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        File::create(path).unwrap().write_all(&data).unwrap();
+        data
+    }
+
+    #[test]
+    fn copy_file_to_file() {
+        let dir = tmpdir();
+        let source = dir.join("source");
+        let sink = dir.join("sink");
+        let data = create_file(&source, 100_000);
+
+        let mut reader = File::open(&source).unwrap();
+        let mut writer = File::create(&sink).unwrap();
+        assert_eq!(io::copy(&mut reader, &mut writer).unwrap(), data.len() as u64);
+        assert_eq!(crate::fs::read(&sink).unwrap(), data);
+    }
+
+    #[test]
+    fn copy_through_buffers_and_take() {
+        let dir = tmpdir();
+        let source = dir.join("source");
+        let sink = dir.join("sink");
+        let data = create_file(&source, 100_000);
+
+        let mut reader = BufReader::with_capacity(1024, File::open(&source).unwrap());
+        // fill the read buffer, so data must be drained from it first
+        assert_eq!(reader.fill_buf().unwrap().len(), 1024);
+        reader.consume(10);
+        let mut reader = reader.take(50_000);
+
+        let mut writer = BufWriter::new(
+            OpenOptions::new().read(true).write(true).create(true).open(&sink).unwrap(),
+        );
+        // leave some data in the write buffer, which must be written first
+        writer.write_all(b"header").unwrap();
+
+        assert_eq!(io::copy(&mut reader, &mut writer).unwrap(), 50_000);
+        assert_eq!(reader.limit(), 0);
+        // the input must be positioned right after the copied data
+        let mut next = [0u8];
+        reader.get_mut().get_mut().read_exact(&mut next).unwrap();
+        assert_eq!(next[0], data[50_010]);
+
+        let mut writer = writer.into_inner().unwrap();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        let mut written = Vec::new();
+        writer.read_to_end(&mut written).unwrap();
+        assert_eq!(&written[..6], b"header");
+        assert_eq!(&written[6..], &data[10..50_010]);
+    }
+
+    #[test]
+    fn copy_file_to_socket() {
+        let dir = tmpdir();
+        let source = dir.join("source");
+        let data = create_file(&source, 100_000);
+
+        let (mut tx, mut rx) = UnixStream::pair().unwrap();
+        let receiver = thread::spawn(move || {
+            let mut received = Vec::new();
+            rx.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let mut reader = File::open(&source).unwrap();
+        assert_eq!(io::copy(&mut reader, &mut tx).unwrap(), data.len() as u64);
+        drop(tx);
+        assert_eq!(receiver.join().unwrap(), data);
+    }
+
+    #[test]
+    fn copy_socket_to_file() {
+        let dir = tmpdir();
+        let sink = dir.join("sink");
+        let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+
+        let (mut tx, mut rx) = UnixStream::pair().unwrap();
+        let sent = data.clone();
+        let sender = thread::spawn(move || tx.write_all(&sent).unwrap());
+
+        let mut writer = File::create(&sink).unwrap();
+        assert_eq!(io::copy(&mut rx, &mut writer).unwrap(), data.len() as u64);
+        sender.join().unwrap();
+        assert_eq!(crate::fs::read(&sink).unwrap(), data);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::cell::UnsafeCell;
+use crate::sync::atomic::{
+    spin_loop_hint, AtomicU32, AtomicUsize,
+    Ordering::{Acquire, Relaxed, Release},
+};
+use crate::sys::futex::{futex_wait, futex_wake};
+
+pub type MovableMutex = Mutex;
+
+// A futex-based mutex doesn't need to be at a stable address until it is
+// locked, and it never allocates, so it can be moved freely while unlocked
+// and is usable in statics.
+//
+// The futex is 0 when the mutex is unlocked, 1 when it is locked and 2 when it
+// is locked and other threads may be waiting for it. Only unlocking a mutex in
+// the latter state wakes up a waiting thread, so an uncontended mutex is
+// locked and unlocked without any syscall.
+pub struct Mutex {
+    futex: AtomicU32,
+}
+
+impl Mutex {
+    pub const fn new() -> Mutex {
+        Mutex { futex: AtomicU32::new(0) }
+    }
+
+    #[inline]
+    pub unsafe fn init(&mut self) {}
+
+    #[inline]
+    pub unsafe fn destroy(&self) {}
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        self.futex.compare_exchange(0, 1, Acquire, Relaxed).is_ok()
+    }
+
+    #[inline]
+    pub unsafe fn lock(&self) {
+        if self.futex.compare_exchange(0, 1, Acquire, Relaxed).is_err() {
+            self.lock_contended();
+        }
+    }
+
+    #[cold]
+    fn lock_contended(&self) {
+        // Spin first to speed things up if the lock is released quickly.
+        let mut state = self.spin();
+
+        // If it's unlocked now, attempt to take the lock without marking it as
+        // contended.
+        if state == 0 {
+            match self.futex.compare_exchange(0, 1, Acquire, Relaxed) {
+                Ok(_) => return, // Locked!
+                Err(s) => state = s,
+            }
+        }
+
+        loop {
+            // Put the lock in contended state. We avoid an unnecessary write if
+            // it is already set to 2, to be friendlier for the caches.
+            if state != 2 && self.futex.swap(2, Acquire) == 0 {
+                // We changed it from 0 to 2, so we just successfully locked it.
+                return;
+            }
+
+            // Wait for the futex to change state, assuming it is still 2.
+            futex_wait(&self.futex, 2, None);
+
+            // Spin again after waking up.
+            state = self.spin();
+        }
+    }
+
+    fn spin(&self) -> u32 {
+        let mut spin = 100;
+        loop {
+            // We only use `load` (and not `swap` or `compare_exchange`) while
+            // spinning, to be easier on the caches.
+            let state = self.futex.load(Relaxed);
+
+            // We stop spinning when the mutex is unlocked (0), but also when
+            // it's contended (2), as other threads are already waiting then.
+            if state != 1 || spin == 0 {
+                return state;
+            }
+
+            spin_loop_hint();
+            spin -= 1;
+        }
+    }
+
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        if self.futex.swap(0, Release) == 2 {
+            // We only wake up one thread. When that thread locks the mutex, it
+            // marks the mutex as contended (2) again (see `lock_contended`),
+            // which makes sure that the other waiting threads are woken up
+            // eventually too.
+            self.wake();
+        }
+    }
+
+    #[cold]
+    fn wake(&self) {
+        futex_wake(&self.futex);
+    }
+}
+
+pub struct ReentrantMutex {
+    mutex: Mutex,
+    owner: AtomicUsize,
+    lock_count: UnsafeCell<u32>,
+}
+
+unsafe impl Send for ReentrantMutex {}
+unsafe impl Sync for ReentrantMutex {}
+
+// A reentrant mutex locks the futex-based mutex above the first time a thread
+// locks it, and then records the thread as its owner. Further locks by the
+// owner only increment a counter, which is only accessed by the owner. A
+// thread that doesn't own the mutex can't see its own id in `owner`, so the
+// relaxed loads of `owner` are enough to tell whether it is the owner.
+impl ReentrantMutex {
+    pub unsafe fn uninitialized() -> ReentrantMutex {
+        ReentrantMutex {
+            mutex: Mutex::new(),
+            owner: AtomicUsize::new(0),
+            lock_count: UnsafeCell::new(0),
+        }
+    }
+
+    #[inline]
+    pub unsafe fn init(&mut self) {}
+
+    pub unsafe fn lock(&self) {
+        let this_thread = current_thread();
+        if self.owner.load(Relaxed) == this_thread {
+            self.increment_lock_count();
+        } else {
+            self.mutex.lock();
+            self.owner.store(this_thread, Relaxed);
+            debug_assert_eq!(*self.lock_count.get(), 0);
+            *self.lock_count.get() = 1;
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        let this_thread = current_thread();
+        if self.owner.load(Relaxed) == this_thread {
+            self.increment_lock_count();
+            true
+        } else if self.mutex.try_lock() {
+            self.owner.store(this_thread, Relaxed);
+            debug_assert_eq!(*self.lock_count.get(), 0);
+            *self.lock_count.get() = 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub unsafe fn unlock(&self) {
+        *self.lock_count.get() -= 1;
+        if *self.lock_count.get() == 0 {
+            self.owner.store(0, Relaxed);
+            self.mutex.unlock();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn destroy(&self) {}
+
+    unsafe fn increment_lock_count(&self) {
+        *self.lock_count.get() = (*self.lock_count.get())
+            .checked_add(1)
+            .expect("lock count overflow in reentrant mutex");
+    }
+}
+
+/// Returns an id of the current thread, which is never 0 and unique among the
+/// running threads.
+#[inline]
+fn current_thread() -> usize {
+    unsafe { libc::pthread_self() as usize }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mutex, ReentrantMutex};
+    use crate::cell::UnsafeCell;
+    use crate::sync::atomic::Ordering::Relaxed;
+    use crate::sync::Arc;
+    use crate::thread;
+
+    struct Counter<M> {
+        mutex: M,
+        count: UnsafeCell<usize>,
+    }
+
+    unsafe impl<M: Sync> Sync for Counter<M> {}
+
+    const THREADS: usize = 8;
+    const ITERS: usize = 10_000;
+
+    #[test]
+    fn contended() {
+        let counter = Arc::new(Counter { mutex: Mutex::new(), count: UnsafeCell::new(0) });
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITERS {
+                        unsafe {
+                            counter.mutex.lock();
+                            *counter.count.get() += 1;
+                            counter.mutex.unlock();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(unsafe { *counter.count.get() }, THREADS * ITERS);
+        // The last unlock leaves the mutex unlocked and uncontended.
+        assert_eq!(counter.mutex.futex.load(Relaxed), 0);
+    }
+
+    #[test]
+    fn try_lock_contended() {
+        let counter = Arc::new(Counter { mutex: Mutex::new(), count: UnsafeCell::new(0) });
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    let mut locked = 0;
+                    while locked < ITERS {
+                        unsafe {
+                            if counter.mutex.try_lock() {
+                                *counter.count.get() += 1;
+                                counter.mutex.unlock();
+                                locked += 1;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(unsafe { *counter.count.get() }, THREADS * ITERS);
+    }
+
+    #[test]
+    fn reentrant_contended() {
+        let counter = Arc::new(Counter {
+            mutex: unsafe { ReentrantMutex::uninitialized() },
+            count: UnsafeCell::new(0),
+        });
+        let threads: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let counter = counter.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITERS {
+                        unsafe {
+                            counter.mutex.lock();
+                            counter.mutex.lock();
+                            *counter.count.get() += 1;
+                            counter.mutex.unlock();
+                            *counter.count.get() += 1;
+                            counter.mutex.unlock();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(unsafe { *counter.count.get() }, 2 * THREADS * ITERS);
+    }
+}
@@ -1,12 +1,46 @@
+use crate::cmp;
 use crate::fmt;
 use crate::io::{self, Error, ErrorKind};
 use crate::ptr;
 use crate::sys;
 use crate::sys::cvt;
 use crate::sys::process::process_common::*;
+use crate::thread;
+use crate::time::{Duration, Instant};
 
 use libc::{c_int, gid_t, pid_t, uid_t};
 
+#[cfg(target_os = "linux")]
+use crate::os::linux::process::PidFd;
+#[cfg(target_os = "linux")]
+use crate::sys_common::AsInner;
+
+// Syscall numbers that the `libc` crate we depend on doesn't define yet. The
+// syscalls added since Linux 5.1 have the same number on every architecture,
+// but some ABIs add an offset to all their syscall numbers: 4000 for MIPS o32,
+// 5000 for MIPS n64, 6000 for MIPS n32 and `__X32_SYSCALL_BIT` for x32.
+#[cfg(all(target_os = "linux", target_arch = "mips"))]
+const SYSCALL_BASE: libc::c_long = 4000;
+#[cfg(all(target_os = "linux", target_arch = "mips64", target_pointer_width = "64"))]
+const SYSCALL_BASE: libc::c_long = 5000;
+#[cfg(all(target_os = "linux", target_arch = "mips64", target_pointer_width = "32"))]
+const SYSCALL_BASE: libc::c_long = 6000;
+#[cfg(all(target_os = "linux", target_arch = "x86_64", target_pointer_width = "32"))]
+const SYSCALL_BASE: libc::c_long = 0x4000_0000;
+#[cfg(all(
+    target_os = "linux",
+    not(any(
+        target_arch = "mips",
+        target_arch = "mips64",
+        all(target_arch = "x86_64", target_pointer_width = "32")
+    ))
+))]
+const SYSCALL_BASE: libc::c_long = 0;
+#[cfg(target_os = "linux")]
+const SYS_PIDFD_SEND_SIGNAL: libc::c_long = SYSCALL_BASE + 424;
+#[cfg(target_os = "linux")]
+const SYS_CLONE3: libc::c_long = SYSCALL_BASE + 435;
+
 ////////////////////////////////////////////////////////////////////////////////
 // Command
 ////////////////////////////////////////////////////////////////////////////////
@@ -41,9 +75,9 @@ impl Command {
         // Note that as soon as we're done with the fork there's no need to hold
         // a lock any more because the parent won't do anything and the child is
         // in its own process.
-        let result = unsafe {
+        let (result, pidfd) = unsafe {
             let _env_lock = sys::os::env_lock();
-            cvt(libc::fork())?
+            self.do_fork()?
         };
 
         let pid = unsafe {
@@ -72,7 +106,7 @@ impl Command {
             }
         };
 
-        let mut p = Process { pid: pid, status: None };
+        let mut p = unsafe { Process::new(pid, pidfd) };
         drop(output);
         let mut bytes = [0; 8];
 
@@ -113,6 +147,78 @@ impl Command {
         }
     }
 
+    // Attempts to fork the process. If successful, returns a pid of 0 in the
+    // child, and `(child_pid, -1)` or `(child_pid, child_pidfd)` in the parent.
+    #[cfg(not(target_os = "linux"))]
+    unsafe fn do_fork(&mut self) -> io::Result<(pid_t, c_int)> {
+        cvt(libc::fork()).map(|pid| (pid, -1))
+    }
+
+    // When a pidfd was requested, the child is created with `clone3` and
+    // `CLONE_PIDFD` so the pidfd refers to it from the start, before anyone
+    // could reap it. If `clone3` is unavailable (Linux < 5.3, or filtered out
+    // by seccomp), the child is forked without a pidfd. Opening one afterwards
+    // with `pidfd_open` isn't safe: the child may already have been reaped by
+    // another thread calling `waitpid(-1)` or because `SIGCHLD` is ignored, and
+    // its pid reused by an unrelated process.
+    #[cfg(target_os = "linux")]
+    unsafe fn do_fork(&mut self) -> io::Result<(pid_t, c_int)> {
+        use crate::sync::atomic::{AtomicBool, Ordering};
+
+        static HAS_CLONE3: AtomicBool = AtomicBool::new(true);
+
+        // Not yet defined by the `libc` crate we depend on.
+        const CLONE_PIDFD: u64 = 0x1000;
+
+        #[repr(C)]
+        struct clone_args {
+            flags: u64,
+            pidfd: u64,
+            child_tid: u64,
+            parent_tid: u64,
+            exit_signal: u64,
+            stack: u64,
+            stack_size: u64,
+            tls: u64,
+        }
+
+        if !self.get_create_pidfd() {
+            return cvt(libc::fork()).map(|pid| (pid, -1));
+        }
+
+        if HAS_CLONE3.load(Ordering::Relaxed) {
+            let mut pidfd: c_int = -1;
+            let mut args = clone_args {
+                flags: CLONE_PIDFD,
+                pidfd: &mut pidfd as *mut c_int as u64,
+                child_tid: 0,
+                parent_tid: 0,
+                exit_signal: libc::SIGCHLD as u64,
+                stack: 0,
+                stack_size: 0,
+                tls: 0,
+            };
+            let r = libc::syscall(
+                SYS_CLONE3,
+                &mut args as *mut clone_args,
+                crate::mem::size_of::<clone_args>(),
+            );
+            match cvt(r) {
+                Ok(pid) => return Ok((pid as pid_t, pidfd)),
+                Err(e) => match e.raw_os_error() {
+                    // Several threads may race to store this, which is fine:
+                    // they all just tried the same syscall and failed.
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        HAS_CLONE3.store(false, Ordering::Relaxed)
+                    }
+                    _ => return Err(e),
+                },
+            }
+        }
+
+        cvt(libc::fork()).map(|pid| (pid, -1))
+    }
+
     pub fn exec(&mut self, default: Stdio) -> io::Error {
         let envp = self.capture_env();
 
@@ -287,6 +393,7 @@ impl Command {
             || self.get_uid().is_some()
             || self.env_saw_path()
             || !self.get_closures().is_empty()
+            || self.get_create_pidfd()
         {
             return Ok(None);
         }
@@ -321,7 +428,7 @@ impl Command {
             None => None,
         };
 
-        let mut p = Process { pid: 0, status: None };
+        let mut p = unsafe { Process::new(0, -1) };
 
         struct PosixSpawnFileActions(MaybeUninit<libc::posix_spawn_file_actions_t>);
 
@@ -408,9 +515,29 @@ impl Command {
 pub struct Process {
     pid: pid_t,
     status: Option<ExitStatus>,
+    // The pidfd of this child, if one was requested through
+    // `CommandExt::create_pidfd` and the kernel supports it.
+    #[cfg(target_os = "linux")]
+    pidfd: Option<PidFd>,
 }
 
 impl Process {
+    // `pidfd` is -1 if there is none; otherwise it must be a valid pidfd that
+    // nothing else owns.
+    #[cfg(target_os = "linux")]
+    unsafe fn new(pid: pid_t, pidfd: c_int) -> Self {
+        use crate::sys::fd::FileDesc;
+        use crate::sys_common::FromInner;
+
+        let pidfd = if pidfd >= 0 { Some(PidFd::from_inner(FileDesc::new(pidfd))) } else { None };
+        Process { pid, status: None, pidfd }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    unsafe fn new(pid: pid_t, _pidfd: c_int) -> Self {
+        Process { pid, status: None }
+    }
+
     pub fn id(&self) -> u32 {
         self.pid as u32
     }
@@ -425,10 +552,31 @@ impl Process {
                 "invalid argument: can't kill an exited process",
             ))
         } else {
-            cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(drop)
+            self.send_kill()
         }
     }
 
+    // With a pidfd, the signal can't end up at another process that reused
+    // the pid, even if the child was reaped behind our back.
+    #[cfg(target_os = "linux")]
+    fn send_kill(&self) -> io::Result<()> {
+        match self.pidfd {
+            Some(ref pidfd) => {
+                let fd = pidfd.as_inner().raw();
+                cvt(unsafe {
+                    libc::syscall(SYS_PIDFD_SEND_SIGNAL, fd, libc::SIGKILL, ptr::null::<u8>(), 0)
+                })
+                .map(drop)
+            }
+            None => cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(drop),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn send_kill(&self) -> io::Result<()> {
+        cvt(unsafe { libc::kill(self.pid, libc::SIGKILL) }).map(drop)
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::sys::cvt_r;
         if let Some(status) = self.status {
@@ -444,6 +592,9 @@ impl Process {
         if let Some(status) = self.status {
             return Ok(Some(status));
         }
+        if let Some(false) = self.pidfd_readable(Some(Duration::from_secs(0)))? {
+            return Ok(None);
+        }
         let mut status = 0 as c_int;
         let pid = cvt(unsafe { libc::waitpid(self.pid, &mut status, libc::WNOHANG) })?;
         if pid == 0 {
@@ -453,6 +604,89 @@ impl Process {
             Ok(Some(ExitStatus::new(status)))
         }
     }
+
+    /// Waits for the child to exit for at most `timeout`, returning `None` if
+    /// it is still running afterwards.
+    ///
+    /// With a pidfd this sleeps until the child exits. Without one, there is
+    /// nothing to block on, so it polls `try_wait` with increasing intervals.
+    #[allow(dead_code)]
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+        match self.pidfd_readable(Some(timeout))? {
+            Some(true) => return self.wait().map(Some),
+            Some(false) => return Ok(None),
+            None => {}
+        }
+
+        let deadline = Instant::now().checked_add(timeout);
+        let mut delay = Duration::from_millis(1);
+        loop {
+            if let Some(status) = self.try_wait()? {
+                return Ok(Some(status));
+            }
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if remaining > Duration::from_secs(0) => remaining,
+                    _ => return Ok(None),
+                },
+                None => delay,
+            };
+            thread::sleep(cmp::min(delay, remaining));
+            delay = cmp::min(delay * 2, Duration::from_millis(100));
+        }
+    }
+
+    // Waits up to `timeout` (forever if `None`) for the pidfd to become
+    // readable, which happens when the child exits. Returns `None` if this
+    // process has no pidfd.
+    #[cfg(target_os = "linux")]
+    fn pidfd_readable(&self, timeout: Option<Duration>) -> io::Result<Option<bool>> {
+        let fd = match self.pidfd {
+            Some(ref pidfd) => pidfd.as_inner().raw(),
+            None => return Ok(None),
+        };
+        let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+        loop {
+            // `poll` takes milliseconds as a `c_int`, so long timeouts are
+            // waited for in several rounds. Round up so we don't spin when
+            // less than a millisecond is left.
+            let ms = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    let partial_ms = remaining.subsec_nanos() % 1_000_000 != 0;
+                    let ms = remaining.as_millis() + partial_ms as u128;
+                    cmp::min(ms, c_int::max_value() as u128) as c_int
+                }
+                None => -1,
+            };
+            let mut pfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+            match cvt(unsafe { libc::poll(&mut pfd, 1, ms) }) {
+                Ok(0) if ms == 0 => return Ok(Some(false)),
+                Ok(0) => {}
+                Ok(_) => return Ok(Some(true)),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn pidfd_readable(&self, _timeout: Option<Duration>) -> io::Result<Option<bool>> {
+        Ok(None)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn pidfd(&self) -> Option<&PidFd> {
+        self.pidfd.as_ref()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn take_pidfd(&mut self) -> Option<PidFd> {
+        self.pidfd.take()
+    }
 }
 
 /// Unix exit statuses
@@ -0,0 +1,515 @@
+use crate::sync::atomic::{
+    spin_loop_hint, AtomicU32,
+    Ordering::{Acquire, Relaxed, Release},
+};
+use crate::sys::futex::{futex_wait, futex_wake, futex_wake_all};
+
+pub type MovableRWLock = RWLock;
+
+pub struct RWLock {
+    // The state consists of a 30-bit reader counter, a 'readers waiting' flag,
+    // and a 'writers waiting' flag.
+    // Bits 0..30:
+    //   0: Unlocked
+    //   1..=0x3FFF_FFFD: Locked by N readers
+    //   0x3FFF_FFFE: Unlocked, but reserved for a writer that was woken up
+    //   0x3FFF_FFFF: Write locked
+    // Bit 30: Readers are waiting on this futex.
+    // Bit 31: Writers are waiting on the writer_notify futex.
+    state: AtomicU32,
+    // The 'condition variable' to notify writers through.
+    // Incremented on every signal.
+    writer_notify: AtomicU32,
+}
+
+const READ_LOCKED: u32 = 1;
+const MASK: u32 = (1 << 30) - 1;
+const WRITE_LOCKED: u32 = MASK;
+const WRITE_HANDOFF: u32 = MASK - 1;
+const MAX_READERS: u32 = MASK - 2;
+const READERS_WAITING: u32 = 1 << 30;
+const WRITERS_WAITING: u32 = 1 << 31;
+
+fn is_unlocked(state: u32) -> bool {
+    state & MASK == 0
+}
+
+fn is_write_locked(state: u32) -> bool {
+    state & MASK == WRITE_LOCKED
+}
+
+fn is_handed_off(state: u32) -> bool {
+    state & MASK == WRITE_HANDOFF
+}
+
+fn has_readers_waiting(state: u32) -> bool {
+    state & READERS_WAITING != 0
+}
+
+fn has_writers_waiting(state: u32) -> bool {
+    state & WRITERS_WAITING != 0
+}
+
+fn is_read_lockable(state: u32) -> bool {
+    // This also returns false if the counter could overflow if we tried to read
+    // lock it, and while the lock is handed off to a writer.
+    //
+    // We don't allow read-locking if there's readers waiting, even if the lock
+    // is unlocked and there's no writers waiting. The only situation when this
+    // happens is after unlocking, at which point the unlocking thread might be
+    // waking up writers, which have priority over readers. The unlocking thread
+    // will clear the readers waiting bit and wake up readers, if necessary.
+    state & MASK < MAX_READERS && !has_readers_waiting(state) && !has_writers_waiting(state)
+}
+
+fn has_reached_max_readers(state: u32) -> bool {
+    state & MASK == MAX_READERS
+}
+
+// Writers are preferred over readers: new readers don't get the lock while a
+// writer is waiting, so a steady stream of readers can't starve the writers.
+//
+// Unlocking while writers are waiting hands the lock off to the writer that is
+// woken up: the lock is left in the `WRITE_HANDOFF` state, in which neither new
+// readers nor new writers can lock it, until a writer that was woken from its
+// `futex_wait` takes it. If it turns out no writer was asleep, the unlocking
+// thread takes the handoff back. A steady stream of writers can still starve
+// the readers.
+impl RWLock {
+    pub const fn new() -> RWLock {
+        RWLock { state: AtomicU32::new(0), writer_notify: AtomicU32::new(0) }
+    }
+
+    #[inline]
+    pub unsafe fn try_read(&self) -> bool {
+        let mut state = self.state.load(Relaxed);
+        while is_read_lockable(state) {
+            match self.state.compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed) {
+                Ok(_) => return true,
+                Err(s) => state = s,
+            }
+        }
+        false
+    }
+
+    #[inline]
+    pub unsafe fn read(&self) {
+        let state = self.state.load(Relaxed);
+        if !is_read_lockable(state)
+            || self
+                .state
+                .compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
+                .is_err()
+        {
+            self.read_contended();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn read_unlock(&self) {
+        let state = self.state.fetch_sub(READ_LOCKED, Release) - READ_LOCKED;
+
+        // It's impossible for a reader to be waiting on a read-locked RWLock,
+        // except if there is also a writer waiting.
+        debug_assert!(!has_readers_waiting(state) || has_writers_waiting(state));
+
+        // Wake up a writer if we were the last reader and there's a writer
+        // waiting.
+        if is_unlocked(state) && has_writers_waiting(state) {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    #[cold]
+    fn read_contended(&self) {
+        let mut state = self.spin_read();
+
+        loop {
+            // If we can lock it, lock it.
+            if is_read_lockable(state) {
+                match self.state.compare_exchange_weak(state, state + READ_LOCKED, Acquire, Relaxed)
+                {
+                    Ok(_) => return, // Locked!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // Check for overflow.
+            if has_reached_max_readers(state) {
+                panic!("too many active read locks on RwLock");
+            }
+
+            // Make sure the readers waiting bit is set before we go to sleep.
+            if !has_readers_waiting(state) {
+                if let Err(s) =
+                    self.state.compare_exchange(state, state | READERS_WAITING, Relaxed, Relaxed)
+                {
+                    state = s;
+                    continue;
+                }
+            }
+
+            // Wait for the state to change.
+            futex_wait(&self.state, state | READERS_WAITING, None);
+
+            // Spin again after waking up.
+            state = self.spin_read();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_write(&self) -> bool {
+        let mut state = self.state.load(Relaxed);
+        while is_unlocked(state) {
+            match self.state.compare_exchange_weak(state, state + WRITE_LOCKED, Acquire, Relaxed) {
+                Ok(_) => return true,
+                Err(s) => state = s,
+            }
+        }
+        false
+    }
+
+    #[inline]
+    pub unsafe fn write(&self) {
+        if self.state.compare_exchange_weak(0, WRITE_LOCKED, Acquire, Relaxed).is_err() {
+            self.write_contended();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn write_unlock(&self) {
+        let state = self.state.fetch_sub(WRITE_LOCKED, Release) - WRITE_LOCKED;
+
+        debug_assert!(is_unlocked(state));
+
+        if has_writers_waiting(state) || has_readers_waiting(state) {
+            self.wake_writer_or_readers(state);
+        }
+    }
+
+    #[cold]
+    fn write_contended(&self) {
+        let mut state = self.spin_write();
+
+        let mut other_writers_waiting = 0;
+
+        // Whether we have been woken up from `futex_wait`, which entitles us
+        // to take the lock when it is handed off to a writer.
+        let mut woken = false;
+
+        loop {
+            // If it was handed off to a woken writer, and we are one, take it.
+            if woken && is_handed_off(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    (state & !MASK) | WRITE_LOCKED | other_writers_waiting,
+                    Acquire,
+                    Relaxed,
+                ) {
+                    Ok(_) => return, // Locked!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // If it's unlocked, we try to lock it.
+            if is_unlocked(state) {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state | WRITE_LOCKED | other_writers_waiting,
+                    Acquire,
+                    Relaxed,
+                ) {
+                    Ok(_) => return, // Locked!
+                    Err(s) => {
+                        state = s;
+                        continue;
+                    }
+                }
+            }
+
+            // Set the waiting bit indicating that we're waiting on it.
+            if !has_writers_waiting(state) {
+                if let Err(s) =
+                    self.state.compare_exchange(state, state | WRITERS_WAITING, Relaxed, Relaxed)
+                {
+                    state = s;
+                    continue;
+                }
+            }
+
+            // Other writers might be waiting now too, so we should make sure
+            // we keep that bit on once we manage lock it.
+            other_writers_waiting = WRITERS_WAITING;
+
+            // Examine the notification counter before we check if `state` has
+            // changed, to make sure we don't miss any notifications.
+            let seq = self.writer_notify.load(Acquire);
+
+            // Don't go to sleep if the lock has become available, or if the
+            // writers waiting bit is no longer set.
+            state = self.state.load(Relaxed);
+            if is_unlocked(state) || !has_writers_waiting(state) {
+                continue;
+            }
+
+            // Wait for the state to change.
+            futex_wait(&self.writer_notify, seq, None);
+            woken = true;
+
+            // Spin again after waking up.
+            state = self.spin_write();
+        }
+    }
+
+    /// Wakes up waiting threads after unlocking.
+    ///
+    /// If both are waiting, this will hand the lock off to one writer and wake
+    /// it up, but will fall back to waking up readers if there was no writer
+    /// to wake up.
+    #[cold]
+    fn wake_writer_or_readers(&self, mut state: u32) {
+        assert!(is_unlocked(state));
+
+        // The readers waiting bit might be turned on at any point now, since
+        // readers will block when there's anything waiting. Writers that have
+        // not gone to sleep yet might lock the lock or turn on the writers
+        // waiting bit.
+        //
+        // If the lock gets locked in the meantime, we don't have to do
+        // anything, because then the thread that locked the lock will take
+        // care of waking up waiters when it unlocks.
+        loop {
+            // If writers are waiting, hand the lock off to one of them. This
+            // clears the writers waiting bit; the writer that takes the lock
+            // sets it again, since more writers might be waiting.
+            if has_writers_waiting(state) {
+                let handoff = (state & !MASK & !WRITERS_WAITING) | WRITE_HANDOFF;
+                if let Err(s) = self.state.compare_exchange(state, handoff, Relaxed, Relaxed) {
+                    if !is_unlocked(s) {
+                        // The lock got locked. Not our problem anymore.
+                        return;
+                    }
+                    state = s;
+                    continue;
+                }
+                if self.wake_writer() {
+                    return;
+                }
+                // No writers were actually blocked on `futex_wait`, so nobody
+                // might take the handoff. Take it back, unless a writer that
+                // was about to go to sleep has already taken it.
+                match self.revoke_handoff() {
+                    Some(s) => {
+                        state = s;
+                        continue;
+                    }
+                    None => return,
+                }
+            }
+
+            // If only readers are waiting, wake them all up.
+            if state == READERS_WAITING {
+                match self.state.compare_exchange(state, 0, Relaxed, Relaxed) {
+                    Ok(_) => futex_wake_all(&self.state),
+                    Err(s) if is_unlocked(s) => {
+                        state = s;
+                        continue;
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            return;
+        }
+    }
+
+    /// Puts a handed off lock back in the unlocked state, and returns the new
+    /// state. Returns `None` if a writer took the lock already.
+    fn revoke_handoff(&self) -> Option<u32> {
+        let mut state = self.state.load(Relaxed);
+        while is_handed_off(state) {
+            match self.state.compare_exchange(state, state & !MASK, Relaxed, Relaxed) {
+                Ok(_) => return Some(state & !MASK),
+                Err(s) => state = s,
+            }
+        }
+        None
+    }
+
+    /// Wakes up one writer and returns true if it was blocked on `futex_wait`.
+    ///
+    /// If this returns false, it might still be the case that we notified a
+    /// writer that was about to go to sleep.
+    fn wake_writer(&self) -> bool {
+        self.writer_notify.fetch_add(1, Release);
+        futex_wake(&self.writer_notify)
+    }
+
+    /// Spins for a while, but stops directly at the given condition.
+    fn spin_until(&self, f: impl Fn(u32) -> bool) -> u32 {
+        let mut spin = 100;
+        loop {
+            let state = self.state.load(Relaxed);
+            if f(state) || spin == 0 {
+                return state;
+            }
+            spin_loop_hint();
+            spin -= 1;
+        }
+    }
+
+    fn spin_write(&self) -> u32 {
+        // Stop spinning when it's unlocked or when there's waiting writers, to
+        // keep things somewhat fair.
+        self.spin_until(|state| is_unlocked(state) || has_writers_waiting(state))
+    }
+
+    fn spin_read(&self) -> u32 {
+        // Stop spinning when it's unlocked or read locked, or when there's
+        // waiting threads.
+        self.spin_until(|state| {
+            !is_write_locked(state) || has_readers_waiting(state) || has_writers_waiting(state)
+        })
+    }
+
+    #[inline]
+    pub unsafe fn destroy(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RWLock;
+    use crate::sync::atomic::{
+        AtomicBool, AtomicUsize,
+        Ordering::{Relaxed, SeqCst},
+    };
+    use crate::sync::{mpsc, Arc};
+    use crate::thread;
+    use crate::time::Duration;
+
+    /// A lock along with the number of threads holding it for reading and for writing.
+    struct Checked {
+        lock: RWLock,
+        readers: AtomicUsize,
+        writers: AtomicUsize,
+    }
+
+    #[test]
+    fn contended() {
+        const THREADS: usize = 8;
+        const ITERS: usize = 2_000;
+
+        let checked = Arc::new(Checked {
+            lock: RWLock::new(),
+            readers: AtomicUsize::new(0),
+            writers: AtomicUsize::new(0),
+        });
+        let threads: Vec<_> = (0..THREADS)
+            .map(|i| {
+                let checked = checked.clone();
+                thread::spawn(move || {
+                    for j in 0..ITERS {
+                        // Every thread writes every few iterations, at different times.
+                        if (i + j) % 4 == 0 {
+                            unsafe { checked.lock.write() };
+                            assert_eq!(checked.writers.fetch_add(1, SeqCst), 0);
+                            assert_eq!(checked.readers.load(SeqCst), 0);
+                            checked.writers.fetch_sub(1, SeqCst);
+                            unsafe { checked.lock.write_unlock() };
+                        } else {
+                            unsafe { checked.lock.read() };
+                            checked.readers.fetch_add(1, SeqCst);
+                            assert_eq!(checked.writers.load(SeqCst), 0);
+                            checked.readers.fetch_sub(1, SeqCst);
+                            unsafe { checked.lock.read_unlock() };
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        // All the waiters were woken up, and the waiting bits cleared.
+        assert_eq!(checked.lock.state.load(Relaxed), 0);
+    }
+
+    #[test]
+    fn handoff_to_waiting_writer() {
+        let lock = Arc::new(RWLock::new());
+        unsafe { lock.read() };
+
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || unsafe {
+                lock.write();
+                lock.write_unlock();
+            })
+        };
+        while lock.state.load(Relaxed) & super::WRITERS_WAITING == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        // Give the writer time to go to sleep, so unlocking hands the lock off to it.
+        thread::sleep(Duration::from_millis(100));
+
+        // Once the reader unlocks, the lock belongs to the waiting writer: no one else gets it
+        // until that writer has locked it.
+        unsafe {
+            lock.read_unlock();
+            assert!(!lock.try_write());
+            assert!(!lock.try_read());
+        }
+
+        writer.join().unwrap();
+        assert_eq!(lock.state.load(Relaxed), 0);
+    }
+
+    #[test]
+    fn writer_not_starved_by_readers() {
+        let lock = Arc::new(RWLock::new());
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Overlapping readers keep the lock read-locked all the time, unless new readers stop
+        // getting it.
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let lock = lock.clone();
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    while !stop.load(Relaxed) {
+                        unsafe {
+                            lock.read();
+                            thread::sleep(Duration::from_millis(1));
+                            lock.read_unlock();
+                        }
+                    }
+                })
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(50));
+
+        let (tx, rx) = mpsc::channel();
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || unsafe {
+                lock.write();
+                lock.write_unlock();
+                tx.send(()).unwrap();
+            })
+        };
+        let acquired = rx.recv_timeout(Duration::from_secs(10)).is_ok();
+
+        stop.store(true, Relaxed);
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert!(acquired, "the writer was starved by the readers");
+    }
+}
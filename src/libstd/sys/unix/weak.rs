@@ -25,7 +25,13 @@ macro_rules! weak {
     (fn $name:ident($($t:ty),*) -> $ret:ty) => (
         static $name: crate::sys::weak::Weak<unsafe extern fn($($t),*) -> $ret> =
             crate::sys::weak::Weak::new(concat!(stringify!($name), '\0'));
-    )
+    );
+    // For symbols whose name differs from the function's, like the `$INODE64`
+    // variants on macOS.
+    (fn $name:ident($($t:ty),*) -> $ret:ty, $sym:literal) => (
+        static $name: crate::sys::weak::Weak<unsafe extern fn($($t),*) -> $ret> =
+            crate::sys::weak::Weak::new(concat!($sym, '\0'));
+    );
 }
 
 pub struct Weak<F> {
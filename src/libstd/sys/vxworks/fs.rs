@@ -14,6 +14,9 @@ use crate::sys::{cvt, cvt_r};
 use crate::sys_common::{AsInner, FromInner};
 use libc::{self, c_int, mode_t, off_t, stat64};
 use libc::{dirent, ftruncate, lseek, open, readdir_r as readdir64_r};
+
+pub use crate::sys_common::fs::DirHandle;
+
 pub struct File(FileDesc);
 
 #[derive(Clone)]
@@ -15,6 +15,8 @@ use crate::sys_common::FromInner;
 
 use super::to_u16s;
 
+pub use crate::sys_common::fs::DirHandle;
+
 pub struct File {
     handle: Handle,
 }
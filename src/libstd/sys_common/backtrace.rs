@@ -184,7 +184,22 @@ pub fn output_filename(
     print_fmt: PrintFmt,
     cwd: Option<&PathBuf>,
 ) -> fmt::Result {
-    let file: Cow<'_, Path> = match bows {
+    let file = filename_to_path(bows);
+    if print_fmt == PrintFmt::Short && file.is_absolute() {
+        if let Some(cwd) = cwd {
+            if let Ok(stripped) = file.strip_prefix(&cwd) {
+                if let Some(s) = stripped.to_str() {
+                    return write!(fmt, ".{}{}", path::MAIN_SEPARATOR, s);
+                }
+            }
+        }
+    }
+    fmt::Display::fmt(&file.display(), fmt)
+}
+
+/// Converts a filename reported by the symbolizer into a path.
+pub fn filename_to_path(bows: BytesOrWideString<'_>) -> Cow<'_, Path> {
+    match bows {
         #[cfg(unix)]
         BytesOrWideString::Bytes(bytes) => {
             use crate::os::unix::prelude::*;
@@ -201,15 +216,5 @@ pub fn output_filename(
         }
         #[cfg(not(windows))]
         BytesOrWideString::Wide(_wide) => Path::new("<unknown>").into(),
-    };
-    if print_fmt == PrintFmt::Short && file.is_absolute() {
-        if let Some(cwd) = cwd {
-            if let Ok(stripped) = file.strip_prefix(&cwd) {
-                if let Some(s) = stripped.to_str() {
-                    return write!(fmt, ".{}{}", path::MAIN_SEPARATOR, s);
-                }
-            }
-        }
     }
-    fmt::Display::fmt(&file.display(), fmt)
 }
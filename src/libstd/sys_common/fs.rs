@@ -2,7 +2,8 @@
 
 use crate::fs;
 use crate::io::{self, Error, ErrorKind};
-use crate::path::Path;
+use crate::path::{Path, PathBuf};
+use crate::sys::fs as fs_imp;
 
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     if !from.is_file() {
@@ -37,3 +38,51 @@ fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
     }
     fs::remove_dir(path)
 }
+
+/// Directory handle for platforms without `*at` system calls.
+///
+/// Every operation is forwarded to the path-based implementation after
+/// joining its argument onto the path the handle was opened with, so unlike
+/// the descriptor-based implementations it does not guard against the
+/// directory being moved or replaced while the handle is in use.
+#[derive(Debug)]
+pub struct DirHandle {
+    root: PathBuf,
+}
+
+impl DirHandle {
+    pub fn open(path: &Path) -> io::Result<DirHandle> {
+        if !fs_imp::stat(path)?.file_type().is_dir() {
+            return Err(Error::new(ErrorKind::Other, "the path is not a directory"));
+        }
+        Ok(DirHandle { root: path.to_path_buf() })
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &fs_imp::OpenOptions) -> io::Result<fs_imp::File> {
+        fs_imp::File::open(&self.root.join(path), opts)
+    }
+
+    pub fn mkdir(&self, path: &Path) -> io::Result<()> {
+        fs_imp::DirBuilder::new().mkdir(&self.root.join(path))
+    }
+
+    pub fn unlink(&self, path: &Path) -> io::Result<()> {
+        fs_imp::unlink(&self.root.join(path))
+    }
+
+    pub fn rmdir(&self, path: &Path) -> io::Result<()> {
+        fs_imp::rmdir(&self.root.join(path))
+    }
+
+    pub fn lstat(&self, path: &Path) -> io::Result<fs_imp::FileAttr> {
+        fs_imp::lstat(&self.root.join(path))
+    }
+
+    pub fn readdir(&self, path: &Path) -> io::Result<fs_imp::ReadDir> {
+        fs_imp::readdir(&self.root.join(path))
+    }
+
+    pub fn rename(&self, old: &Path, new_dir: &DirHandle, new: &Path) -> io::Result<()> {
+        fs_imp::rename(&self.root.join(old), &new_dir.root.join(new))
+    }
+}
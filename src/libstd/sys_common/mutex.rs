@@ -81,9 +81,130 @@ impl Mutex {
     }
 }
 
-// not meant to be exported to the outside world, just the containing module
-pub fn raw(mutex: &Mutex) -> &imp::Mutex {
-    &mutex.0
+/// An OS-based mutual exclusion lock.
+///
+/// This mutex does *not* have a const constructor, cleans up its resources in
+/// its `Drop` implementation, may safely be moved (when not borrowed), and
+/// does not cause UB when used reentrantly.
+///
+/// This mutex does not implement poisoning.
+///
+/// This is either a wrapper around `Box<imp::Mutex>` or `imp::Mutex`,
+/// depending on the platform. It is boxed on platforms where `imp::Mutex` may
+/// not be moved.
+pub struct MovableMutex {
+    inner: imp::MovableMutex,
+    id: id::MutexId,
+}
+
+unsafe impl Sync for MovableMutex {}
+
+impl MovableMutex {
+    /// Creates a new mutex.
+    pub fn new() -> Self {
+        let mut mutex = imp::MovableMutex::from(imp::Mutex::new());
+        unsafe { mutex.init() };
+        Self { inner: mutex, id: id::MutexId::new() }
+    }
+
+    pub(super) fn raw(&self) -> &imp::Mutex {
+        &self.inner
+    }
+
+    /// Returns a non-zero number identifying this mutex, which stays the same
+    /// when the mutex is moved. Used by `Condvar` to check it is only ever
+    /// used with one mutex.
+    pub(super) fn id(&self) -> usize {
+        <imp::MovableMutex as id::HasMutexId>::id(&self.inner, &self.id)
+    }
+
+    /// Locks the mutex blocking the current thread until it is available.
+    #[inline]
+    pub fn raw_lock(&self) {
+        unsafe { self.inner.lock() }
+    }
+
+    /// Attempts to lock the mutex without blocking, returning whether it was
+    /// successfully acquired or not.
+    #[inline]
+    pub fn try_lock(&self) -> bool {
+        unsafe { self.inner.try_lock() }
+    }
+
+    /// Unlocks the mutex.
+    ///
+    /// Behavior is undefined if the current thread does not actually hold the
+    /// mutex.
+    #[inline]
+    pub unsafe fn raw_unlock(&self) {
+        self.inner.unlock()
+    }
+}
+
+impl Drop for MovableMutex {
+    fn drop(&mut self) {
+        unsafe { self.inner.destroy() };
+    }
+}
+
+mod id {
+    use crate::sync::atomic::{AtomicUsize, Ordering};
+    use crate::sys::mutex as imp;
+
+    pub type MutexId = <imp::MovableMutex as HasMutexId>::Id;
+
+    pub trait HasMutexId {
+        type Id;
+        fn id(mutex: &Self, id: &Self::Id) -> usize;
+    }
+
+    /// Boxed mutexes never move, so they are identified by their address.
+    impl HasMutexId for Box<imp::Mutex> {
+        type Id = NoId;
+        fn id(mutex: &Self, _: &NoId) -> usize {
+            &**mutex as *const imp::Mutex as usize
+        }
+    }
+
+    /// Unboxed mutexes may move, so they are given a unique number the first
+    /// time their identity is needed.
+    impl HasMutexId for imp::Mutex {
+        type Id = LazyId;
+        fn id(_: &Self, id: &LazyId) -> usize {
+            id.get()
+        }
+    }
+
+    pub struct NoId;
+
+    #[allow(dead_code)]
+    impl NoId {
+        pub const fn new() -> Self {
+            Self
+        }
+    }
+
+    pub struct LazyId(AtomicUsize);
+
+    #[allow(dead_code)]
+    impl LazyId {
+        pub const fn new() -> Self {
+            Self(AtomicUsize::new(0))
+        }
+        fn get(&self) -> usize {
+            static NEXT: AtomicUsize = AtomicUsize::new(1);
+            match self.0.load(Ordering::Relaxed) {
+                0 => {
+                    let new = NEXT.fetch_add(1, Ordering::Relaxed);
+                    match self.0.compare_and_swap(0, new, Ordering::Relaxed) {
+                        0 => new,   // Stored our number
+                        old => old, // Another thread stored one first
+                    }
+                }
+                id => id,
+            }
+        }
+    }
 }
 
 #[must_use]
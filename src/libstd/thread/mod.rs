@@ -161,6 +161,7 @@ use crate::cell::UnsafeCell;
 use crate::ffi::{CStr, CString};
 use crate::fmt;
 use crate::io;
+use crate::marker::PhantomData;
 use crate::mem;
 use crate::num::NonZeroU64;
 use crate::panic;
@@ -207,6 +208,15 @@ pub use self::local::os::Key as __OsLocalKeyInner;
 #[doc(hidden)]
 pub use self::local::statik::Key as __StaticLocalKeyInner;
 
+////////////////////////////////////////////////////////////////////////////////
+// Scoped threads
+////////////////////////////////////////////////////////////////////////////////
+
+mod scoped;
+
+#[unstable(feature = "scoped_threads", issue = "none")]
+pub use scoped::{scope, Scope, ScopedJoinHandle};
+
 ////////////////////////////////////////////////////////////////////////////////
 // Builder
 ////////////////////////////////////////////////////////////////////////////////
@@ -454,6 +464,20 @@ impl Builder {
         F: FnOnce() -> T,
         F: Send + 'a,
         T: Send + 'a,
+    {
+        Ok(JoinHandle(self.spawn_unchecked_(f, None)?))
+    }
+
+    unsafe fn spawn_unchecked_<'a, 'scope, F, T>(
+        self,
+        f: F,
+        scope_data: Option<Arc<scoped::ScopeData>>,
+    ) -> io::Result<JoinInner<'scope, T>>
+    where
+        F: FnOnce() -> T,
+        F: Send + 'a,
+        T: Send + 'a,
+        'scope: 'a,
     {
         let Builder { name, stack_size } = self;
 
@@ -462,7 +486,11 @@ impl Builder {
         let my_thread = Thread::new(name);
         let their_thread = my_thread.clone();
 
-        let my_packet: Arc<UnsafeCell<Option<Result<T>>>> = Arc::new(UnsafeCell::new(None));
+        let my_packet: Arc<Packet<'scope, T>> = Arc::new(Packet {
+            scope: scope_data,
+            result: UnsafeCell::new(None),
+            _marker: PhantomData,
+        });
         let their_packet = my_packet.clone();
 
         let main = move || {
@@ -474,10 +502,18 @@ impl Builder {
             let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
                 crate::sys_common::backtrace::__rust_begin_short_backtrace(f)
             }));
-            *their_packet.get() = Some(try_result);
+            *their_packet.result.get() = Some(try_result);
+            // Drop our half of the packet here rather than when the closure is dropped, so a
+            // scope waiting on this thread is only notified once the result is stored and no
+            // other borrowed data of the closure is in use anymore.
+            drop(their_packet);
         };
 
-        Ok(JoinHandle(JoinInner {
+        if let Some(scope_data) = &my_packet.scope {
+            scope_data.increment_num_running_threads();
+        }
+
+        Ok(JoinInner {
             // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
             // through FFI or otherwise used with low-level threading primitives that have no
             // notion of or way to enforce lifetimes.
@@ -496,8 +532,8 @@ impl Builder {
                 )),
             )?),
             thread: my_thread,
-            packet: Packet(my_packet),
-        }))
+            packet: my_packet,
+        })
     }
 }
 
@@ -1333,28 +1369,61 @@ pub type Result<T> = crate::result::Result<T, Box<dyn Any + Send + 'static>>;
 // parent thread never reads this packet until the child has exited).
 //
 // This packet itself is then stored into a `JoinInner` which in turns is placed
-// in `JoinHandle` and `JoinGuard`. Due to the usage of `UnsafeCell` we need to
-// manually worry about impls like Send and Sync. The type `T` should
-// already always be Send (otherwise the thread could not have been created) and
-// this type is inherently Sync because no methods take &self. Regardless,
-// however, we add inheriting impls for Send/Sync to this type to ensure it's
-// Send/Sync and that future modifications will still appropriately classify it.
-struct Packet<T>(Arc<UnsafeCell<Option<Result<T>>>>);
+// in `JoinHandle` and `ScopedJoinHandle`. If the thread was spawned in a scope,
+// the packet also tells the scope when the thread is done with everything it
+// borrowed, which is when the last reference to the packet is dropped.
+struct Packet<'scope, T> {
+    scope: Option<Arc<scoped::ScopeData>>,
+    result: UnsafeCell<Option<Result<T>>>,
+    _marker: PhantomData<Option<&'scope scoped::ScopeData>>,
+}
 
-unsafe impl<T: Send> Send for Packet<T> {}
-unsafe impl<T: Sync> Sync for Packet<T> {}
+// Due to the usage of `UnsafeCell` we need to manually implement Sync.
+// The type `T` should already always be Send (otherwise the thread could not
+// have been created) and the Packet is Sync because all access to the
+// `UnsafeCell` is synchronized (by the `join()` boundary), and `ScopeData` is Sync.
+unsafe impl<'scope, T: Sync> Sync for Packet<'scope, T> {}
+
+impl<'scope, T> Drop for Packet<'scope, T> {
+    fn drop(&mut self) {
+        // If this packet was for a thread that ran in a scope, the thread
+        // panicked, and nobody consumed the panic payload, we make sure
+        // the scope function will panic.
+        let unhandled_panic = matches!(self.result.get_mut(), Some(Err(_)));
+        // Drop the result without causing unwinding.
+        // This is only relevant for threads that aren't join()ed, as
+        // join() will take the `result` and set it to None, such that
+        // there is nothing left to drop here.
+        // If this panics, we should handle that, because we're outside the
+        // outermost `catch_unwind` of our thread.
+        // We just abort in that case, since there's nothing else we can do.
+        if let Err(_) = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            *self.result.get_mut() = None;
+        })) {
+            rtabort!("thread result panicked on drop");
+        }
+        // Book-keeping so the scope knows when it's done.
+        if let Some(scope) = &self.scope {
+            // Now that there will be no more user code running on this thread
+            // that can use 'scope, mark the thread as 'finished'.
+            // It's important we only do this after the `result` has been dropped,
+            // since dropping it might still use things it borrowed from 'scope.
+            scope.decrement_num_running_threads(unhandled_panic);
+        }
+    }
+}
 
 /// Inner representation for JoinHandle
-struct JoinInner<T> {
+struct JoinInner<'scope, T> {
     native: Option<imp::Thread>,
     thread: Thread,
-    packet: Packet<T>,
+    packet: Arc<Packet<'scope, T>>,
 }
 
-impl<T> JoinInner<T> {
+impl<'scope, T> JoinInner<'scope, T> {
     fn join(&mut self) -> Result<T> {
         self.native.take().unwrap().join();
-        unsafe { (*self.packet.0.get()).take().unwrap() }
+        Arc::get_mut(&mut self.packet).unwrap().result.get_mut().take().unwrap()
     }
 }
 
@@ -1422,7 +1491,7 @@ impl<T> JoinInner<T> {
 /// [`thread::spawn`]: fn.spawn.html
 /// [`thread::Builder::spawn`]: struct.Builder.html#method.spawn
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct JoinHandle<T>(JoinInner<T>);
+pub struct JoinHandle<T>(JoinInner<'static, T>);
 
 #[stable(feature = "joinhandle_impl_send_sync", since = "1.29.0")]
 unsafe impl<T> Send for JoinHandle<T> {}
@@ -1523,6 +1592,7 @@ mod tests {
     use crate::any::Any;
     use crate::mem;
     use crate::result;
+    use crate::sync::atomic::{AtomicBool, Ordering};
     use crate::sync::mpsc::{channel, Sender};
     use crate::thread::{self, ThreadId};
     use crate::time::Duration;
@@ -1783,6 +1853,69 @@ mod tests {
         assert!(thread::current().id() != spawned_id);
     }
 
+    #[test]
+    fn test_scope_borrows() {
+        let mut a = vec![1, 2, 3];
+        let mut x = 0;
+        thread::scope(|s| {
+            s.spawn(|| assert_eq!(a.len(), 3));
+            s.spawn(|| x += a[0] + a[2]);
+        });
+        a.push(4);
+        assert_eq!(x, a.len());
+    }
+
+    #[test]
+    fn test_scope_join() {
+        let v = vec![1, 2, 3];
+        let sum = thread::scope(|s| {
+            let t = s.spawn(|| v.iter().sum::<i32>());
+            assert_eq!(t.thread().name(), None);
+            t.join().unwrap()
+        });
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn test_scope_builder() {
+        let name = thread::scope(|s| {
+            Builder::new()
+                .name("scoped".to_string())
+                .spawn_scoped(s, || thread::current().name().unwrap().to_string())
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+        assert_eq!(name, "scoped");
+    }
+
+    #[test]
+    fn test_scope_join_panic() {
+        thread::scope(|s| {
+            assert!(s.spawn(|| panic!()).join().is_err());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "a scoped thread panicked")]
+    fn test_scope_unjoined_panic() {
+        thread::scope(|s| {
+            s.spawn(|| panic!());
+        });
+    }
+
+    #[test]
+    fn test_scope_waits_for_threads() {
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(10));
+                done.store(true, Ordering::Relaxed);
+            });
+        });
+        assert!(done.load(Ordering::Relaxed));
+    }
+
     // NOTE: the corresponding test for stderr is in ui/thread-stderr, due
     // to the test harness apparently interfering with stderr configuration.
 }
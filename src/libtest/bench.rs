@@ -225,7 +225,7 @@ where
     };
 
     let stdout = data.lock().unwrap().to_vec();
-    let message = CompletedTest::new(desc, test_result, None, stdout);
+    let message = CompletedTest::new(desc, test_result, None, stdout, None);
     monitor_ch.send(message).unwrap();
 }
 
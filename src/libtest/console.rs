@@ -236,9 +236,10 @@ fn on_test_event(
             let result = &completed_test.result;
             let exec_time = &completed_test.exec_time;
             let stdout = &completed_test.stdout;
+            let details = &completed_test.details;
 
             st.write_log_result(test, result, exec_time.as_ref())?;
-            out.write_result(test, result, exec_time.as_ref(), &*stdout, st)?;
+            out.write_result(test, result, exec_time.as_ref(), &*stdout, details.as_ref(), st)?;
             handle_test_result(st, completed_test);
         }
     }
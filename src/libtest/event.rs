@@ -3,7 +3,7 @@
 
 use super::test_result::TestResult;
 use super::time::TestExecTime;
-use super::types::TestDesc;
+use super::types::{TestDesc, TestDetails};
 
 #[derive(Debug, Clone)]
 pub struct CompletedTest {
@@ -11,6 +11,7 @@ pub struct CompletedTest {
     pub result: TestResult,
     pub exec_time: Option<TestExecTime>,
     pub stdout: Vec<u8>,
+    pub details: Option<TestDetails>,
 }
 
 impl CompletedTest {
@@ -19,8 +20,9 @@ impl CompletedTest {
         result: TestResult,
         exec_time: Option<TestExecTime>,
         stdout: Vec<u8>,
+        details: Option<TestDetails>,
     ) -> Self {
-        Self { desc, result, exec_time, stdout }
+        Self { desc, result, exec_time, stdout, details }
     }
 }
 
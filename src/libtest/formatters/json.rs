@@ -5,7 +5,7 @@ use crate::{
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
-    types::TestDesc,
+    types::{TestDesc, TestDetails},
 };
 
 pub(crate) struct JsonFormatter<T> {
@@ -38,6 +38,7 @@ impl<T: Write> JsonFormatter<T> {
         exec_time: Option<&time::TestExecTime>,
         stdout: Option<Cow<'_, str>>,
         extra: Option<&str>,
+        details: Option<&TestDetails>,
     ) -> io::Result<()> {
         self.write_message(&*format!(
             r#"{{ "type": "{}", "name": "{}", "event": "{}""#,
@@ -52,8 +53,42 @@ impl<T: Write> JsonFormatter<T> {
         if let Some(extra) = extra {
             self.write_message(&*format!(r#", {}"#, extra))?;
         }
+        if let Some(details) = details {
+            self.write_details(details)?;
+        }
         self.writeln_message(" }")
     }
+
+    fn write_details(&mut self, details: &TestDetails) -> io::Result<()> {
+        self.write_message(&*format!(
+            r#", "source_file": "{}", "start_line": {}, "end_line": {}"#,
+            EscapedString(&details.source_file),
+            details.start_line,
+            details.end_line
+        ))?;
+        if let Some(compile_time) = details.compile_time {
+            let compile_time = time::TestExecTime(compile_time);
+            self.write_message(&*format!(r#", "compile_time": "{}""#, compile_time))?;
+        }
+        if let Some(run_time) = details.run_time {
+            let run_time = time::TestExecTime(run_time);
+            self.write_message(&*format!(r#", "run_time": "{}""#, run_time))?;
+        }
+        if let Some(ref failure_kind) = details.failure_kind {
+            self.write_message(&*format!(
+                r#", "failure_kind": "{}""#,
+                EscapedString(failure_kind)
+            ))?;
+        }
+        if !details.diagnostics.is_empty() {
+            // The diagnostics are already JSON objects, written by the compiler.
+            self.write_message(&*format!(
+                r#", "diagnostics": [{}]"#,
+                details.diagnostics.join(", ")
+            ))?;
+        }
+        Ok(())
+    }
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
@@ -77,6 +112,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        details: Option<&TestDetails>,
         state: &ConsoleTestState,
     ) -> io::Result<()> {
         let display_stdout = state.options.display_output || *result != TestResult::TrOk;
@@ -86,13 +122,25 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
             None
         };
         match *result {
-            TestResult::TrOk => {
-                self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
-            }
+            TestResult::TrOk => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ok",
+                exec_time,
+                stdout,
+                None,
+                details,
+            ),
 
-            TestResult::TrFailed => {
-                self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
-            }
+            TestResult::TrFailed => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                None,
+                details,
+            ),
 
             TestResult::TrTimedFail => self.write_event(
                 "test",
@@ -101,6 +149,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 exec_time,
                 stdout,
                 Some(r#""reason": "time limit exceeded""#),
+                details,
             ),
 
             TestResult::TrFailedMsg(ref m) => self.write_event(
@@ -110,11 +159,18 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 exec_time,
                 stdout,
                 Some(&*format!(r#""message": "{}""#, EscapedString(m))),
+                details,
             ),
 
-            TestResult::TrIgnored => {
-                self.write_event("test", desc.name.as_slice(), "ignored", exec_time, stdout, None)
-            }
+            TestResult::TrIgnored => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ignored",
+                exec_time,
+                stdout,
+                None,
+                details,
+            ),
 
             TestResult::TrAllowedFail => self.write_event(
                 "test",
@@ -123,6 +179,7 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
                 exec_time,
                 stdout,
                 None,
+                details,
             ),
 
             TestResult::TrBench(ref bs) => {
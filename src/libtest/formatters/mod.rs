@@ -4,7 +4,7 @@ use crate::{
     console::ConsoleTestState,
     test_result::TestResult,
     time,
-    types::{TestDesc, TestName},
+    types::{TestDesc, TestDetails, TestName},
 };
 
 mod json;
@@ -25,6 +25,7 @@ pub(crate) trait OutputFormatter {
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        details: Option<&TestDetails>,
         state: &ConsoleTestState,
     ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
@@ -6,7 +6,7 @@ use crate::{
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
-    types::TestDesc,
+    types::{TestDesc, TestDetails},
 };
 
 pub(crate) struct PrettyFormatter<T> {
@@ -199,6 +199,7 @@ impl<T: Write> OutputFormatter for PrettyFormatter<T> {
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         _: &[u8],
+        _: Option<&TestDetails>,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         if self.is_multithreaded {
@@ -8,6 +8,7 @@ use crate::{
     time,
     types::NamePadding,
     types::TestDesc,
+    types::TestDetails,
 };
 
 // insert a '\n' after 100 tests in quiet mode
@@ -189,6 +190,7 @@ impl<T: Write> OutputFormatter for TerseFormatter<T> {
         result: &TestResult,
         _: Option<&time::TestExecTime>,
         _: &[u8],
+        _: Option<&TestDetails>,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         match *result {
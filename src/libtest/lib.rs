@@ -60,6 +60,7 @@ pub mod test {
 }
 
 use std::{
+    cell::RefCell,
     env, io,
     io::prelude::Write,
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
@@ -184,6 +185,17 @@ fn make_owned_test(test: &&TestDescAndFn) -> TestDescAndFn {
     }
 }
 
+thread_local! {
+    /// The details reported by the test running on the current thread.
+    static TEST_DETAILS: RefCell<Option<TestDetails>> = RefCell::new(None);
+}
+
+/// Reports details about the test running on the current thread, to be included in the JSON
+/// output. Only the details of tests run in-process are reported.
+pub fn set_test_details(details: TestDetails) {
+    TEST_DETAILS.with(|d| *d.borrow_mut() = Some(details));
+}
+
 /// Invoked when unit tests terminate. Should panic if the unit
 /// Tests is considered a failure. By default, invokes `report()`
 /// and checks for a `0` result.
@@ -426,7 +438,7 @@ pub fn run_test(
         && !cfg!(target_os = "emscripten");
 
     if force_ignore || desc.ignore || ignore_because_no_process_support {
-        let message = CompletedTest::new(desc, TrIgnored, None, Vec::new());
+        let message = CompletedTest::new(desc, TrIgnored, None, Vec::new(), None);
         monitor_ch.send(message).unwrap();
         return;
     }
@@ -544,6 +556,7 @@ fn run_test_in_process(
         let duration = start.elapsed();
         TestExecTime(duration)
     });
+    let details = TEST_DETAILS.with(|d| d.borrow_mut().take());
 
     if let Some((printio, panicio)) = oldio {
         io::set_print(printio);
@@ -555,7 +568,7 @@ fn run_test_in_process(
         Err(e) => calc_result(&desc, Err(e.as_ref()), &time_opts, &exec_time),
     };
     let stdout = data.lock().unwrap().to_vec();
-    let message = CompletedTest::new(desc, test_result, exec_time, stdout);
+    let message = CompletedTest::new(desc, test_result, exec_time, stdout, details);
     monitor_ch.send(message).unwrap();
 }
 
@@ -609,7 +622,7 @@ fn spawn_test_subprocess(
         (result, test_output, exec_time)
     })();
 
-    let message = CompletedTest::new(desc, result, exec_time, test_output);
+    let message = CompletedTest::new(desc, result, exec_time, test_output, None);
     monitor_ch.send(message).unwrap();
 }
 
@@ -119,6 +119,34 @@ pub fn ignored_tests_result_in_ignored() {
     assert_eq!(result, TrIgnored);
 }
 
+#[test]
+fn test_details_are_reported() {
+    fn details() -> TestDetails {
+        TestDetails {
+            source_file: "src/lib.rs".to_string(),
+            start_line: 3,
+            end_line: 7,
+            failure_kind: Some("compile_error".to_string()),
+            ..TestDetails::default()
+        }
+    }
+    let desc = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("whatever"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            test_type: TestType::DocTest,
+        },
+        testfn: DynTestFn(Box::new(|| set_test_details(details()))),
+    };
+    let (tx, rx) = channel();
+    run_test(&TestOpts::new(), false, desc, RunStrategy::InProcess, tx, Concurrent::No);
+    let completed = rx.recv().unwrap();
+    assert_eq!(completed.result, TrOk);
+    assert_eq!(completed.details, Some(details()));
+}
+
 // FIXME: Re-enable emscripten once it can catch panics again (introduced by #65251)
 #[test]
 #[cfg(not(target_os = "emscripten"))]
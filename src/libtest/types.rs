@@ -2,6 +2,7 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::time::Duration;
 
 use super::bench::Bencher;
 use super::options;
@@ -138,6 +139,26 @@ impl TestDesc {
     }
 }
 
+/// Details that a test reports about itself with `set_test_details`, which are included in the JSON
+/// output. Rustdoc uses them to point doctests back to the documentation they come from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestDetails {
+    /// The file that contains the test.
+    pub source_file: String,
+    /// The first line of the test in `source_file`, starting at 1.
+    pub start_line: usize,
+    /// The last line of the test in `source_file`.
+    pub end_line: usize,
+    /// How long it took to build the test, if it had to be built first.
+    pub compile_time: Option<Duration>,
+    /// How long it took to run the test, once it was built.
+    pub run_time: Option<Duration>,
+    /// The kind of failure of the test, like `compile_error`, if it failed.
+    pub failure_kind: Option<String>,
+    /// The diagnostics emitted while building the test, each as a JSON object.
+    pub diagnostics: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct TestDescAndFn {
     pub desc: TestDesc,
@@ -91,6 +91,10 @@ extern "C" unsigned int LLVMRustGetInstructionCount(LLVMModuleRef M) {
   return unwrap(M)->getInstructionCount();
 }
 
+extern "C" unsigned int LLVMRustGetFunctionInstructionCount(LLVMValueRef Fn) {
+  return unwrap<Function>(Fn)->getInstructionCount();
+}
+
 extern "C" void LLVMRustSetLastError(const char *Err) {
   free((void *)LastError);
   LastError = strdup(Err);
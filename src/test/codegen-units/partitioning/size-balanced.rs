@@ -0,0 +1,52 @@
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-mono-items=eager -Zincremental=tmp/partitioning-tests/size-balanced -Zcgu-partitioning=size-balanced -Ccodegen-units=2
+
+// The modules are placed largest first, each into the codegen unit that is
+// currently the smallest: `a` goes into `cgu.0`, `b` and then `c` into
+// `cgu.1`, which leaves `cgu.0` as the smaller one for `d`. Merging the
+// smallest codegen units instead would have put `a` and `b` together.
+
+#![allow(dead_code)]
+#![crate_type="lib"]
+
+mod a {
+    //~ MONO_ITEM fn size_balanced::a[0]::work[0] @@ size_balanced-cgu.0[Internal]
+    fn work() -> u32 {
+        let mut x = 0;
+        x += 1; x += 1; x += 1; x += 1; x += 1;
+        x += 1; x += 1; x += 1; x += 1; x += 1;
+        x
+    }
+}
+
+mod b {
+    //~ MONO_ITEM fn size_balanced::b[0]::work[0] @@ size_balanced-cgu.1[Internal]
+    fn work() -> u32 {
+        let mut x = 0;
+        x += 1; x += 1; x += 1; x += 1;
+        x += 1; x += 1; x += 1; x += 1;
+        x
+    }
+}
+
+mod c {
+    //~ MONO_ITEM fn size_balanced::c[0]::work[0] @@ size_balanced-cgu.1[Internal]
+    fn work() -> u32 {
+        let mut x = 0;
+        x += 1; x += 1; x += 1;
+        x += 1; x += 1; x += 1;
+        x
+    }
+}
+
+mod d {
+    //~ MONO_ITEM fn size_balanced::d[0]::work[0] @@ size_balanced-cgu.0[Internal]
+    fn work() -> u32 {
+        let mut x = 0;
+        x += 1; x += 1; x += 1;
+        x += 1; x += 1; x += 1;
+        x
+    }
+}
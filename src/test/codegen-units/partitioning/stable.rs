@@ -0,0 +1,32 @@
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-mono-items=eager -Zincremental=tmp/partitioning-tests/stable -Zcgu-partitioning=stable -Ccodegen-units=3
+
+// Each module is placed by a hash of its path alone, independent of the size
+// of the others: the crate root and `mod1::mod2` hash to `cgu.2`, `mod1` and
+// `mod2` to `cgu.0`, leaving `cgu.1` empty.
+
+#![allow(dead_code)]
+#![crate_type="lib"]
+
+//~ MONO_ITEM fn stable::foo[0] @@ stable-cgu.2[Internal]
+fn foo() {}
+
+//~ MONO_ITEM static stable::BAZ[0] @@ stable-cgu.2[Internal]
+static BAZ: u64 = 0;
+
+mod mod1 {
+    //~ MONO_ITEM fn stable::mod1[0]::foo[0] @@ stable-cgu.0[Internal]
+    fn foo() {}
+
+    mod mod2 {
+        //~ MONO_ITEM fn stable::mod1[0]::mod2[0]::foo[0] @@ stable-cgu.2[Internal]
+        fn foo() {}
+    }
+}
+
+mod mod2 {
+    //~ MONO_ITEM fn stable::mod2[0]::foo[0] @@ stable-cgu.0[Internal]
+    fn foo() {}
+}
@@ -36,7 +36,7 @@ fn main() {
 //   return;
 // }
 // bb4 (cleanup): {
-//   _3 = const alloc::alloc::box_free::<std::vec::Vec<u32>>(move (_2.0: std::ptr::Unique<std::vec::Vec<u32>>)) -> bb1;
+//   _3 = const alloc::alloc::box_free::<std::vec::Vec<u32>, std::alloc::Global>(move (_2.0: std::ptr::Unique<std::vec::Vec<u32>>), move (_2.1: std::alloc::Global)) -> bb1;
 // }
 // END rustc.main.Inline.before.mir
 // START rustc.main.Inline.after.mir
@@ -0,0 +1,60 @@
+// compile-flags: -Z mir-opt-level=2
+
+// The loop header switches on `should_break`, which both of its predecessors set to a known
+// constant, so both jumps into it get threaded to the right successor.
+
+fn main() {
+    let mut should_break = false;
+    loop {
+        if should_break {
+            break;
+        }
+        should_break = true;
+    }
+}
+
+// END RUST SOURCE
+// START rustc.main.JumpThreading.before.mir
+// bb0: {
+//     StorageLive(_1);
+//     _1 = const false;
+//     goto -> bb1;
+// }
+// bb1: {
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     switchInt(_4) -> [false: bb2, otherwise: bb3];
+// }
+// bb2: {
+//     ...
+//     _1 = const true;
+//     ...
+//     goto -> bb1;
+// }
+// END rustc.main.JumpThreading.before.mir
+// START rustc.main.JumpThreading.after.mir
+// bb0: {
+//     StorageLive(_1);
+//     _1 = const false;
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     goto -> bb2;
+// }
+// bb1: {
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     switchInt(_4) -> [false: bb2, otherwise: bb3];
+// }
+// bb2: {
+//     ...
+//     _1 = const true;
+//     ...
+//     StorageLive(_3);
+//     StorageLive(_4);
+//     _4 = _1;
+//     goto -> bb3;
+// }
+// END rustc.main.JumpThreading.after.mir
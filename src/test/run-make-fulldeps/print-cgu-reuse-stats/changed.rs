@@ -0,0 +1,21 @@
+pub fn foo() -> u32 {
+    1
+}
+
+pub mod mod1 {
+    pub fn foo() -> u32 {
+        2
+    }
+
+    pub mod mod2 {
+        pub fn foo() -> u32 {
+            30
+        }
+    }
+}
+
+pub mod mod2 {
+    pub fn foo() -> u32 {
+        4
+    }
+}
@@ -0,0 +1,8 @@
+#[inline(never)]
+fn twice<T: std::ops::Add<Output = T> + Copy>(x: T) -> T {
+    x + x
+}
+
+fn main() {
+    println!("{} {}", twice(1u32), twice(2u64));
+}
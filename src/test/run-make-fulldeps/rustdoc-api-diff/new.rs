@@ -0,0 +1,33 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+pub trait Draw {
+    fn draw(&self);
+
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+
+pub fn area(_shape: &Shape, _scale: f64) -> f64 {
+    0.0
+}
+
+pub fn make() -> u8 {
+    0
+}
+
+pub fn new_name(x: u32) -> u32 {
+    x
+}
+
+pub fn added_fn(_flag: bool) {}
@@ -0,0 +1,27 @@
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+pub trait Draw {
+    fn draw(&self);
+}
+
+pub fn area(_shape: &Shape) -> f64 {
+    0.0
+}
+
+pub const fn make() -> u8 {
+    0
+}
+
+pub fn old_name(x: u32) -> u32 {
+    x
+}
+
+pub fn removed_fn() {}
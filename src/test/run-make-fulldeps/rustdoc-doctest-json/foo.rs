@@ -0,0 +1,14 @@
+/// ```
+/// let x: u32 = "not a number";
+/// ```
+pub fn compile_fails() {}
+
+/// ```
+/// panic!("oh no");
+/// ```
+pub fn run_fails() {}
+
+/// ```
+/// assert_eq!(1 + 1, 2);
+/// ```
+pub fn passes() {}
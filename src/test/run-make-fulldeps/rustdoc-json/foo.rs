@@ -0,0 +1,26 @@
+#![crate_name = "foo"]
+
+/// A documented struct, see [Bar].
+pub struct Foo {
+    pub field: u32,
+}
+
+impl Foo {
+    /// Does something.
+    pub fn frobnicate(&self) -> u32 {
+        self.field
+    }
+}
+
+pub trait Bar<T> {
+    fn bar(&self, t: T);
+}
+
+impl<T> Bar<T> for Foo
+where
+    T: Copy,
+{
+    fn bar(&self, _: T) {}
+}
+
+fn hidden() {}
@@ -0,0 +1,27 @@
+extern crate foobar;
+
+fn main() {
+    foobar::ok();
+    let foo = foobar::Foo::new();
+    assert_eq!(foo.frob(), ());
+    ok();
+    Other.frob();
+    let foo = Other;
+    foo.frob();
+}
+
+fn ok() {}
+
+struct Other;
+
+impl Other {
+    fn frob(&self) {}
+}
+
+mod imports {
+    use foobar::Foo as Renamed;
+
+    fn renamed(foo: &Renamed) {
+        foo.frob();
+    }
+}
@@ -0,0 +1,27 @@
+// ignore-tidy-linelength
+
+#![crate_name = "foobar"]
+
+// @has foobar/fn.ok.html '//details[@class="scraped-examples"]//a[@href="../src/foobar/examples/ex.rs.html#4"]' 'examples/ex.rs (line 4)'
+// @has - '//details[@class="scraped-examples"]//pre[@class="rust scraped-example"]' 'foobar::ok();'
+// The call to the example's own `ok` function isn't shown.
+// @count - '//div[@class="scraped-example"]' 1
+pub fn ok() {}
+
+// @!has foobar/fn.unused.html '//details[@class="scraped-examples"]'
+pub fn unused() {}
+
+pub struct Foo;
+
+// The calls to `frob` on the example's own `Other` type aren't shown.
+// @count foobar/struct.Foo.html '//div[@class="scraped-example"]' 3
+impl Foo {
+    // @has foobar/struct.Foo.html '//details[@class="scraped-examples"]//a[@href="../src/foobar/examples/ex.rs.html#5"]' 'examples/ex.rs (line 5)'
+    pub fn new() -> Foo {
+        Foo
+    }
+
+    // @has - '//details[@class="scraped-examples"]//a[@href="../src/foobar/examples/ex.rs.html#6"]' 'examples/ex.rs (line 6)'
+    // @has - '//details[@class="scraped-examples"]//a[@href="../src/foobar/examples/ex.rs.html#25"]' 'examples/ex.rs (line 25)'
+    pub fn frob(&self) {}
+}
@@ -0,0 +1,39 @@
+//! The crate documentation.
+
+/// A documented struct, see [`Bar`] and [`bar::baz`].
+///
+/// # Examples
+///
+/// ```
+/// # let hidden = 1;
+/// let foo = foo::Foo { x: 1 };
+/// ```
+pub struct Foo {
+    /// The x field.
+    pub x: u32,
+}
+
+impl Foo {
+    /// Frobnicates the foo.
+    pub fn frobnicate(&self) -> u32 {
+        self.x
+    }
+}
+
+/// An enum.
+pub enum Bar {
+    /// The A variant.
+    A,
+    B(u8),
+}
+
+pub mod bar {
+    /// A function in a module.
+    pub fn baz() {}
+}
+
+/// A macro with the same name as a module.
+#[macro_export]
+macro_rules! bar {
+    () => {};
+}
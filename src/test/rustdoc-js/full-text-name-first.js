@@ -0,0 +1,12 @@
+// exact-check
+
+const QUERY = 'frobnicate';
+
+// The items whose name matches come first, even if the query appears more often in the
+// documentation of others.
+const EXPECTED = {
+    'docs': [
+        { 'path': 'full_text_name_first', 'name': 'frobnicate' },
+        { 'path': 'full_text_name_first', 'name': 'Widget' },
+    ],
+};
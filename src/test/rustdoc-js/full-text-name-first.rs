@@ -0,0 +1,7 @@
+// compile-flags: -Z unstable-options --full-text-search
+
+/// Does the frobnicate thing.
+pub fn frobnicate() {}
+
+/// Call frobnicate to frobnicate a widget, then frobnicate it again.
+pub struct Widget;
@@ -0,0 +1,10 @@
+// should-fail
+
+const QUERY = '"zero sized"';
+
+// A quoted query doesn't match items where its words aren't a phrase.
+const EXPECTED = {
+    'docs': [
+        { 'path': 'full_text_phrase_only', 'name': 'Scattered' },
+    ],
+};
@@ -0,0 +1,7 @@
+// compile-flags: -Z unstable-options --full-text-search
+
+/// Only works with zero-sized types.
+pub struct Phrase;
+
+/// Sized types, but not always zero.
+pub struct Scattered;
@@ -0,0 +1,7 @@
+const QUERY = '"zero sized"';
+
+const EXPECTED = {
+    'docs': [
+        { 'path': 'full_text_phrase', 'name': 'Phrase' },
+    ],
+};
@@ -0,0 +1,11 @@
+// exact-check
+
+const QUERY = 'zero sized';
+
+// Every word has to appear, but the items where they appear as a phrase come first.
+const EXPECTED = {
+    'docs': [
+        { 'path': 'full_text_words', 'name': 'Phrase' },
+        { 'path': 'full_text_words', 'name': 'Scattered' },
+    ],
+};
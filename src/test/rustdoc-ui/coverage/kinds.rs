@@ -0,0 +1,29 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-group-by kind
+// build-pass
+
+//! Crate docs don't need an example.
+
+/// A function with an example.
+///
+/// ```
+/// kinds::with_example();
+/// ```
+pub fn with_example() {}
+
+/// A function without one.
+pub fn without_example() {}
+
+pub fn undocumented() {}
+
+/// A struct with an example.
+///
+/// ```
+/// let _ = kinds::Foo { bar: 0 };
+/// ```
+pub struct Foo {
+    /// Fields don't need examples.
+    pub bar: usize,
+}
+
+/// Constants don't need examples.
+pub const BAZ: usize = 0;
@@ -0,0 +1,14 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-group-by module
+// compile-flags:--output-format json
+// build-pass
+
+//! The crate root.
+
+pub mod foo {
+    /// Documented.
+    pub fn bar() {}
+
+    pub mod baz {
+        pub struct Qux;
+    }
+}
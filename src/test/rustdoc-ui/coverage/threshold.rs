@@ -0,0 +1,9 @@
+// compile-flags:-Z unstable-options --show-coverage
+// compile-flags:--coverage-threshold 80 --coverage-examples-threshold 50
+
+//! The crate root.
+
+/// Documented, without an example.
+pub fn documented() {}
+
+pub fn undocumented() {}
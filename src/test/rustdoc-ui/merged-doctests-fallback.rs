@@ -0,0 +1,23 @@
+// build-pass
+// compile-flags:--test -Z unstable-options --merge-doctests --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+// Check that doctests which can't be compiled together, here because they export the same symbol,
+// still pass when the merged binary fails to build.
+
+/// ```
+/// #[no_mangle]
+/// pub extern "C" fn answer() -> u32 {
+///     1
+/// }
+/// assert_eq!(answer(), 1);
+/// ```
+///
+/// ```
+/// #[no_mangle]
+/// pub extern "C" fn answer() -> u32 {
+///     2
+/// }
+/// assert_eq!(answer(), 2);
+/// ```
+pub struct Foo;
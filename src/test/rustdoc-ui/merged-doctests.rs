@@ -0,0 +1,38 @@
+// build-pass
+// compile-flags:--test -Z unstable-options --merge-doctests --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+// Check that merged doctests still run and report separately, and that tests which can't be
+// merged are compiled on their own.
+
+/// ```
+/// assert_eq!(1 + 2, 3);
+/// ```
+///
+/// ```should_panic
+/// assert_eq!(1 + 2, 4);
+/// ```
+///
+/// ```no_run
+/// loop {}
+/// ```
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+/// ```
+/// fn main() {
+///     assert_eq!(Some(1), Some(1));
+/// }
+/// ```
+///
+/// ```compile_fail
+/// let x: u32 = "not a number";
+/// ```
+///
+/// ```
+/// let x: Result<u32, std::num::ParseIntError> = "12".parse();
+/// assert_eq!(x?, 12);
+/// # Ok::<(), std::num::ParseIntError>(())
+/// ```
+pub struct Foo;
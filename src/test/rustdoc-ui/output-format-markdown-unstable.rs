@@ -0,0 +1,4 @@
+// compile-flags: --output-format markdown
+
+/// Foo
+pub struct Foo;
@@ -0,0 +1,23 @@
+// compile-flags: -Zunstable-options --generate-link-to-definition
+// aux-build:source-code-bar.rs
+// build-aux-docs
+// ignore-cross-compile
+// ignore-tidy-linelength
+
+#![crate_name = "foo"]
+
+extern crate source_code_bar;
+
+// @has 'src/foo/check-source-code-urls-to-def.rs.html'
+
+pub struct Foo;
+
+// @has - '//a[@href="../../src/foo/check-source-code-urls-to-def.rs.html#13"]' 'Foo'
+// @has - '//a[@href="../../source_code_bar/struct.Bar.html"]' 'Bar'
+pub fn foo(_a: Foo, _b: source_code_bar::Bar) {}
+
+// @has - '//a[@class="definition"][@data-usages="../../src/foo/check-source-code-urls-to-def.rs.html#23"]' 'Trait'
+pub trait Trait {}
+
+// @has - '//a[@href="../../src/foo/check-source-code-urls-to-def.rs.html#20"]' 'Trait'
+impl Trait for Foo {}
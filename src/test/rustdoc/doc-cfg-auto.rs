@@ -0,0 +1,36 @@
+// compile-flags: -Z unstable-options --doc-cfg-auto --doc-cfg-hide test
+// compile-flags: --cfg feature="serde" --cfg feature="std"
+
+#![crate_name = "foo"]
+#![feature(doc_cfg)]
+
+// @has foo/struct.Serde.html
+// @has - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported with crate feature serde only.'
+// @has foo/index.html
+// @has - '//*[@class="module-item"]//*[@class="stab portability"]' 'serde'
+#[cfg(feature = "serde")]
+pub struct Serde;
+
+// @has foo/struct.NotTest.html
+// @!has - '//*[@class="stab portability"]' ''
+#[cfg(not(test))]
+pub struct NotTest;
+
+// @has foo/with_std/index.html
+// @has - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported with crate feature std only.'
+#[cfg(feature = "std")]
+pub mod with_std {
+    // @has foo/with_std/fn.serde_only.html
+    // @has - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+    //  'This is supported on crate feature std and crate feature serde only.'
+    #[cfg(feature = "serde")]
+    pub fn serde_only() {}
+
+    // @has foo/with_std/fn.explicit.html
+    // @has - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+    //  'This is supported on crate feature std and Windows only.'
+    #[doc(cfg(windows))]
+    pub fn explicit() {}
+}
@@ -0,0 +1,20 @@
+// compile-flags: -Z unstable-options --full-text-search
+
+#![crate_name = "foo"]
+
+// @has 'search-index.js' '"f":true'
+// @has 'search-fulltext/foo.js' 'addFullTextIndex("foo",'
+
+// @has - 'first paragraph'
+// @has - 'the second paragraph mentions frobnication'
+// @!has - 'in_a_code_block'
+/// First paragraph.
+///
+/// The second paragraph mentions *frobnication*.
+///
+/// ```
+/// let in_a_code_block = 1;
+/// ```
+pub struct Foo;
+
+pub fn undocumented() {}
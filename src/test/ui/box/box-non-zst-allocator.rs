@@ -0,0 +1,28 @@
+// build-fail
+// error-pattern: evaluation of constant value failed
+
+// `Box` only supports zero-sized allocators for now, and rejects others at compile time.
+
+#![feature(allocator_api)]
+
+use std::alloc::{AllocErr, AllocRef, Layout, System};
+use std::ptr::NonNull;
+
+struct Counting {
+    allocated: u64,
+}
+
+unsafe impl AllocRef for Counting {
+    fn alloc(&mut self, layout: Layout) -> Result<(NonNull<u8>, usize), AllocErr> {
+        self.allocated += 1;
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn main() {
+    let _b = Box::new_in(5, Counting { allocated: 0 });
+}
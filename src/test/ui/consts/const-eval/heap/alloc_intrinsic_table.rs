@@ -0,0 +1,27 @@
+// run-pass
+#![feature(const_fn, const_heap, const_loop, const_mut_refs, const_raw_ptr_deref)]
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+
+// Compute a lookup table in heap memory that is frozen into the final value.
+const fn squares() -> &'static [u32; 8] {
+    unsafe {
+        let ptr = intrinsics::const_allocate(32, 4) as *mut [u32; 8];
+        *ptr = [0; 8];
+        let mut i = 0;
+        while i < 8 {
+            (*ptr)[i] = (i * i) as u32;
+            i += 1;
+        }
+        &*ptr
+    }
+}
+
+const SQUARES: &[u32; 8] = squares();
+static SQUARES_STATIC: &[u32; 8] = squares();
+
+fn main() {
+    assert_eq!(SQUARES, &[0, 1, 4, 9, 16, 25, 36, 49]);
+    assert_eq!(SQUARES_STATIC, SQUARES);
+}
@@ -0,0 +1,9 @@
+#![feature(const_heap)]
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+
+const FOO: *const i32 = unsafe { intrinsics::const_allocate(4, 4) as *const i32 };
+//~^ ERROR any use of this value will cause an error
+
+fn main() {}
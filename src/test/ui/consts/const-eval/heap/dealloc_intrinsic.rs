@@ -0,0 +1,24 @@
+// run-pass
+#![feature(const_fn, const_heap, const_mut_refs, const_raw_ptr_deref)]
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+
+// Scratch memory that is freed again does not end up in the final value.
+const fn sum_with_scratch(a: u32, b: u32) -> u32 {
+    unsafe {
+        let ptr = intrinsics::const_allocate(8, 4) as *mut [u32; 2];
+        *ptr = [a, b];
+        let sum = (*ptr)[0] + (*ptr)[1];
+        intrinsics::const_deallocate(ptr as *mut u8, 8, 4);
+        sum
+    }
+}
+
+const SUM: u32 = sum_with_scratch(20, 22);
+
+fn main() {
+    assert_eq!(SUM, 42);
+    // At runtime there is no compile-time heap.
+    assert!(unsafe { intrinsics::const_allocate(4, 4) }.is_null());
+}
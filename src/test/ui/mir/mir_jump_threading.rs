@@ -0,0 +1,80 @@
+// run-pass
+// compile-flags: -Z mir-opt-level=2
+
+// Checks that jump threading preserves the behavior of code that switches on values set to known
+// constants in a predecessor block.
+
+enum Flow<B, C> {
+    Continue(C),
+    Break(B),
+}
+
+fn flow(x: Result<i32, usize>) -> Flow<usize, i32> {
+    match x {
+        Ok(v) => Flow::Continue(v),
+        Err(r) => Flow::Break(r),
+    }
+}
+
+fn chained(x: Result<i32, usize>) -> Option<i32> {
+    match flow(x) {
+        Flow::Continue(v) => Some(v),
+        Flow::Break(_) => None,
+    }
+}
+
+fn nested_match(x: Result<i32, usize>) -> Option<i32> {
+    let f = match x {
+        Ok(v) => Flow::Continue(v + 1),
+        Err(r) => Flow::Break(r),
+    };
+    match f {
+        Flow::Continue(v) => Some(v),
+        Flow::Break(_) => None,
+    }
+}
+
+fn try_chain(a: Option<u8>, b: Option<u8>) -> Option<u16> {
+    let a = a?;
+    let b = b?;
+    Some(a as u16 + b as u16)
+}
+
+fn bools(x: u32) -> u32 {
+    let mut small = false;
+    if x < 10 {
+        small = true;
+    }
+    if small { x } else { x * 2 }
+}
+
+fn reassigned(x: u32) -> u32 {
+    let mut state = 0;
+    if x == 1 {
+        state = 1;
+    }
+    if x == 2 {
+        state = 2;
+    }
+    match state {
+        0 => 10,
+        1 => 11,
+        _ => 12,
+    }
+}
+
+fn main() {
+    assert_eq!(chained(Ok(3)), Some(3));
+    assert_eq!(chained(Err(1)), None);
+    assert_eq!(nested_match(Ok(3)), Some(4));
+    assert_eq!(nested_match(Err(1)), None);
+    assert_eq!(try_chain(Some(1), Some(2)), Some(3));
+    assert_eq!(try_chain(None, Some(2)), None);
+    assert_eq!(try_chain(Some(1), None), None);
+    assert!(matches!(flow(Ok(0)), Flow::Continue(0)));
+    assert_eq!(bools(3), 3);
+    assert_eq!(bools(30), 60);
+    assert_eq!(reassigned(0), 10);
+    assert_eq!(reassigned(1), 11);
+    assert_eq!(reassigned(2), 12);
+}
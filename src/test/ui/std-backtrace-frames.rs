@@ -0,0 +1,53 @@
+// run-pass
+// ignore-android FIXME #17520
+// ignore-cloudabi no backtrace support
+// ignore-emscripten no backtrace support
+// ignore-openbsd no support for libbacktrace without filename
+// ignore-sgx no backtrace support
+// ignore-msvc see #62897 and `backtrace-debuginfo.rs` test
+// compile-flags:-g
+
+#![feature(backtrace, backtrace_frames)]
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+
+#[inline(never)]
+fn capture() -> Backtrace {
+    Backtrace::force_capture()
+}
+
+fn main() {
+    let disabled = {
+        std::env::set_var("RUST_LIB_BACKTRACE", "0");
+        Backtrace::capture()
+    };
+    assert_eq!(disabled.status(), BacktraceStatus::Disabled);
+    assert_eq!(disabled.frames().count(), 0);
+
+    let bt = capture();
+    assert_eq!(bt.status(), BacktraceStatus::Captured);
+
+    // Frames aren't resolved until asked for.
+    let mut frames: Vec<_> = bt.frames().collect();
+    assert!(!frames.is_empty());
+    assert!(frames.iter().all(|f| !f.is_resolved() && f.symbols().is_empty()));
+
+    // Resolving a single frame doesn't touch the backtrace it came from.
+    frames[0].resolve();
+    assert!(frames[0].is_resolved());
+    assert!(bt.frames().all(|f| !f.is_resolved()));
+
+    bt.resolve();
+    let frames: Vec<_> = bt.frames().collect();
+    assert!(frames.iter().all(|f| f.is_resolved()));
+
+    let symbol = frames
+        .iter()
+        .flat_map(|f| f.symbols())
+        .find(|s| s.name().map_or(false, |n| n.ends_with("::capture") && !n.contains("Backtrace")))
+        .expect("no symbol for `capture`");
+    assert!(symbol.name_raw().is_some());
+    let file = symbol.filename().expect("no filename for `capture`");
+    assert!(file.ends_with("std-backtrace-frames.rs"), "{:?}", file);
+    assert!(symbol.lineno().is_some());
+}
@@ -234,13 +234,14 @@ function load_files(out_folder, crate) {
     var arraysToLoad = ["itemTypes"];
     var variablesToLoad = ["MAX_LEV_DISTANCE", "MAX_RESULTS",
                            "GENERICS_DATA", "NAME", "INPUTS_DATA", "OUTPUT_DATA",
-                           "TY_PRIMITIVE", "TY_KEYWORD",
-                           "levenshtein_row2"];
+                           "TY_PRIMITIVE", "TY_KEYWORD", "FULL_TEXT_SEPARATOR",
+                           "fullTextIndex", "levenshtein_row2"];
     // execQuery first parameter is built in getQuery (which takes in the search input).
     // execQuery last parameter is built in buildIndex.
     // buildIndex requires the hashmap from search-index.
     var functionsToLoad = ["buildHrefAndPath", "pathSplitter", "levenshtein", "validateResult",
-                           "getQuery", "buildIndex", "execQuery", "execSearch"];
+                           "getQuery", "buildIndex", "buildFullTextEntries", "execQuery",
+                           "execSearch"];
 
     finalJS += 'window = { "currentCrate": "' + crate + '" };\n';
     finalJS += 'var rootPath = "../";\n';
@@ -250,7 +251,21 @@ function load_files(out_folder, crate) {
     finalJS += loadThings(functionsToLoad, 'function', extractFunction, mainJs);
 
     var loaded = loadContent(finalJS);
-    return [loaded, loaded.buildIndex(searchIndex.searchIndex)];
+    var index = loaded.buildIndex(searchIndex.searchIndex);
+
+    // The full-text indexes of the crates documented with `--full-text-search` are in files of
+    // their own, which the search page only loads when a search is made.
+    for (var fullTextCrate in loaded.fullTextIndex) {
+        if (!loaded.fullTextIndex.hasOwnProperty(fullTextCrate)) {
+            continue;
+        }
+        var crateIndex = loaded.fullTextIndex[fullTextCrate];
+        var fullText = loadContent(
+            'function addFullTextIndex(crate, entries) { exports.entries = entries; }\n' +
+            readFile(out_folder + "/search-fulltext/" + fullTextCrate + ".js"));
+        crateIndex.entries = loaded.buildFullTextEntries(crateIndex.start, fullText.entries);
+    }
+    return [loaded, index];
 }
 
 function main(argv) {